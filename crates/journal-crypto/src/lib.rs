@@ -0,0 +1,129 @@
+//! Client-side encryption for `crud-backend` journal entries.
+//!
+//! Entry content is encrypted with XChaCha20-Poly1305 before it is sent, so
+//! the chain only ever sees the scheme id, nonce and ciphertext. The key is
+//! derived with HKDF-SHA256 from the owner's wallet signature over
+//! [`key_message`]. Ed25519 signatures are deterministic, so the same wallet
+//! gets the same key back on any device and no key has to be stored.
+
+use std::fmt;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+
+/// Scheme id stored on-chain for XChaCha20-Poly1305 with a signature-derived
+/// key. Matches `crud_backend::ENCRYPTION_SCHEME_XCHACHA20_POLY1305`.
+pub const SCHEME_XCHACHA20_POLY1305: u8 = 1;
+
+pub const NONCE_LEN: usize = 24;
+
+/// Bytes the authentication tag adds to every ciphertext.
+pub const TAG_LEN: usize = 16;
+
+const HKDF_SALT: &[u8] = b"crud-journal";
+const HKDF_INFO: &[u8] = b"entry encryption key v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The signature is not the owner's signature over [`key_message`].
+    WrongSignature,
+    /// The entry was sealed with a scheme this crate doesn't know.
+    UnknownScheme(u8),
+    /// Wrong key, or the nonce or ciphertext was changed.
+    Decrypt,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::WrongSignature => {
+                write!(f, "signature is not the owner's key message signature")
+            }
+            Error::UnknownScheme(scheme) => write!(f, "unknown encryption scheme {scheme}"),
+            Error::Decrypt => write!(f, "entry does not decrypt with this key"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Encrypted entry content, field for field what `crud_backend::EncryptedContent`
+/// stores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub scheme: u8,
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+/// The message a wallet signs to unlock its entries. It names the owner, so
+/// a signature can't be replayed to derive another wallet's key.
+pub fn key_message(owner: &Pubkey) -> Vec<u8> {
+    format!("Unlock my encrypted crud-journal entries\nowner: {owner}").into_bytes()
+}
+
+/// An owner's entry key.
+pub struct EntryKey {
+    cipher: XChaCha20Poly1305,
+}
+
+impl EntryKey {
+    /// Derives the key from `signature`, which must be `owner`'s signature
+    /// over [`key_message`]. Wallets return it from `signMessage`.
+    pub fn from_signature(owner: &Pubkey, signature: &Signature) -> Result<Self, Error> {
+        if !signature.verify(owner.as_ref(), &key_message(owner)) {
+            return Err(Error::WrongSignature);
+        }
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(HKDF_SALT), signature.as_ref())
+            .expand(HKDF_INFO, &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Ok(Self {
+            cipher: XChaCha20Poly1305::new(&key.into()),
+        })
+    }
+
+    /// Signs [`key_message`] with `keypair` and derives its key.
+    pub fn from_keypair(keypair: &Keypair) -> Self {
+        let owner = keypair.pubkey();
+        let signature = keypair.sign_message(&key_message(&owner));
+        Self::from_signature(&owner, &signature).expect("a keypair's own signature verifies")
+    }
+
+    /// Encrypts `plaintext` under a fresh random nonce.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Sealed {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        self.encrypt_with_nonce(nonce.into(), plaintext)
+    }
+
+    /// Encrypts `plaintext` under `nonce`, which must never be used twice
+    /// with the same key. Prefer [`EntryKey::encrypt`].
+    pub fn encrypt_with_nonce(&self, nonce: [u8; NONCE_LEN], plaintext: &[u8]) -> Sealed {
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .expect("XChaCha20-Poly1305 encrypts any length that fits in memory");
+        Sealed {
+            scheme: SCHEME_XCHACHA20_POLY1305,
+            nonce,
+            ciphertext,
+        }
+    }
+
+    pub fn decrypt(&self, sealed: &Sealed) -> Result<Vec<u8>, Error> {
+        if sealed.scheme != SCHEME_XCHACHA20_POLY1305 {
+            return Err(Error::UnknownScheme(sealed.scheme));
+        }
+        self.cipher
+            .decrypt(
+                XNonce::from_slice(&sealed.nonce),
+                sealed.ciphertext.as_slice(),
+            )
+            .map_err(|_| Error::Decrypt)
+    }
+}
@@ -0,0 +1,87 @@
+use journal_crypto::{key_message, EntryKey, Error, SCHEME_XCHACHA20_POLY1305, TAG_LEN};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn round_trips_content_of_any_length() {
+    let key = EntryKey::from_keypair(&Keypair::new());
+    for plaintext in [
+        Vec::new(),
+        b"Dear diary".to_vec(),
+        "Ünïcödé, emoji 🦀 and all".as_bytes().to_vec(),
+        vec![b'x'; 1000],
+    ] {
+        let sealed = key.encrypt(&plaintext);
+        assert_eq!(sealed.scheme, SCHEME_XCHACHA20_POLY1305);
+        assert_eq!(sealed.ciphertext.len(), plaintext.len() + TAG_LEN);
+        assert_eq!(key.decrypt(&sealed).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn every_encryption_gets_a_fresh_nonce() {
+    let key = EntryKey::from_keypair(&Keypair::new());
+    let first = key.encrypt(b"same text");
+    let second = key.encrypt(b"same text");
+    assert_ne!(first.nonce, second.nonce);
+    assert_ne!(first.ciphertext, second.ciphertext);
+}
+
+#[test]
+fn the_same_wallet_derives_the_same_key() {
+    let wallet = Keypair::new();
+    let sealed = EntryKey::from_keypair(&wallet).encrypt(b"written on my laptop");
+
+    // E.g. on another device: the wallet signs the message again
+    let signature = wallet.sign_message(&key_message(&wallet.pubkey()));
+    let key = EntryKey::from_signature(&wallet.pubkey(), &signature).unwrap();
+    assert_eq!(key.decrypt(&sealed).unwrap(), b"written on my laptop");
+}
+
+#[test]
+fn other_wallets_cannot_decrypt() {
+    let sealed = EntryKey::from_keypair(&Keypair::new()).encrypt(b"private");
+    let stranger = EntryKey::from_keypair(&Keypair::new());
+    assert_eq!(stranger.decrypt(&sealed), Err(Error::Decrypt));
+}
+
+#[test]
+fn only_the_owners_key_message_signature_derives_a_key() {
+    let wallet = Keypair::new();
+    let other = Keypair::new();
+
+    let wrong_message = wallet.sign_message(b"gm");
+    assert!(matches!(
+        EntryKey::from_signature(&wallet.pubkey(), &wrong_message),
+        Err(Error::WrongSignature)
+    ));
+
+    // Someone else's signature, even over this owner's message
+    let wrong_signer = other.sign_message(&key_message(&wallet.pubkey()));
+    assert!(matches!(
+        EntryKey::from_signature(&wallet.pubkey(), &wrong_signer),
+        Err(Error::WrongSignature)
+    ));
+}
+
+#[test]
+fn tampering_is_detected() {
+    let key = EntryKey::from_keypair(&Keypair::new());
+    let sealed = key.encrypt(b"pay Alice 5 SOL");
+
+    let mut ciphertext = sealed.clone();
+    ciphertext.ciphertext[0] ^= 1;
+    assert_eq!(key.decrypt(&ciphertext), Err(Error::Decrypt));
+
+    let mut nonce = sealed.clone();
+    nonce.nonce[0] ^= 1;
+    assert_eq!(key.decrypt(&nonce), Err(Error::Decrypt));
+
+    let mut truncated = sealed.clone();
+    truncated.ciphertext.truncate(TAG_LEN - 1);
+    assert_eq!(key.decrypt(&truncated), Err(Error::Decrypt));
+
+    let mut scheme = sealed;
+    scheme.scheme = 2;
+    assert_eq!(key.decrypt(&scheme), Err(Error::UnknownScheme(2)));
+}
@@ -0,0 +1,89 @@
+//! Fetches payloads from an Arweave or IPFS HTTP gateway.
+//!
+//! Requests go through `ureq`, so both a local `http://` gateway and a public
+//! `https://` one work. The digest check makes the gateway's answer safe to
+//! use either way.
+
+use std::io::Read;
+use std::time::Duration;
+
+use crate::{ContentSource, ContentUri, Error};
+
+/// Largest payload read by default before giving up, so a misbehaving
+/// gateway can't stream an endless body into memory.
+pub const MAX_CONTENT_BYTES: u64 = 64 * 1024 * 1024;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serves `ar://<id>` from `GET /<id>` and `ipfs://<cid>/<path>` from
+/// `GET /ipfs/<cid>/<path>`, the paths Arweave and IPFS gateways use.
+pub struct HttpGateway {
+    agent: ureq::Agent,
+    base: String,
+    max_content_bytes: u64,
+}
+
+impl HttpGateway {
+    /// `url` must be an `http://` or `https://` URL with no path, e.g.
+    /// `http://127.0.0.1:8080` or `https://arweave.net`.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        let base = url.trim_end_matches('/');
+        let valid = base
+            .strip_prefix("http://")
+            .or_else(|| base.strip_prefix("https://"))
+            .is_some_and(|host| !host.is_empty() && !host.contains('/'));
+        if !valid {
+            return Err(Error::Fetch(format!(
+                "unsupported gateway URL {url}: expected http(s)://host[:port]"
+            )));
+        }
+        Ok(HttpGateway {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            base: base.to_string(),
+            max_content_bytes: MAX_CONTENT_BYTES,
+        })
+    }
+
+    /// Caps payloads at `limit` bytes instead of [`MAX_CONTENT_BYTES`].
+    pub fn with_max_content_bytes(mut self, limit: u64) -> Self {
+        self.max_content_bytes = limit;
+        self
+    }
+
+    fn get(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let response = match self.agent.get(&format!("{}{path}", self.base)).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(Error::Fetch(format!(
+                    "gateway error: {status} {}",
+                    response.status_text()
+                )))
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(Error::Fetch(format!("gateway unreachable: {transport}")))
+            }
+        };
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(self.max_content_bytes + 1)
+            .read_to_end(&mut body)?;
+        if body.len() as u64 > self.max_content_bytes {
+            return Err(Error::Fetch(format!(
+                "gateway payload is larger than {} bytes",
+                self.max_content_bytes
+            )));
+        }
+        Ok(body)
+    }
+}
+
+impl ContentSource for HttpGateway {
+    fn fetch(&self, uri: &ContentUri) -> Result<Vec<u8>, Error> {
+        match uri {
+            ContentUri::Arweave(id) => self.get(&format!("/{id}")),
+            ContentUri::Ipfs(path) => self.get(&format!("/ipfs/{path}")),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Off-chain content for `crud-backend` journal entries.
+//!
+//! An off-chain entry stores only a content URI (`ar://<id>` or
+//! `ipfs://<cid>[/<path>]`) and the SHA-256 of the payload. [`verify`] gets
+//! the payload from a [`ContentSource`] and checks it against that digest, so
+//! a gateway or mirror can serve it without being trusted. Sources are a
+//! local directory ([`LocalStore`]) and an HTTP(S) gateway
+//! ([`HttpGateway`]).
+
+pub mod gateway;
+pub mod local;
+
+use std::fmt;
+use std::io;
+
+use sha2::{Digest, Sha256};
+
+pub use gateway::HttpGateway;
+pub use local::LocalStore;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Not an `ar://` or `ipfs://` URI, or nothing follows the scheme.
+    UnsupportedUri(String),
+    /// The source couldn't serve the payload.
+    Fetch(String),
+    /// The payload isn't the content the entry committed to.
+    DigestMismatch {
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnsupportedUri(uri) => write!(f, "unsupported content URI {uri}"),
+            Error::Fetch(message) => f.write_str(message),
+            Error::DigestMismatch { expected, actual } => write!(
+                f,
+                "content digest is {}, the entry expects {}",
+                hex(actual),
+                hex(expected)
+            ),
+            Error::Io(error) => write!(f, "reading content failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+/// A parsed content URI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentUri<'a> {
+    /// `ar://<transaction id>`
+    Arweave(&'a str),
+    /// `ipfs://<cid>` with an optional `/<path>` inside a directory
+    Ipfs(&'a str),
+}
+
+impl<'a> ContentUri<'a> {
+    pub fn parse(uri: &'a str) -> Result<Self, Error> {
+        let parsed = if let Some(id) = uri.strip_prefix("ar://") {
+            ContentUri::Arweave(id)
+        } else if let Some(path) = uri.strip_prefix("ipfs://") {
+            ContentUri::Ipfs(path)
+        } else {
+            return Err(Error::UnsupportedUri(uri.to_string()));
+        };
+        let rest = parsed.path();
+        // Nothing after the scheme, or an attempt to climb out of it
+        if rest.is_empty() || rest.split('/').any(|part| part.is_empty() || part == "..") {
+            return Err(Error::UnsupportedUri(uri.to_string()));
+        }
+        Ok(parsed)
+    }
+
+    /// Everything after the scheme.
+    pub fn path(&self) -> &'a str {
+        match self {
+            ContentUri::Arweave(path) | ContentUri::Ipfs(path) => path,
+        }
+    }
+}
+
+/// Where payloads are read from.
+pub trait ContentSource {
+    fn fetch(&self, uri: &ContentUri) -> Result<Vec<u8>, Error>;
+}
+
+/// The digest an off-chain entry stores for `payload`.
+pub fn digest(payload: &[u8]) -> [u8; 32] {
+    Sha256::digest(payload).into()
+}
+
+/// Fetches the payload at `uri` and returns it if it matches `sha256`.
+pub fn verify(source: &impl ContentSource, uri: &str, sha256: &[u8; 32]) -> Result<Vec<u8>, Error> {
+    let payload = source.fetch(&ContentUri::parse(uri)?)?;
+    let actual = digest(&payload);
+    if actual != *sha256 {
+        return Err(Error::DigestMismatch {
+            expected: *sha256,
+            actual,
+        });
+    }
+    Ok(payload)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,41 @@
+//! A directory standing in for remote storage: mirrors, backups and tests.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ContentSource, ContentUri, Error};
+
+/// Keeps `ar://<id>` at `<root>/ar/<id>` and `ipfs://<cid>/<path>` at
+/// `<root>/ipfs/<cid>/<path>`.
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalStore { root: root.into() }
+    }
+
+    pub fn path_of(&self, uri: &ContentUri) -> PathBuf {
+        let (scheme, path) = match uri {
+            ContentUri::Arweave(path) => ("ar", path),
+            ContentUri::Ipfs(path) => ("ipfs", path),
+        };
+        path.split('/')
+            .fold(self.root.join(scheme), |dir, part| dir.join(part))
+    }
+
+    /// Stores `payload` under `uri`, e.g. to mirror an upload.
+    pub fn put(&self, uri: &str, payload: &[u8]) -> Result<PathBuf, Error> {
+        let path = self.path_of(&ContentUri::parse(uri)?);
+        fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+        fs::write(&path, payload)?;
+        Ok(path)
+    }
+}
+
+impl ContentSource for LocalStore {
+    fn fetch(&self, uri: &ContentUri) -> Result<Vec<u8>, Error> {
+        Ok(fs::read(self.path_of(uri))?)
+    }
+}
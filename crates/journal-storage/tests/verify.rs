@@ -0,0 +1,169 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+
+use journal_storage::{digest, verify, ContentUri, Error, HttpGateway, LocalStore};
+
+/// A fresh directory under the system temp dir.
+fn temp_store(name: &str) -> (LocalStore, PathBuf) {
+    let root = std::env::temp_dir().join(format!("journal-storage-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    (LocalStore::new(&root), root)
+}
+
+#[test]
+fn parses_arweave_and_ipfs_uris() {
+    assert_eq!(
+        ContentUri::parse("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U").unwrap(),
+        ContentUri::Arweave("bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U")
+    );
+    assert_eq!(
+        ContentUri::parse("ipfs://bafybeigdyrzt/entries/1.md").unwrap(),
+        ContentUri::Ipfs("bafybeigdyrzt/entries/1.md")
+    );
+    for uri in [
+        "https://example.com/entry",
+        "ar://",
+        "ipfs://cid//double",
+        "ipfs://cid/../../etc/passwd",
+    ] {
+        assert!(
+            matches!(ContentUri::parse(uri), Err(Error::UnsupportedUri(_))),
+            "{uri}"
+        );
+    }
+}
+
+#[test]
+fn verifies_local_copies_against_the_digest() {
+    let (store, root) = temp_store("local");
+    let payload = "A very long entry ".repeat(10_000).into_bytes();
+    let path = store.put("ar://entry-1", &payload).unwrap();
+    assert_eq!(path, root.join("ar").join("entry-1"));
+    store.put("ipfs://bafy/notes/2.md", b"second").unwrap();
+
+    assert_eq!(
+        verify(&store, "ar://entry-1", &digest(&payload)).unwrap(),
+        payload
+    );
+    assert_eq!(
+        verify(&store, "ipfs://bafy/notes/2.md", &digest(b"second")).unwrap(),
+        b"second"
+    );
+
+    // A changed copy no longer matches what the entry committed to
+    fs::write(&path, b"tampered").unwrap();
+    match verify(&store, "ar://entry-1", &digest(&payload)) {
+        Err(Error::DigestMismatch { expected, actual }) => {
+            assert_eq!(expected, digest(&payload));
+            assert_eq!(actual, digest(b"tampered"));
+        }
+        other => panic!("expected a digest mismatch, got {other:?}"),
+    }
+
+    assert!(matches!(
+        verify(&store, "ar://missing", &digest(b"")),
+        Err(Error::Io(_))
+    ));
+    fs::remove_dir_all(root).unwrap();
+}
+
+/// Serves `body` for `expected_path` and 404 for anything else.
+fn serve(expected_path: &'static str, body: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            respond(stream.unwrap(), expected_path, body);
+        }
+    });
+    url
+}
+
+fn respond(mut stream: TcpStream, expected_path: &str, body: &[u8]) {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    reader.read_line(&mut request_line).unwrap();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    if path == expected_path {
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+    } else {
+        write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn fetches_from_a_gateway() {
+    let arweave = HttpGateway::new(&serve("/tx-1", b"from arweave")).unwrap();
+    assert_eq!(
+        verify(&arweave, "ar://tx-1", &digest(b"from arweave")).unwrap(),
+        b"from arweave"
+    );
+    assert!(matches!(
+        verify(&arweave, "ar://tx-2", &digest(b"")),
+        Err(Error::Fetch(_))
+    ));
+
+    let ipfs = HttpGateway::new(&serve("/ipfs/bafy/1.md", b"from ipfs")).unwrap();
+    assert_eq!(
+        verify(&ipfs, "ipfs://bafy/1.md", &digest(b"from ipfs")).unwrap(),
+        b"from ipfs"
+    );
+
+    // The gateway isn't trusted: a wrong answer fails the digest check
+    let liar = HttpGateway::new(&serve("/tx-1", b"something else")).unwrap();
+    assert!(matches!(
+        verify(&liar, "ar://tx-1", &digest(b"from arweave")),
+        Err(Error::DigestMismatch { .. })
+    ));
+}
+
+#[test]
+fn rejects_gateway_errors_and_oversized_payloads() {
+    let url = serve("/tx-1", b"twelve bytes");
+    match verify(&HttpGateway::new(&url).unwrap(), "ar://tx-2", &digest(b"")) {
+        Err(Error::Fetch(message)) => assert!(message.contains("404"), "{message}"),
+        other => panic!("expected a gateway error, got {other:?}"),
+    }
+
+    // A payload at the cap is read; one byte over is refused before hashing
+    let exact = HttpGateway::new(&url).unwrap().with_max_content_bytes(12);
+    assert_eq!(
+        verify(&exact, "ar://tx-1", &digest(b"twelve bytes")).unwrap(),
+        b"twelve bytes"
+    );
+    let small = HttpGateway::new(&url).unwrap().with_max_content_bytes(11);
+    match verify(&small, "ar://tx-1", &digest(b"twelve bytes")) {
+        Err(Error::Fetch(message)) => assert!(message.contains("larger than 11"), "{message}"),
+        other => panic!("expected an oversized payload error, got {other:?}"),
+    }
+}
+
+#[test]
+fn gateway_urls_are_http_or_https_hosts() {
+    assert!(HttpGateway::new("http://127.0.0.1:8080/").is_ok());
+    assert!(HttpGateway::new("https://arweave.net").is_ok());
+    assert!(HttpGateway::new("ftp://arweave.net").is_err());
+    assert!(HttpGateway::new("http://").is_err());
+    assert!(HttpGateway::new("https://").is_err());
+    assert!(HttpGateway::new("http://gateway/ipfs").is_err());
+}
@@ -0,0 +1,441 @@
+//! Synchronous `solana-program-test` harness for the workspace programs.
+//!
+//! Each program is deployed through the upgradeable loader. When
+//! `target/deploy/<name>.so` exists (after `anchor build`) the bank runs that
+//! SBF build, metered like on a cluster. Otherwise the program is linked into
+//! the test binary and run through `solana-program-test`'s native shim, so a
+//! plain `cargo test` needs neither the Solana toolchain nor a validator.
+//!
+//! SPL Token, Token-2022 and the associated token account program are the
+//! SBF builds `solana-program-test` ships with.
+
+mod stubs;
+mod token;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{Mutex, OnceLock};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use solana_program_test::{processor, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::clock::{Clock, Slot, UnixTimestamp};
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use tokio::runtime::Runtime;
+
+/// A program's entrypoint, e.g. `swap::entry`.
+pub type Processor = for<'a> fn(&Pubkey, &'a [AccountInfo<'a>], &[u8]) -> ProgramResult;
+
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Tag, slot and optional upgrade authority ahead of the ELF in a
+/// program-data account.
+const PROGRAM_DATA_METADATA_LEN: usize = 45;
+
+/// Fee charged to the fee payer for each signature, as on mainnet.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// A program to deploy through the upgradeable loader.
+pub struct UpgradeableProgram {
+    /// File stem of the SBF build, e.g. `swap` for `target/deploy/swap.so`.
+    pub name: &'static str,
+    pub program_id: Pubkey,
+    /// Native entrypoint, used when there is no SBF build.
+    pub processor: Processor,
+    pub upgrade_authority: Option<Pubkey>,
+}
+
+/// A program call, top-level (depth 1) or CPI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub program_id: Pubkey,
+    pub depth: usize,
+}
+
+/// What a successful transaction left behind.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionMetadata {
+    pub logs: Vec<String>,
+    /// Fields of every `sol_log_data` call, e.g. Anchor `emit!` payloads.
+    pub events: Vec<Vec<Vec<u8>>>,
+    pub return_data: Option<(Pubkey, Vec<u8>)>,
+    /// Every program invocation, top-level and CPI, in call order.
+    pub invocations: Vec<Invocation>,
+    /// Only meaningful for SBF builds; the native shim charges one unit per
+    /// invocation.
+    pub compute_units_consumed: u64,
+    pub fee: u64,
+}
+
+/// A rejected transaction. Only the fee was charged.
+#[derive(Debug, Clone)]
+pub struct FailedTransaction {
+    pub error: TransactionError,
+    pub logs: Vec<String>,
+}
+
+impl fmt::Display for FailedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "transaction failed: {}", self.error)?;
+        for line in &self.logs {
+            writeln!(f, "  {line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FailedTransaction {}
+
+pub struct LocalSvm {
+    runtime: Runtime,
+    context: ProgramTestContext,
+    rent: Rent,
+    /// Signatures already sent. The bank rejects a second copy under the
+    /// same blockhash, so resending one waits for a fresh blockhash.
+    sent: HashSet<Signature>,
+    native_programs: Vec<Pubkey>,
+}
+
+/// Native entrypoints by program id. `processor!` needs a plain function, so
+/// the shim dispatches through here.
+fn native_processors() -> &'static Mutex<HashMap<Pubkey, Processor>> {
+    static PROCESSORS: OnceLock<Mutex<HashMap<Pubkey, Processor>>> = OnceLock::new();
+    PROCESSORS.get_or_init(Default::default)
+}
+
+fn process_natively(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let processor = native_processors().lock().unwrap()[program_id];
+    // Builtins are owned by the native loader. Show the program its own
+    // account as the upgradeable loader would have left it, so `Program<T>`
+    // and `programdata_address()` checks behave as on a cluster. The account
+    // is readonly, so the shim never writes the stand-in back; it must not
+    // be passed on to a CPI, where the shim would see it differ.
+    let accounts = accounts
+        .iter()
+        .map(|account| {
+            if account.key != program_id {
+                return account.clone();
+            }
+            AccountInfo {
+                owner: &BPF_LOADER_UPGRADEABLE_ID,
+                data: Rc::new(RefCell::new(Vec::leak(program_account_data(program_id)))),
+                ..account.clone()
+            }
+        })
+        .collect();
+    // Anchor entrypoints tie the slice to the `AccountInfo` lifetime. The
+    // shim drops the accounts after the call, so leaking the handles only
+    // keeps the `Rc`s alive.
+    processor(program_id, Vec::leak(accounts), data)
+}
+
+/// `target/deploy/<name>.so` at the workspace root, if it has been built.
+fn sbf_build(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../target/deploy")
+        .join(format!("{name}.so"));
+    path.exists().then_some(path)
+}
+
+fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+/// An upgradeable program account: its tag and program-data address.
+fn program_account_data(program_id: &Pubkey) -> Vec<u8> {
+    let mut data = vec![2, 0, 0, 0];
+    data.extend_from_slice(program_data_address(program_id).as_ref());
+    data
+}
+
+/// The program and program-data accounts the upgradeable loader would
+/// leave behind for `elf`.
+fn upgradeable_accounts(
+    program: &UpgradeableProgram,
+    elf: &[u8],
+    rent: &Rent,
+) -> [(Pubkey, Account); 2] {
+    let program_account = program_account_data(&program.program_id);
+    let program_data = program_data_address(&program.program_id);
+    let mut program_data_account = vec![3, 0, 0, 0];
+    program_data_account.extend_from_slice(&0u64.to_le_bytes());
+    match program.upgrade_authority {
+        Some(authority) => {
+            program_data_account.push(1);
+            program_data_account.extend_from_slice(authority.as_ref());
+        }
+        None => program_data_account.push(0),
+    }
+    program_data_account.resize(PROGRAM_DATA_METADATA_LEN, 0);
+    program_data_account.extend_from_slice(elf);
+
+    let account = |data: Vec<u8>, executable| Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable,
+        rent_epoch: 0,
+    };
+    [
+        (program.program_id, account(program_account, true)),
+        (program_data, account(program_data_account, false)),
+    ]
+}
+
+impl LocalSvm {
+    pub fn new(programs: &[UpgradeableProgram]) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let rent = Rent::default();
+
+        let mut program_test = ProgramTest::default();
+        // `ProgramTest` turns on debug logging for every invocation. Keep
+        // test output to warnings, minus tarpc's per-request one, unless
+        // `RUST_LOG` asks for more.
+        solana_logger::setup_with_default("warn,tarpc=error");
+        program_test.prefer_bpf(false);
+        let mut native_programs = Vec::new();
+        for program in programs {
+            match sbf_build(program.name) {
+                Some(path) => {
+                    let elf = std::fs::read(&path)
+                        .unwrap_or_else(|error| panic!("reading {}: {error}", path.display()));
+                    for (address, account) in upgradeable_accounts(program, &elf, &rent) {
+                        program_test.add_account(address, account);
+                    }
+                }
+                None => {
+                    native_processors()
+                        .lock()
+                        .unwrap()
+                        .insert(program.program_id, program.processor);
+                    program_test.add_program(
+                        program.name,
+                        program.program_id,
+                        processor!(process_natively),
+                    );
+                    let [_, (program_data, account)] = upgradeable_accounts(program, &[], &rent);
+                    native_programs.push(program.program_id);
+                    program_test.add_account(program_data, account);
+                }
+            }
+        }
+
+        let context = runtime.block_on(program_test.start_with_context());
+        stubs::install();
+        LocalSvm {
+            runtime,
+            context,
+            rent,
+            sent: HashSet::new(),
+            native_programs,
+        }
+    }
+
+    /// Whether `program_id` runs through the native shim rather than from
+    /// an SBF build.
+    pub fn is_native(&self, program_id: &Pubkey) -> bool {
+        self.native_programs.contains(program_id)
+    }
+
+    /// Funded keypair that pays for the helper transactions.
+    pub fn payer(&self) -> &Keypair {
+        &self.context.payer
+    }
+
+    pub fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        self.runtime
+            .block_on(self.context.banks_client.get_account(*address))
+            .unwrap()
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.get_account(address)
+            .map_or(0, |account| account.lamports)
+    }
+
+    /// Credits `lamports`, creating a system account if needed.
+    pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        let mut account = self.get_account(address).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*address, account);
+    }
+
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        self.rent.minimum_balance(data_len)
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.runtime
+            .block_on(self.context.banks_client.get_sysvar::<Clock>())
+            .unwrap()
+    }
+
+    /// Moves the bank forward to `slot`, which must be later than the
+    /// current one.
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        self.context.warp_to_slot(slot).unwrap();
+    }
+
+    pub fn set_unix_timestamp(&mut self, unix_timestamp: UnixTimestamp) {
+        let clock = Clock {
+            unix_timestamp,
+            ..self.clock()
+        };
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Executes `instructions` atomically. `signers[0]` pays the fee, which
+    /// is charged even if an instruction fails. Signers the instructions
+    /// don't ask for are ignored.
+    pub fn send_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let fail = |error| FailedTransaction {
+            error,
+            logs: Vec::new(),
+        };
+
+        let fee_payer = signers
+            .first()
+            .ok_or(fail(TransactionError::MissingSignatureForFee))?;
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&fee_payer.pubkey()));
+        let message = &transaction.message;
+        let required = &message.account_keys[..message.header.num_required_signatures as usize];
+        let mut keypairs: Vec<&Keypair> = Vec::new();
+        for key in required {
+            let keypair = signers
+                .iter()
+                .find(|signer| signer.pubkey() == *key)
+                .ok_or(fail(TransactionError::SignatureFailure))?;
+            keypairs.push(keypair);
+        }
+
+        // Sign with the bank's latest blockhash so long-lived harnesses never
+        // hit an expired one, and move to a fresh one for a resend
+        let banks_client = &mut self.context.banks_client;
+        let blockhash = self
+            .runtime
+            .block_on(banks_client.get_latest_blockhash())
+            .unwrap();
+        transaction.sign(&keypairs, blockhash);
+        if self.sent.contains(&transaction.signatures[0]) {
+            let blockhash = self
+                .runtime
+                .block_on(banks_client.get_new_latest_blockhash(&blockhash))
+                .unwrap();
+            self.sent.clear();
+            transaction.sign(&keypairs, blockhash);
+        }
+        self.sent.insert(transaction.signatures[0]);
+
+        let fee = self
+            .runtime
+            .block_on(banks_client.get_fee_for_message(transaction.message.clone()))
+            .unwrap()
+            .unwrap_or_default();
+        let outcome = self
+            .runtime
+            .block_on(banks_client.process_transaction_with_metadata(transaction))
+            .unwrap();
+        let metadata = match (outcome.result, outcome.metadata) {
+            (Ok(()), Some(metadata)) => metadata,
+            (Ok(()), None) => unreachable!("executed transactions have metadata"),
+            (Err(error), metadata) => {
+                return Err(FailedTransaction {
+                    error,
+                    logs: metadata
+                        .map(|metadata| self.logs(metadata.log_messages))
+                        .unwrap_or_default(),
+                })
+            }
+        };
+        let logs = self.logs(metadata.log_messages);
+
+        Ok(TransactionMetadata {
+            events: events(&logs),
+            invocations: invocations(&logs),
+            logs,
+            return_data: metadata
+                .return_data
+                .map(|return_data| (return_data.program_id, return_data.data)),
+            compute_units_consumed: metadata.compute_units_consumed,
+            fee,
+        })
+    }
+
+    /// Logs as an SBF build writes them. The native shim and its CPI stub
+    /// each log an invoke line, the stub's at the caller's depth, and each
+    /// log the result; only the innermost of each is kept. Native
+    /// `sol_log_data` lines are rewritten as well.
+    fn logs(&self, lines: Vec<String>) -> Vec<String> {
+        if self.native_programs.is_empty() {
+            return lines;
+        }
+        let mut logs: Vec<String> = Vec::with_capacity(lines.len());
+        for mut line in lines {
+            if line.starts_with(stubs::NATIVE_DATA_PREFIX) {
+                line.replace_range(.."Program log: ".len(), "");
+            }
+            let repeated = logs.last().is_some_and(|last| {
+                let (Some(outer), Some(inner)) = (invoke(last), invoke(&line)) else {
+                    return *last == line
+                        && (line.ends_with(" success") || line.contains(" failed: "));
+                };
+                outer.program_id == inner.program_id
+                    && (inner.depth == outer.depth || inner.depth == outer.depth + 1)
+            });
+            if repeated {
+                logs.pop();
+            }
+            logs.push(line);
+        }
+        logs
+    }
+}
+
+/// Reads a `Program <id> invoke [<depth>]` line.
+fn invoke(line: &str) -> Option<Invocation> {
+    let (program_id, depth) = line.strip_prefix("Program ")?.split_once(" invoke [")?;
+    Some(Invocation {
+        program_id: program_id.parse().ok()?,
+        depth: depth.strip_suffix(']')?.parse().ok()?,
+    })
+}
+
+fn invocations(logs: &[String]) -> Vec<Invocation> {
+    logs.iter().filter_map(|line| invoke(line)).collect()
+}
+
+/// Decodes the `Program data:` lines `sol_log_data` writes.
+fn events(logs: &[String]) -> Vec<Vec<Vec<u8>>> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .map(|fields| {
+            fields
+                .split(' ')
+                .filter_map(|field| BASE64_STANDARD.decode(field).ok())
+                .collect()
+        })
+        .collect()
+}
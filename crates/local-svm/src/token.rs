@@ -0,0 +1,96 @@
+//! Token fixtures. Each helper sends real SPL instructions paid for by
+//! [`LocalSvm::payer`] and panics if the transaction fails.
+
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_system_interface::instruction::create_account;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::instruction::{initialize_mint2, mint_to};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+
+use crate::LocalSvm;
+
+impl LocalSvm {
+    /// Creates a mint without extensions under `token_program`, which may be
+    /// SPL Token or Token-2022.
+    pub fn create_mint(
+        &mut self,
+        token_program: &Pubkey,
+        authority: &Pubkey,
+        decimals: u8,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer().insecure_clone();
+        let instructions = [
+            create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                self.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                token_program,
+            ),
+            initialize_mint2(token_program, &mint.pubkey(), authority, None, decimals).unwrap(),
+        ];
+        self.send_transaction(&instructions, &[&payer, &mint])
+            .unwrap_or_else(|failure| panic!("create_mint: {failure}"));
+        mint.pubkey()
+    }
+
+    /// Creates `owner`'s associated token account for `mint` if it does not
+    /// exist yet and returns its address.
+    pub fn create_associated_token_account(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        let payer = self.payer().insecure_clone();
+        let instruction =
+            create_associated_token_account_idempotent(&payer.pubkey(), owner, mint, token_program);
+        self.send_transaction(&[instruction], &[&payer])
+            .unwrap_or_else(|failure| panic!("create_associated_token_account: {failure}"));
+        get_associated_token_address_with_program_id(owner, mint, token_program)
+    }
+
+    pub fn mint_to(
+        &mut self,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+        mint_authority: &Keypair,
+        destination: &Pubkey,
+        amount: u64,
+    ) {
+        let payer = self.payer().insecure_clone();
+        let instruction = mint_to(
+            token_program,
+            mint,
+            destination,
+            &mint_authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send_transaction(&[instruction], &[&payer, mint_authority])
+            .unwrap_or_else(|failure| panic!("mint_to: {failure}"));
+    }
+
+    /// Amount held by a token account of either token program, or `None` if
+    /// the address is not an initialized token account.
+    pub fn token_balance(&self, address: &Pubkey) -> Option<u64> {
+        let account = self.get_account(address)?;
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .ok()
+            .map(|state| state.base.amount)
+    }
+
+    pub fn mint_supply(&self, mint: &Pubkey) -> Option<u64> {
+        let account = self.get_account(mint)?;
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .ok()
+            .map(|state| state.base.supply)
+    }
+}
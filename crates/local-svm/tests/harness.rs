@@ -0,0 +1,128 @@
+//! What the harness adds on top of `solana-program-test`, exercised with
+//! small native programs defined here.
+
+use local_svm::{Invocation, LocalSvm, UpgradeableProgram};
+use solana_program::log::sol_log_data;
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::program::{invoke, set_return_data};
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_system_interface::instruction::transfer;
+
+const PROBE_ID: Pubkey = pubkey!("Probe11111111111111111111111111111111111111");
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Returns the program-data address its own program account points at and
+/// logs it as an event.
+fn probe<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>], _: &[u8]) -> ProgramResult {
+    let program = &accounts[0];
+    if program.key != program_id || *program.owner != BPF_LOADER_UPGRADEABLE_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let program_data = &program.data.borrow()[4..36];
+    set_return_data(program_data);
+    sol_log_data(&[b"probe", program_data]);
+    Ok(())
+}
+
+/// Asks the system program to move one lamport from the first account to
+/// the second.
+fn forward<'a>(_: &Pubkey, accounts: &'a [AccountInfo<'a>], _: &[u8]) -> ProgramResult {
+    invoke(
+        &transfer(accounts[0].key, accounts[1].key, 1),
+        &accounts[..2],
+    )
+}
+
+fn svm(processor: local_svm::Processor, upgrade_authority: Option<Pubkey>) -> LocalSvm {
+    LocalSvm::new(&[UpgradeableProgram {
+        name: "probe",
+        program_id: PROBE_ID,
+        processor,
+        upgrade_authority,
+    }])
+}
+
+#[test]
+fn native_programs_look_deployed() {
+    let authority = Pubkey::new_unique();
+    let mut svm = svm(probe, Some(authority));
+    assert!(svm.is_native(&PROBE_ID));
+    let (program_data, _) =
+        Pubkey::find_program_address(&[PROBE_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+
+    let payer = svm.payer().insecure_clone();
+    let instruction = Instruction::new_with_bytes(
+        PROBE_ID,
+        &[],
+        vec![AccountMeta::new_readonly(PROBE_ID, false)],
+    );
+    let meta = svm.send_transaction(&[instruction], &[&payer]).unwrap();
+    assert_eq!(
+        meta.return_data,
+        Some((PROBE_ID, program_data.to_bytes().to_vec()))
+    );
+    assert_eq!(
+        meta.events,
+        vec![vec![b"probe".to_vec(), program_data.to_bytes().to_vec()]]
+    );
+
+    // The program-data account carries the upgrade authority
+    let account = svm.get_account(&program_data).unwrap();
+    assert_eq!(account.owner, BPF_LOADER_UPGRADEABLE_ID);
+    assert_eq!(
+        &account.data[12..45],
+        [&[1][..], authority.as_ref()].concat()
+    );
+}
+
+#[test]
+fn native_invocations_are_reported_once() {
+    let mut svm = svm(forward, None);
+    let alice = Keypair::new();
+    let bob = Pubkey::new_unique();
+    svm.airdrop(&alice.pubkey(), LAMPORTS_PER_SOL);
+    svm.airdrop(&bob, LAMPORTS_PER_SOL);
+
+    let instruction = Instruction::new_with_bytes(
+        PROBE_ID,
+        &[],
+        vec![
+            AccountMeta::new(alice.pubkey(), true),
+            AccountMeta::new(bob, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+        ],
+    );
+    let meta = svm
+        .send_transaction(std::slice::from_ref(&instruction), &[&alice])
+        .unwrap();
+    assert_eq!(
+        meta.invocations,
+        [
+            Invocation {
+                program_id: PROBE_ID,
+                depth: 1
+            },
+            Invocation {
+                program_id: solana_system_interface::program::ID,
+                depth: 2
+            },
+        ]
+    );
+    assert_eq!(meta.fee, local_svm::LAMPORTS_PER_SIGNATURE);
+
+    // An identical transaction runs again instead of being dropped as a
+    // duplicate
+    svm.send_transaction(&[instruction], &[&alice]).unwrap();
+    assert_eq!(svm.lamports(&bob), LAMPORTS_PER_SOL + 2);
+    assert_eq!(
+        svm.lamports(&alice.pubkey()),
+        LAMPORTS_PER_SOL - 2 - 2 * local_svm::LAMPORTS_PER_SIGNATURE
+    );
+}
@@ -0,0 +1,148 @@
+//! Joins offers with their vault balances and mint decimals.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+use solana_sdk::pubkey::Pubkey;
+use swap_client::filters::OfferFilters;
+use swap_client::pda::find_associated_token_address;
+use swap_client::state::{Offer, SwapAccount};
+
+use crate::rpc::{RpcAccount, RpcClient};
+use crate::Error;
+
+/// Offset of `decimals` in an SPL mint, shared by Token and Token-2022.
+const MINT_DECIMALS_OFFSET: usize = 44;
+/// Offset of `amount` in an SPL token account, shared by Token and
+/// Token-2022.
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// An open offer with what is needed to price it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookEntry {
+    pub address: Pubkey,
+    pub offer: Offer,
+    /// Token A left in the vault, in base units.
+    pub token_a_remaining: u64,
+    pub decimals_a: u8,
+    pub decimals_b: u8,
+}
+
+impl BookEntry {
+    /// Token B asked per whole token A, adjusted for decimals. `None` when
+    /// the vault is empty.
+    pub fn price(&self) -> Option<f64> {
+        if self.token_a_remaining == 0 {
+            return None;
+        }
+        let token_b = ui_amount(self.offer.token_b_wanted_amount, self.decimals_b);
+        let token_a = ui_amount(self.token_a_remaining, self.decimals_a);
+        Some(token_b / token_a)
+    }
+}
+
+pub fn ui_amount(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals.into())
+}
+
+/// Fetches every offer matching `filters`, sorted by market and then by
+/// price, cheapest first.
+pub fn fetch(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    filters: &OfferFilters,
+) -> Result<Vec<BookEntry>, Error> {
+    let mut offers = Vec::new();
+    for (address, account) in rpc.get_program_accounts(program_id, &filters.build())? {
+        let offer = Offer::decode(&account.data)
+            .map_err(|error| Error::Rpc(format!("offer {address}: {error}")))?;
+        offers.push((address, offer));
+    }
+
+    let mints: Vec<Pubkey> = offers
+        .iter()
+        .flat_map(|(_, offer)| [offer.token_mint_a, offer.token_mint_b])
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let mint_accounts = lookup(rpc, &mints)?;
+
+    let mut vaults = Vec::with_capacity(offers.len());
+    for (address, offer) in &offers {
+        let mint_a = mint(&mint_accounts, &offer.token_mint_a)?;
+        vaults.push(find_associated_token_address(
+            address,
+            &offer.token_mint_a,
+            &mint_a.owner,
+        ));
+    }
+    let vault_accounts = lookup(rpc, &vaults)?;
+
+    let mut book = Vec::with_capacity(offers.len());
+    for ((address, offer), vault) in offers.into_iter().zip(vaults) {
+        let token_a_remaining = match vault_accounts.get(&vault) {
+            Some(account) => read_u64(&account.data, TOKEN_ACCOUNT_AMOUNT_OFFSET)
+                .ok_or_else(|| Error::Rpc(format!("vault {vault} is not a token account")))?,
+            None => 0,
+        };
+        book.push(BookEntry {
+            address,
+            token_a_remaining,
+            decimals_a: decimals(&mint_accounts, &offer.token_mint_a)?,
+            decimals_b: decimals(&mint_accounts, &offer.token_mint_b)?,
+            offer,
+        });
+    }
+    sort(&mut book);
+    Ok(book)
+}
+
+/// Groups offers by mint pair, then orders each market by price with empty
+/// vaults last. Ties keep a stable order by address.
+pub fn sort(book: &mut [BookEntry]) {
+    book.sort_by(|left, right| {
+        (left.offer.token_mint_a, left.offer.token_mint_b)
+            .cmp(&(right.offer.token_mint_a, right.offer.token_mint_b))
+            .then_with(|| match (left.price(), right.price()) {
+                (Some(left), Some(right)) => left.total_cmp(&right),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| left.address.cmp(&right.address))
+    });
+}
+
+fn lookup(
+    rpc: &RpcClient,
+    addresses: &[Pubkey],
+) -> Result<HashMap<Pubkey, RpcAccount>, Error> {
+    let accounts = rpc.get_multiple_accounts(addresses)?;
+    Ok(addresses
+        .iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| Some((*address, account?)))
+        .collect())
+}
+
+fn mint<'a>(
+    mint_accounts: &'a HashMap<Pubkey, RpcAccount>,
+    address: &Pubkey,
+) -> Result<&'a RpcAccount, Error> {
+    mint_accounts
+        .get(address)
+        .ok_or_else(|| Error::Rpc(format!("mint {address} not found")))
+}
+
+fn decimals(mint_accounts: &HashMap<Pubkey, RpcAccount>, address: &Pubkey) -> Result<u8, Error> {
+    mint(mint_accounts, address)?
+        .data
+        .get(MINT_DECIMALS_OFFSET)
+        .copied()
+        .ok_or_else(|| Error::Rpc(format!("{address} is not a mint")))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
@@ -0,0 +1,144 @@
+//! Snapshot of the open offers of the `swap` program.
+//!
+//! Lists every `Offer` account from an RPC endpoint, optionally narrowed by
+//! maker and mints, joins each with its vault balance and mint decimals and
+//! prints the book as a table, JSON or CSV. The `offer-book` binary is a
+//! thin wrapper around [`run`].
+
+pub mod book;
+pub mod output;
+pub mod rpc;
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+use swap_client::filters::OfferFilters;
+
+use crate::output::Format;
+use crate::rpc::RpcClient;
+
+/// A local test validator's RPC port.
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+
+pub const USAGE: &str = "\
+Usage: offer-book [OPTIONS]
+
+Lists the open offers of the swap program.
+
+Options:
+  --url <URL>            RPC endpoint, http:// or https:// [default: http://127.0.0.1:8899]
+  --program-id <PUBKEY>  swap program id [default: the deployed id]
+  --maker <PUBKEY>       only offers by this maker
+  --mint-a <PUBKEY>      only offers selling this mint
+  --mint-b <PUBKEY>      only offers asking for this mint
+  --format <FORMAT>      table, json or csv [default: table]
+  -h, --help             print this help
+";
+
+#[derive(Debug)]
+pub enum Error {
+    /// Bad command-line arguments.
+    Usage(String),
+    /// The endpoint returned an error or data that isn't what we expected.
+    Rpc(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) | Error::Rpc(message) => f.write_str(message),
+            Error::Io(error) => write!(f, "RPC connection failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub url: String,
+    pub program_id: Pubkey,
+    pub maker: Option<Pubkey>,
+    pub token_mint_a: Option<Pubkey>,
+    pub token_mint_b: Option<Pubkey>,
+    pub format: Format,
+    pub help: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            url: DEFAULT_URL.to_string(),
+            program_id: swap_client::ID,
+            maker: None,
+            token_mint_a: None,
+            token_mint_b: None,
+            format: Format::default(),
+            help: false,
+        }
+    }
+}
+
+impl Args {
+    /// Parses the arguments after the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            if flag == "-h" || flag == "--help" {
+                parsed.help = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| Error::Usage(format!("{flag} needs a value")))?;
+            match flag.as_str() {
+                "--url" => parsed.url = value,
+                "--program-id" => parsed.program_id = parse_pubkey(&flag, &value)?,
+                "--maker" => parsed.maker = Some(parse_pubkey(&flag, &value)?),
+                "--mint-a" => parsed.token_mint_a = Some(parse_pubkey(&flag, &value)?),
+                "--mint-b" => parsed.token_mint_b = Some(parse_pubkey(&flag, &value)?),
+                "--format" => parsed.format = value.parse()?,
+                _ => return Err(Error::Usage(format!("unknown option {flag}"))),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn filters(&self) -> OfferFilters {
+        let mut filters = OfferFilters::new();
+        if let Some(maker) = self.maker {
+            filters = filters.maker(maker);
+        }
+        if let Some(mint) = self.token_mint_a {
+            filters = filters.token_mint_a(mint);
+        }
+        if let Some(mint) = self.token_mint_b {
+            filters = filters.token_mint_b(mint);
+        }
+        filters
+    }
+}
+
+fn parse_pubkey(flag: &str, value: &str) -> Result<Pubkey, Error> {
+    Pubkey::from_str(value).map_err(|_| Error::Usage(format!("{flag}: {value} is not a pubkey")))
+}
+
+/// Fetches the book described by `args` and renders it.
+pub fn run(args: &Args) -> Result<String, Error> {
+    if args.help {
+        return Ok(USAGE.to_string());
+    }
+    let rpc = RpcClient::new(&args.url)?;
+    let book = book::fetch(&rpc, &args.program_id, &args.filters())?;
+    Ok(output::render(&book, args.format))
+}
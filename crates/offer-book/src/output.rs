@@ -0,0 +1,226 @@
+//! Renders the offer book as an aligned table, JSON or CSV.
+
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use swap_client::state::{OFFER_FLAG_ALL_OR_NONE, OFFER_FLAG_POST_ONLY};
+
+use crate::book::BookEntry;
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        match text {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(Error::Usage(format!(
+                "unknown format {text}: expected table, json or csv"
+            ))),
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 14] = [
+    "offer",
+    "maker",
+    "id",
+    "token_mint_a",
+    "token_mint_b",
+    "token_a_remaining",
+    "token_b_wanted",
+    "decimals_a",
+    "decimals_b",
+    "price",
+    "flags",
+    "min_fill_amount",
+    "vesting_cliff_seconds",
+    "vesting_duration_seconds",
+];
+
+const TABLE_HEADER: [&str; 9] = [
+    "Offer", "Maker", "Id", "Mint A", "Mint B", "Offered A", "Wanted B", "Price", "Terms",
+];
+
+pub fn render(book: &[BookEntry], format: Format) -> String {
+    match format {
+        Format::Table => table(book),
+        Format::Json => json(book),
+        Format::Csv => csv(book),
+    }
+}
+
+/// `amount` in whole tokens, printed exactly.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{digits:0>width$}", width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    format!("{whole}.{fraction}")
+}
+
+fn format_price(entry: &BookEntry) -> String {
+    entry
+        .price()
+        .map_or_else(String::new, |price| format!("{price:.6}"))
+}
+
+/// Flags, minimum fill and vesting schedule, e.g.
+/// `all-or-none, min 1.000000, vests 100s/1000s`.
+fn terms(entry: &BookEntry) -> String {
+    let offer = &entry.offer;
+    let mut terms = Vec::new();
+    if offer.flags & OFFER_FLAG_ALL_OR_NONE != 0 {
+        terms.push("all-or-none".to_string());
+    }
+    if offer.flags & OFFER_FLAG_POST_ONLY != 0 {
+        terms.push("post-only".to_string());
+    }
+    if offer.min_fill_amount > 0 {
+        terms.push(format!(
+            "min {}",
+            format_amount(offer.min_fill_amount, entry.decimals_a)
+        ));
+    }
+    if offer.vests() {
+        terms.push(format!(
+            "vests {}s/{}s",
+            offer.vesting_cliff_seconds, offer.vesting_duration_seconds
+        ));
+    }
+    if offer.needs_migration() {
+        terms.push("legacy layout".to_string());
+    }
+    terms.join(", ")
+}
+
+/// First and last four characters of an address, enough to tell entries
+/// apart on screen.
+fn short(address: &Pubkey) -> String {
+    let text = address.to_string();
+    format!("{}..{}", &text[..4], &text[text.len() - 4..])
+}
+
+fn table(book: &[BookEntry]) -> String {
+    let rows: Vec<[String; 9]> = book
+        .iter()
+        .map(|entry| {
+            let offer = &entry.offer;
+            [
+                short(&entry.address),
+                short(&offer.maker),
+                offer.id.to_string(),
+                short(&offer.token_mint_a),
+                short(&offer.token_mint_b),
+                format_amount(entry.token_a_remaining, entry.decimals_a),
+                format_amount(offer.token_b_wanted_amount, entry.decimals_b),
+                format_price(entry),
+                terms(entry),
+            ]
+        })
+        .collect();
+
+    let mut widths = TABLE_HEADER.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut output = String::new();
+    let mut write_row = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Amounts and prices line up on the right.
+                if (2..8).contains(&column) {
+                    format!("{cell:>width$}")
+                } else {
+                    format!("{cell:<width$}")
+                }
+            })
+            .collect();
+        writeln!(output, "{}", line.join("  ").trim_end()).unwrap();
+    };
+    write_row(&TABLE_HEADER);
+    for row in &rows {
+        write_row(&row.each_ref().map(String::as_str));
+    }
+    writeln!(output, "{} open offers", book.len()).unwrap();
+    output
+}
+
+fn json(book: &[BookEntry]) -> String {
+    let entries: Vec<Value> = book
+        .iter()
+        .map(|entry| {
+            let offer = &entry.offer;
+            json!({
+                "offer": entry.address.to_string(),
+                "maker": offer.maker.to_string(),
+                "id": offer.id,
+                "tokenMintA": offer.token_mint_a.to_string(),
+                "tokenMintB": offer.token_mint_b.to_string(),
+                "tokenARemaining": entry.token_a_remaining,
+                "tokenBWanted": offer.token_b_wanted_amount,
+                "decimalsA": entry.decimals_a,
+                "decimalsB": entry.decimals_b,
+                "price": entry.price(),
+                "flags": offer.flags,
+                "minFillAmount": offer.min_fill_amount,
+                "vestingCliffSeconds": offer.vesting_cliff_seconds,
+                "vestingDurationSeconds": offer.vesting_duration_seconds,
+                "version": offer.version,
+            })
+        })
+        .collect();
+    let mut output = serde_json::to_string_pretty(&entries).unwrap();
+    output.push('\n');
+    output
+}
+
+fn csv(book: &[BookEntry]) -> String {
+    let mut output = CSV_HEADER.join(",");
+    output.push('\n');
+    for entry in book {
+        let offer = &entry.offer;
+        // Every field is a number or a base58 address, so nothing needs
+        // quoting.
+        let fields = [
+            entry.address.to_string(),
+            offer.maker.to_string(),
+            offer.id.to_string(),
+            offer.token_mint_a.to_string(),
+            offer.token_mint_b.to_string(),
+            entry.token_a_remaining.to_string(),
+            offer.token_b_wanted_amount.to_string(),
+            entry.decimals_a.to_string(),
+            entry.decimals_b.to_string(),
+            entry.price().map_or_else(String::new, |price| price.to_string()),
+            offer.flags.to_string(),
+            offer.min_fill_amount.to_string(),
+            offer.vesting_cliff_seconds.to_string(),
+            offer.vesting_duration_seconds.to_string(),
+        ];
+        output.push_str(&fields.join(","));
+        output.push('\n');
+    }
+    output
+}
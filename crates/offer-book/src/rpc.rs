@@ -0,0 +1,196 @@
+//! Minimal JSON-RPC client for the two read calls the offer book needs.
+//!
+//! Requests go through `ureq`, so both a local `http://` validator and a
+//! remote `https://` node work.
+
+use std::io::Read;
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use swap_client::filters::AccountFilter;
+
+use crate::Error;
+
+/// `getMultipleAccounts` accepts at most this many addresses per call.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Largest response body read before giving up. `getProgramAccounts` for the
+/// whole book is the big one; this leaves room for a few hundred thousand
+/// offers.
+pub const MAX_RESPONSE_BYTES: u64 = 256 * 1024 * 1024;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The parts of an account the offer book reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcAccount {
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+pub struct RpcClient {
+    agent: ureq::Agent,
+    url: String,
+}
+
+impl RpcClient {
+    /// `url` must be an `http://` or `https://` URL, e.g.
+    /// `http://127.0.0.1:8899`.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        let host = url
+            .strip_prefix("http://")
+            .or_else(|| url.strip_prefix("https://"))
+            .ok_or_else(|| {
+                Error::Usage(format!(
+                    "unsupported RPC URL {url}: expected http:// or https://"
+                ))
+            })?;
+        if host.is_empty() || host.starts_with('/') {
+            return Err(Error::Usage(format!("RPC URL {url} has no host")));
+        }
+        Ok(RpcClient {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            url: url.to_string(),
+        })
+    }
+
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Result<Vec<(Pubkey, RpcAccount)>, Error> {
+        let filters: Vec<Value> = filters
+            .iter()
+            .map(|filter| match filter {
+                AccountFilter::DataSize(size) => json!({ "dataSize": size }),
+                AccountFilter::Memcmp { offset, bytes } => json!({
+                    "memcmp": {
+                        "offset": offset,
+                        "bytes": BASE64_STANDARD.encode(bytes),
+                        "encoding": "base64",
+                    }
+                }),
+            })
+            .collect();
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                program_id.to_string(),
+                { "encoding": "base64", "commitment": "confirmed", "filters": filters },
+            ]),
+        )?;
+
+        let entries = result
+            .as_array()
+            .ok_or_else(|| malformed("getProgramAccounts result is not an array"))?;
+        entries
+            .iter()
+            .map(|entry| {
+                let address = parse_pubkey(&entry["pubkey"])?;
+                Ok((address, parse_account(&entry["account"])?))
+            })
+            .collect()
+    }
+
+    /// Looks up `addresses` in batches of [`MAX_MULTIPLE_ACCOUNTS`]. Missing
+    /// accounts come back as `None`, in the same order.
+    pub fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<RpcAccount>>, Error> {
+        let mut accounts = Vec::with_capacity(addresses.len());
+        for batch in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<String> = batch.iter().map(Pubkey::to_string).collect();
+            let result = self.call(
+                "getMultipleAccounts",
+                json!([keys, { "encoding": "base64", "commitment": "confirmed" }]),
+            )?;
+            let values = result["value"]
+                .as_array()
+                .filter(|values| values.len() == batch.len())
+                .ok_or_else(|| malformed("getMultipleAccounts returned the wrong number of accounts"))?;
+            for value in values {
+                accounts.push(match value {
+                    Value::Null => None,
+                    account => Some(parse_account(account)?),
+                });
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, Error> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+            .to_string();
+        let response = self.post(&body)?;
+        let mut response: Value = serde_json::from_slice(&response)
+            .map_err(|error| malformed(&format!("{method} response is not JSON: {error}")))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(Error::Rpc(format!("{method} failed: {error}")));
+        }
+        match response.get_mut("result") {
+            Some(result) => Ok(result.take()),
+            None => Err(malformed(&format!("{method} response has no result"))),
+        }
+    }
+
+    fn post(&self, body: &str) -> Result<Vec<u8>, Error> {
+        let response = match self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(body)
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(Error::Rpc(format!(
+                    "HTTP error: {status} {}",
+                    response.status_text()
+                )))
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(Error::Io(std::io::Error::other(transport)))
+            }
+        };
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_RESPONSE_BYTES + 1)
+            .read_to_end(&mut body)?;
+        if body.len() as u64 > MAX_RESPONSE_BYTES {
+            return Err(Error::Rpc(format!(
+                "RPC response is larger than {MAX_RESPONSE_BYTES} bytes"
+            )));
+        }
+        Ok(body)
+    }
+}
+
+fn parse_pubkey(value: &Value) -> Result<Pubkey, Error> {
+    value
+        .as_str()
+        .and_then(|text| Pubkey::from_str(text).ok())
+        .ok_or_else(|| malformed(&format!("expected a pubkey, got {value}")))
+}
+
+/// Parses an account encoded as `{"owner": ..., "data": [<base64>, "base64"]}`.
+fn parse_account(value: &Value) -> Result<RpcAccount, Error> {
+    let data = value["data"][0]
+        .as_str()
+        .ok_or_else(|| malformed("account data is not base64"))?;
+    Ok(RpcAccount {
+        owner: parse_pubkey(&value["owner"])?,
+        data: BASE64_STANDARD
+            .decode(data)
+            .map_err(|_| malformed("account data is not base64"))?,
+    })
+}
+
+fn malformed(message: &str) -> Error {
+    Error::Rpc(format!("malformed RPC response: {message}"))
+}
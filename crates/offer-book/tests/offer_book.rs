@@ -0,0 +1,392 @@
+//! Runs the offer book against a stub JSON-RPC server that serves
+//! program-written offers, mints and vaults.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use anchor_lang::AccountSerialize;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use offer_book::output::{format_amount, Format};
+use offer_book::rpc::RpcClient;
+use offer_book::{run, Args, Error};
+use serde_json::{json, Value};
+use solana_sdk::program_option::COption;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use swap_client::pda::{find_associated_token_address, find_offer_address};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+struct Fixture {
+    accounts: HashMap<Pubkey, (Pubkey, Vec<u8>)>,
+    alice: Pubkey,
+    bob: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    mint_c: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut fixture = Fixture {
+            accounts: HashMap::new(),
+            alice: Pubkey::new_unique(),
+            bob: Pubkey::new_unique(),
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+            mint_c: Pubkey::new_unique(),
+        };
+        fixture.add_mint(fixture.mint_a, TOKEN_PROGRAM_ID, 6);
+        fixture.add_mint(fixture.mint_b, TOKEN_PROGRAM_ID, 9);
+        fixture.add_mint(fixture.mint_c, TOKEN_2022_PROGRAM_ID, 0);
+
+        let (alice, bob) = (fixture.alice, fixture.bob);
+        let (mint_a, mint_b, mint_c) = (fixture.mint_a, fixture.mint_b, fixture.mint_c);
+        // 1 A for 2.5 B.
+        fixture.add_offer(alice, 1, mint_a, mint_b, 1_000_000, 2_500_000_000, |_| {});
+        // 2 A for 3 B, so the better price.
+        fixture.add_offer(alice, 2, mint_a, mint_b, 2_000_000, 3_000_000_000, |offer| {
+            offer.flags = swap::OFFER_FLAG_ALL_OR_NONE;
+            offer.vesting_cliff_seconds = 60;
+            offer.vesting_duration_seconds = 600;
+        });
+        // 5 C for 0.5 A, in a Token-2022 vault.
+        fixture.add_offer(bob, 7, mint_c, mint_a, 5, 500_000, |offer| {
+            offer.min_fill_amount = 2;
+        });
+        fixture
+    }
+
+    fn add_mint(&mut self, address: Pubkey, token_program: Pubkey, decimals: u8) {
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        self.accounts.insert(address, (token_program, data));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_offer(
+        &mut self,
+        maker: Pubkey,
+        id: u64,
+        token_mint_a: Pubkey,
+        token_mint_b: Pubkey,
+        token_a_remaining: u64,
+        token_b_wanted_amount: u64,
+        customize: impl FnOnce(&mut swap::Offer),
+    ) {
+        let (address, bump) = find_offer_address(&maker, id);
+        let mut offer = swap::Offer {
+            id,
+            maker,
+            token_mint_a,
+            token_mint_b,
+            token_b_wanted_amount,
+            bump,
+            version: swap::OFFER_VERSION,
+            flags: 0,
+            min_fill_amount: 0,
+            created_slot: 10,
+            vesting_cliff_seconds: 0,
+            vesting_duration_seconds: 0,
+            nonce: 1,
+            rent_payer: maker,
+            reserved: [0; swap::OFFER_RESERVED_SPACE],
+        };
+        customize(&mut offer);
+        let mut data = Vec::new();
+        offer.try_serialize(&mut data).unwrap();
+        self.accounts.insert(address, (swap::ID, data));
+
+        let token_program = self.accounts[&token_mint_a].0;
+        let vault = spl_token::state::Account {
+            mint: token_mint_a,
+            owner: address,
+            amount: token_a_remaining,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        vault.pack_into_slice(&mut data);
+        self.accounts.insert(
+            find_associated_token_address(&address, &token_mint_a, &token_program),
+            (token_program, data),
+        );
+    }
+
+    /// Starts the stub server and returns its URL. It serves until the test
+    /// process exits, one request per connection.
+    fn serve(self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                self.respond(stream.unwrap());
+            }
+        });
+        url
+    }
+
+    fn respond(&self, stream: TcpStream) {
+        let mut reader = BufReader::new(stream);
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let request: Value = serde_json::from_slice(&body).unwrap();
+
+        let result = match request["method"].as_str().unwrap() {
+            "getProgramAccounts" => self.program_accounts(&request["params"]),
+            "getMultipleAccounts" => self.multiple_accounts(&request["params"]),
+            method => panic!("unexpected method {method}"),
+        };
+        let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+        let response = response.to_string();
+
+        let mut stream = reader.into_inner();
+        if request["method"] == "getProgramAccounts" {
+            // Large responses from a validator come back chunked.
+            let (first, second) = response.split_at(response.len() / 2);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n\
+                 {:x}\r\n{first}\r\n{:x}\r\n{second}\r\n0\r\n\r\n",
+                first.len(),
+                second.len(),
+            )
+            .unwrap();
+        } else {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len(),
+            )
+            .unwrap();
+        }
+    }
+
+    fn program_accounts(&self, params: &Value) -> Value {
+        let program_id: Pubkey = params[0].as_str().unwrap().parse().unwrap();
+        let filters = params[1]["filters"].as_array().unwrap();
+        let matches = |data: &[u8]| {
+            filters.iter().all(|filter| {
+                let memcmp = &filter["memcmp"];
+                assert_eq!(memcmp["encoding"], "base64");
+                let offset = memcmp["offset"].as_u64().unwrap() as usize;
+                let bytes = BASE64_STANDARD
+                    .decode(memcmp["bytes"].as_str().unwrap())
+                    .unwrap();
+                data.get(offset..offset + bytes.len()) == Some(bytes.as_slice())
+            })
+        };
+        self.accounts
+            .iter()
+            .filter(|(_, (owner, data))| *owner == program_id && matches(data))
+            .map(|(address, account)| {
+                json!({ "pubkey": address.to_string(), "account": encode(account) })
+            })
+            .collect()
+    }
+
+    fn multiple_accounts(&self, params: &Value) -> Value {
+        let value: Vec<Value> = params[0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|key| {
+                let address: Pubkey = key.as_str().unwrap().parse().unwrap();
+                self.accounts.get(&address).map_or(Value::Null, encode)
+            })
+            .collect();
+        json!({ "context": { "slot": 1 }, "value": value })
+    }
+}
+
+fn encode((owner, data): &(Pubkey, Vec<u8>)) -> Value {
+    json!({
+        "owner": owner.to_string(),
+        "data": [BASE64_STANDARD.encode(data), "base64"],
+        "lamports": 1,
+        "executable": false,
+        "rentEpoch": 0,
+    })
+}
+
+fn args(url: &str, extra: &[&str]) -> Args {
+    let mut args = vec!["--url".to_string(), url.to_string()];
+    args.extend(extra.iter().map(|arg| arg.to_string()));
+    Args::parse(args).unwrap()
+}
+
+#[test]
+fn json_lists_offers_by_market_then_price() {
+    let fixture = Fixture::new();
+    let (alice, bob, mint_a, mint_b) = (fixture.alice, fixture.bob, fixture.mint_a, fixture.mint_b);
+    let url = fixture.serve();
+
+    let output = run(&args(&url, &["--format", "json"])).unwrap();
+    let book: Vec<Value> = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(book.len(), 3);
+    let market_ab: Vec<&Value> = book
+        .iter()
+        .filter(|entry| entry["tokenMintA"] == mint_a.to_string())
+        .collect();
+    assert_eq!(market_ab.len(), 2);
+    assert_eq!(market_ab[0]["id"], 2);
+    assert_eq!(market_ab[0]["maker"], alice.to_string());
+    assert_eq!(market_ab[0]["tokenMintB"], mint_b.to_string());
+    assert_eq!(market_ab[0]["tokenARemaining"], 2_000_000);
+    assert_eq!(market_ab[0]["tokenBWanted"], 3_000_000_000u64);
+    assert_eq!(market_ab[0]["decimalsA"], 6);
+    assert_eq!(market_ab[0]["decimalsB"], 9);
+    assert_eq!(market_ab[0]["price"], 1.5);
+    assert_eq!(market_ab[0]["flags"], swap::OFFER_FLAG_ALL_OR_NONE);
+    assert_eq!(market_ab[0]["vestingDurationSeconds"], 600);
+    assert_eq!(market_ab[1]["id"], 1);
+    assert_eq!(market_ab[1]["price"], 2.5);
+
+    let token_2022 = book
+        .iter()
+        .find(|entry| entry["maker"] == bob.to_string())
+        .unwrap();
+    assert_eq!(token_2022["tokenARemaining"], 5);
+    assert_eq!(token_2022["price"], 0.1);
+}
+
+#[test]
+fn filters_by_maker_and_mints() {
+    let fixture = Fixture::new();
+    let (alice, bob, mint_a, mint_c) = (fixture.alice, fixture.bob, fixture.mint_a, fixture.mint_c);
+    let url = fixture.serve();
+    let ids = |extra: &[&str]| -> Vec<u64> {
+        let output = run(&args(&url, &[&["--format", "json"], extra].concat())).unwrap();
+        let book: Vec<Value> = serde_json::from_str(&output).unwrap();
+        book.iter().map(|entry| entry["id"].as_u64().unwrap()).collect()
+    };
+
+    assert_eq!(ids(&["--maker", &alice.to_string()]), [2, 1]);
+    assert_eq!(ids(&["--maker", &bob.to_string()]), [7]);
+    assert_eq!(ids(&["--mint-a", &mint_c.to_string()]), [7]);
+    assert_eq!(ids(&["--mint-b", &mint_a.to_string()]), [7]);
+    assert_eq!(
+        ids(&["--maker", &bob.to_string(), "--mint-a", &mint_a.to_string()]),
+        Vec::<u64>::new()
+    );
+}
+
+#[test]
+fn csv_has_one_row_per_offer() {
+    let fixture = Fixture::new();
+    let bob = fixture.bob;
+    let url = fixture.serve();
+
+    let output = run(&args(&url, &["--format", "csv", "--maker", &bob.to_string()])).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "offer,maker,id,token_mint_a,token_mint_b,token_a_remaining,token_b_wanted,\
+         decimals_a,decimals_b,price,flags,min_fill_amount,vesting_cliff_seconds,\
+         vesting_duration_seconds"
+    );
+    assert_eq!(lines.len(), 2);
+    let fields: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(fields[0], find_offer_address(&bob, 7).0.to_string());
+    assert_eq!(&fields[2..3], ["7"]);
+    assert_eq!(&fields[5..], ["5", "500000", "0", "6", "0.1", "0", "2", "0", "0"]);
+}
+
+#[test]
+fn table_shows_whole_token_amounts_and_terms() {
+    let url = Fixture::new().serve();
+
+    let output = run(&args(&url, &[])).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert!(lines[0].starts_with("Offer"));
+    assert!(lines[0].ends_with("Terms"));
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[4], "3 open offers");
+    let vesting = lines.iter().find(|line| line.contains("vests")).unwrap();
+    assert!(vesting.contains("2.000000"));
+    assert!(vesting.contains("3.000000000"));
+    assert!(vesting.contains("1.500000"));
+    assert!(vesting.contains("all-or-none, vests 60s/600s"));
+    assert!(output.contains("min 2"));
+}
+
+#[test]
+fn amounts_are_printed_exactly() {
+    assert_eq!(format_amount(0, 6), "0.000000");
+    assert_eq!(format_amount(1, 6), "0.000001");
+    assert_eq!(format_amount(2_500_000, 6), "2.500000");
+    assert_eq!(format_amount(u64::MAX, 9), "18446744073.709551615");
+    assert_eq!(format_amount(42, 0), "42");
+}
+
+#[test]
+fn rejects_bad_arguments() {
+    let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+    assert_eq!(parse(&[]).unwrap(), Args::default());
+    assert_eq!(parse(&["--format", "csv"]).unwrap().format, Format::Csv);
+    assert!(parse(&["--help"]).unwrap().help);
+    assert!(matches!(parse(&["--format", "xml"]), Err(Error::Usage(_))));
+    assert!(matches!(parse(&["--maker", "not-a-key"]), Err(Error::Usage(_))));
+    assert!(matches!(parse(&["--maker"]), Err(Error::Usage(_))));
+    assert!(matches!(parse(&["--limit", "3"]), Err(Error::Usage(_))));
+    assert!(matches!(
+        run(&args("ftp://api.mainnet-beta.solana.com", &[])),
+        Err(Error::Usage(_))
+    ));
+    assert!(matches!(run(&args("https://", &[])), Err(Error::Usage(_))));
+}
+
+#[test]
+fn accepts_https_endpoints() {
+    assert!(RpcClient::new("https://api.mainnet-beta.solana.com").is_ok());
+    assert!(RpcClient::new("http://127.0.0.1:8899/rpc").is_ok());
+}
+
+#[test]
+fn reports_http_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            line.clear();
+        }
+        let mut stream = reader.into_inner();
+        write!(
+            stream,
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+        .unwrap();
+    });
+
+    let error = run(&args(&url, &[])).unwrap_err();
+
+    assert_eq!(error.to_string(), "HTTP error: 429 Too Many Requests");
+}
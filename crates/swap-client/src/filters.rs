@@ -0,0 +1,85 @@
+//! `getProgramAccounts` filters for `swap` accounts.
+//!
+//! [`AccountFilter`] mirrors the RPC's `memcmp` and `dataSize` filters so this
+//! crate doesn't need the RPC client; map it onto `RpcFilterType` or plain
+//! JSON at the call site.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::state::{Offer, SwapAccount, DISCRIMINATOR_LEN};
+
+pub const OFFER_MAKER_OFFSET: usize = DISCRIMINATOR_LEN + 8;
+pub const OFFER_TOKEN_MINT_A_OFFSET: usize = OFFER_MAKER_OFFSET + 32;
+pub const OFFER_TOKEN_MINT_B_OFFSET: usize = OFFER_TOKEN_MINT_A_OFFSET + 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountFilter {
+    DataSize(u64),
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+/// Selects offers, optionally narrowed by maker and mints.
+///
+/// No `dataSize` filter is added, so offers still in the legacy layout are
+/// returned too; they share the same leading fields.
+#[derive(Debug, Clone, Default)]
+pub struct OfferFilters {
+    maker: Option<Pubkey>,
+    token_mint_a: Option<Pubkey>,
+    token_mint_b: Option<Pubkey>,
+}
+
+impl OfferFilters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn maker(mut self, maker: Pubkey) -> Self {
+        self.maker = Some(maker);
+        self
+    }
+
+    pub fn token_mint_a(mut self, mint: Pubkey) -> Self {
+        self.token_mint_a = Some(mint);
+        self
+    }
+
+    pub fn token_mint_b(mut self, mint: Pubkey) -> Self {
+        self.token_mint_b = Some(mint);
+        self
+    }
+
+    pub fn build(&self) -> Vec<AccountFilter> {
+        let mut filters = vec![AccountFilter::Memcmp {
+            offset: 0,
+            bytes: Offer::discriminator().to_vec(),
+        }];
+
+        let fields = [
+            (OFFER_MAKER_OFFSET, self.maker),
+            (OFFER_TOKEN_MINT_A_OFFSET, self.token_mint_a),
+            (OFFER_TOKEN_MINT_B_OFFSET, self.token_mint_b),
+        ];
+        for (offset, key) in fields {
+            if let Some(key) = key {
+                filters.push(AccountFilter::Memcmp {
+                    offset,
+                    bytes: key.to_bytes().to_vec(),
+                });
+            }
+        }
+
+        filters
+    }
+
+    /// Applies the filters to already-fetched account data, for callers
+    /// that page through accounts themselves.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.build().iter().all(|filter| match filter {
+            AccountFilter::DataSize(size) => data.len() as u64 == *size,
+            AccountFilter::Memcmp { offset, bytes } => data
+                .get(*offset..*offset + bytes.len())
+                .is_some_and(|window| window == bytes.as_slice()),
+        })
+    }
+}
@@ -0,0 +1,458 @@
+//! Builders for every `swap` instruction.
+//!
+//! Account lists follow the order of the program's `#[derive(Accounts)]`
+//! structs. PDAs and token accounts are derived here, so callers only pass
+//! the wallets, mints and token program involved.
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::pda::{
+    find_associated_token_address, find_config_address, find_maker_stats_address,
+    find_offer_address, find_program_data_address, find_quote_address,
+    find_requester_stats_address, find_rfq_address, find_vesting_escrow_address,
+};
+use crate::state::{Offer, Quote, Rfq, VestingEscrow};
+use crate::{discriminator, ASSOCIATED_TOKEN_PROGRAM_ID, ID, SYSTEM_PROGRAM_ID};
+
+fn instruction_data(name: &str, args: &[&[u8]]) -> Vec<u8> {
+    let mut data = discriminator("global", name).to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+pub fn initialize(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    max_open_offers_per_maker: u32,
+    offer_deposit_lamports: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_address().0, false),
+            AccountMeta::new_readonly(ID, false),
+            AccountMeta::new_readonly(find_program_data_address(), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data(
+            "initialize",
+            &[
+                &max_open_offers_per_maker.to_le_bytes(),
+                &offer_deposit_lamports.to_le_bytes(),
+            ],
+        ),
+    }
+}
+
+pub fn update_config(
+    admin: &Pubkey,
+    max_open_offers_per_maker: u32,
+    offer_deposit_lamports: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_address().0, false),
+        ],
+        data: instruction_data(
+            "update_config",
+            &[
+                &max_open_offers_per_maker.to_le_bytes(),
+                &offer_deposit_lamports.to_le_bytes(),
+            ],
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MakeOfferArgs {
+    pub id: u64,
+    pub token_a_offered_amount: u64,
+    pub token_b_wanted_amount: u64,
+    /// Any of the `OFFER_FLAG_*` constants in [`crate::state`].
+    pub flags: u8,
+    pub min_fill_amount: u64,
+    /// Seconds after the take before the taker can claim any token A.
+    pub vesting_cliff_seconds: i64,
+    /// Zero delivers token A on take. Otherwise it vests linearly over this
+    /// many seconds, and must be at least the cliff.
+    pub vesting_duration_seconds: i64,
+}
+
+/// `payer` funds the new accounts and may be the maker or a sponsor. The
+/// maker always pays the offer deposit.
+pub fn make_offer(
+    payer: &Pubkey,
+    maker: &Pubkey,
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+    token_program: &Pubkey,
+    args: &MakeOfferArgs,
+) -> Instruction {
+    let offer = find_offer_address(maker, args.id).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*maker, true),
+            AccountMeta::new_readonly(*token_mint_a, false),
+            AccountMeta::new_readonly(*token_mint_b, false),
+            AccountMeta::new(
+                find_associated_token_address(maker, token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new(offer, false),
+            AccountMeta::new(
+                find_associated_token_address(&offer, token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(find_config_address().0, false),
+            AccountMeta::new(find_maker_stats_address(maker).0, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: instruction_data(
+            "make_offer",
+            &[
+                &args.id.to_le_bytes(),
+                &args.token_a_offered_amount.to_le_bytes(),
+                &args.token_b_wanted_amount.to_le_bytes(),
+                &[args.flags],
+                &args.min_fill_amount.to_le_bytes(),
+                &args.vesting_cliff_seconds.to_le_bytes(),
+                &args.vesting_duration_seconds.to_le_bytes(),
+            ],
+        ),
+    }
+}
+
+/// Returns the unsold token A and the deposit to the maker, and the rent to
+/// the offer's rent payer.
+pub fn cancel_offer(offer: &Offer, token_program: &Pubkey) -> Instruction {
+    let offer_address = find_offer_address(&offer.maker, offer.id).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(offer.maker, true),
+            AccountMeta::new(offer.rent_payer, false),
+            AccountMeta::new_readonly(offer.token_mint_a, false),
+            AccountMeta::new(
+                find_associated_token_address(&offer.maker, &offer.token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new(offer_address, false),
+            AccountMeta::new(
+                find_associated_token_address(&offer_address, &offer.token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new(find_maker_stats_address(&offer.maker).0, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: instruction_data("cancel_offer", &[]),
+    }
+}
+
+/// `take_offer` and `fill_offer` share one account list.
+fn offer_taker_accounts(
+    payer: &Pubkey,
+    taker: &Pubkey,
+    offer: &Offer,
+    token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    let offer_address = find_offer_address(&offer.maker, offer.id).0;
+
+    vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*taker, true),
+        AccountMeta::new(offer.maker, false),
+        AccountMeta::new(offer.rent_payer, false),
+        AccountMeta::new_readonly(offer.token_mint_a, false),
+        AccountMeta::new_readonly(offer.token_mint_b, false),
+        AccountMeta::new(
+            find_associated_token_address(taker, &offer.token_mint_a, token_program),
+            false,
+        ),
+        AccountMeta::new(
+            find_associated_token_address(taker, &offer.token_mint_b, token_program),
+            false,
+        ),
+        AccountMeta::new(
+            find_associated_token_address(&offer.maker, &offer.token_mint_b, token_program),
+            false,
+        ),
+        AccountMeta::new(offer_address, false),
+        AccountMeta::new(
+            find_associated_token_address(&offer_address, &offer.token_mint_a, token_program),
+            false,
+        ),
+        AccountMeta::new(find_maker_stats_address(&offer.maker).0, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ]
+}
+
+/// Takes everything left in the offer. `payer` may be the taker or a sponsor.
+/// A vesting offer also gets its escrow accounts; other offers pass the
+/// program id in their place, as Anchor does for a missing optional account.
+pub fn take_offer(
+    payer: &Pubkey,
+    taker: &Pubkey,
+    offer: &Offer,
+    token_program: &Pubkey,
+) -> Instruction {
+    let mut accounts = offer_taker_accounts(payer, taker, offer, token_program);
+    if offer.vests() {
+        let offer_address = find_offer_address(&offer.maker, offer.id).0;
+        let escrow = find_vesting_escrow_address(&offer_address, offer.nonce).0;
+        accounts.push(AccountMeta::new(escrow, false));
+        accounts.push(AccountMeta::new(
+            find_associated_token_address(&escrow, &offer.token_mint_a, token_program),
+            false,
+        ));
+    } else {
+        accounts.push(AccountMeta::new_readonly(ID, false));
+        accounts.push(AccountMeta::new_readonly(ID, false));
+    }
+
+    Instruction {
+        program_id: ID,
+        accounts,
+        data: instruction_data("take_offer", &[]),
+    }
+}
+
+/// Takes `token_a_amount` of the offer, subject to its flags.
+pub fn fill_offer(
+    payer: &Pubkey,
+    taker: &Pubkey,
+    offer: &Offer,
+    token_program: &Pubkey,
+    token_a_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: offer_taker_accounts(payer, taker, offer, token_program),
+        data: instruction_data("fill_offer", &[&token_a_amount.to_le_bytes()]),
+    }
+}
+
+/// Releases whatever has vested so far to the beneficiary.
+pub fn claim_vested(escrow: &VestingEscrow, token_program: &Pubkey) -> Instruction {
+    let escrow_address = find_vesting_escrow_address(&escrow.offer, escrow.offer_nonce).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(escrow.beneficiary, true),
+            AccountMeta::new_readonly(escrow.token_mint, false),
+            AccountMeta::new(
+                find_associated_token_address(
+                    &escrow.beneficiary,
+                    &escrow.token_mint,
+                    token_program,
+                ),
+                false,
+            ),
+            AccountMeta::new(escrow_address, false),
+            AccountMeta::new(
+                find_associated_token_address(&escrow_address, &escrow.token_mint, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: instruction_data("claim_vested", &[]),
+    }
+}
+
+pub fn migrate_offer(payer: &Pubkey, offer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*offer, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: instruction_data("migrate_offer", &[]),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn request_quote(
+    payer: &Pubkey,
+    requester: &Pubkey,
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+    token_program: &Pubkey,
+    id: u64,
+    token_b_wanted_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*requester, true),
+            AccountMeta::new_readonly(*token_mint_a, false),
+            AccountMeta::new_readonly(*token_mint_b, false),
+            AccountMeta::new(find_rfq_address(requester, id).0, false),
+            AccountMeta::new(find_requester_stats_address(requester).0, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: instruction_data(
+            "request_quote",
+            &[&id.to_le_bytes(), &token_b_wanted_amount.to_le_bytes()],
+        ),
+    }
+}
+
+pub fn submit_quote(
+    payer: &Pubkey,
+    maker: &Pubkey,
+    rfq: &Rfq,
+    token_program: &Pubkey,
+    token_a_wanted_amount: u64,
+) -> Instruction {
+    let rfq_address = find_rfq_address(&rfq.requester, rfq.id).0;
+    let quote = find_quote_address(&rfq_address, rfq.nonce, maker).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*maker, true),
+            AccountMeta::new_readonly(rfq_address, false),
+            AccountMeta::new_readonly(rfq.token_mint_b, false),
+            AccountMeta::new(
+                find_associated_token_address(maker, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new(quote, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: instruction_data("submit_quote", &[&token_a_wanted_amount.to_le_bytes()]),
+    }
+}
+
+pub fn accept_quote(
+    payer: &Pubkey,
+    rfq: &Rfq,
+    quote: &Quote,
+    max_token_a_amount: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let rfq_address = find_rfq_address(&rfq.requester, rfq.id).0;
+    let quote_address = find_quote_address(&rfq_address, rfq.nonce, &quote.maker).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(rfq.requester, true),
+            AccountMeta::new(quote.maker, false),
+            AccountMeta::new_readonly(rfq.token_mint_a, false),
+            AccountMeta::new_readonly(rfq.token_mint_b, false),
+            AccountMeta::new(
+                find_associated_token_address(&rfq.requester, &rfq.token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new(
+                find_associated_token_address(&rfq.requester, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new(
+                find_associated_token_address(&quote.maker, &rfq.token_mint_a, token_program),
+                false,
+            ),
+            AccountMeta::new(rfq_address, false),
+            AccountMeta::new(quote_address, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote_address, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+        data: instruction_data("accept_quote", &[&max_token_a_amount.to_le_bytes()]),
+    }
+}
+
+pub fn reject_quote(rfq: &Rfq, quote: &Quote, token_program: &Pubkey) -> Instruction {
+    let rfq_address = find_rfq_address(&rfq.requester, rfq.id).0;
+    let quote_address = find_quote_address(&rfq_address, rfq.nonce, &quote.maker).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(rfq.requester, true),
+            AccountMeta::new(quote.maker, false),
+            AccountMeta::new_readonly(rfq.token_mint_b, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote.maker, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(rfq_address, false),
+            AccountMeta::new(quote_address, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote_address, &rfq.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: instruction_data("reject_quote", &[]),
+    }
+}
+
+pub fn withdraw_quote(quote: &Quote, token_program: &Pubkey) -> Instruction {
+    let quote_address = find_quote_address(&quote.rfq, quote.rfq_nonce, &quote.maker).0;
+
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(quote.maker, true),
+            AccountMeta::new_readonly(quote.token_mint_b, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote.maker, &quote.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new(quote_address, false),
+            AccountMeta::new(
+                find_associated_token_address(&quote_address, &quote.token_mint_b, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: instruction_data("withdraw_quote", &[]),
+    }
+}
+
+pub fn cancel_rfq(rfq: &Rfq) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(rfq.requester, true),
+            AccountMeta::new(find_rfq_address(&rfq.requester, rfq.id).0, false),
+        ],
+        data: instruction_data("cancel_rfq", &[]),
+    }
+}
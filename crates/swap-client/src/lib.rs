@@ -0,0 +1,32 @@
+//! Off-chain client for the `swap` program.
+//!
+//! Builds instructions, derives PDAs and decodes accounts without linking the
+//! on-chain crate, so services only need `solana-sdk`.
+
+pub mod filters;
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub const ID: Pubkey = pubkey!("EST6eEpbZyQDa1oNzz6rRHuY3kmjbMEd2jf7c4FqbvFv");
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Anchor's 8-byte discriminator: the first bytes of
+/// `sha256("<namespace>:<name>")`.
+pub fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
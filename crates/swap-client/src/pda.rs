@@ -0,0 +1,74 @@
+//! Addresses derived by the `swap` program.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{ASSOCIATED_TOKEN_PROGRAM_ID, BPF_LOADER_UPGRADEABLE_ID, ID};
+
+pub fn find_config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &ID)
+}
+
+pub fn find_maker_stats_address(maker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"maker", maker.as_ref()], &ID)
+}
+
+pub fn find_offer_address(maker: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"offer", maker.as_ref(), &id.to_le_bytes()], &ID)
+}
+
+pub fn find_rfq_address(requester: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rfq", requester.as_ref(), &id.to_le_bytes()], &ID)
+}
+
+pub fn find_requester_stats_address(requester: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"requester", requester.as_ref()], &ID)
+}
+
+/// `rfq_nonce` is the RFQ's `nonce`, which keeps quotes on an earlier RFQ with
+/// the same id apart from quotes on the current one.
+pub fn find_quote_address(rfq: &Pubkey, rfq_nonce: u64, maker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"quote", rfq.as_ref(), &rfq_nonce.to_le_bytes(), maker.as_ref()],
+        &ID,
+    )
+}
+
+/// The escrow that holds token A bought from a vesting offer. Its vault is
+/// the escrow's associated token account.
+pub fn find_vesting_escrow_address(offer: &Pubkey, offer_nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vesting", offer.as_ref(), &offer_nonce.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// The associated token account of `owner` for `mint`. Offer and quote
+/// vaults are the associated token accounts of the offer or quote PDA.
+pub fn find_associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// The vault holding an offer's token A.
+pub fn find_offer_vault_address(
+    maker: &Pubkey,
+    id: u64,
+    token_mint_a: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    let offer = find_offer_address(maker, id).0;
+    find_associated_token_address(&offer, token_mint_a, token_program)
+}
+
+/// The upgradeable-loader account that records the program's upgrade
+/// authority, which `initialize` checks.
+pub fn find_program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
@@ -0,0 +1,392 @@
+//! Decoders for accounts owned by the `swap` program.
+
+use std::fmt;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::discriminator;
+
+/// Length of Anchor's account discriminator.
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+pub const OFFER_SPACE: usize = 218;
+/// Layout version of offers the program takes without migrating them first.
+pub const OFFER_VERSION: u8 = 4;
+/// Size of version 1 to 3 offers, from before `rent_payer` was added.
+pub const OFFER_V3_SPACE: usize = 186;
+/// Size of offers written before the layout was versioned. They decode with
+/// `version` 0 until `migrate_offer` upgrades them.
+pub const OFFER_V0_SPACE: usize = 121;
+pub const RFQ_SPACE: usize = 186;
+pub const QUOTE_SPACE: usize = 178;
+pub const CONFIG_SPACE: usize = 118;
+pub const MAKER_STATS_SPACE: usize = 78;
+pub const REQUESTER_STATS_SPACE: usize = 82;
+pub const VESTING_ESCROW_SPACE: usize = 186;
+
+pub const OFFER_FLAG_ALL_OR_NONE: u8 = 1 << 0;
+/// Not fillable in the slot the offer was made. Unlike post-only on an order
+/// book, it says nothing about crossing other offers.
+pub const OFFER_FLAG_POST_ONLY: u8 = 1 << 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with the expected account discriminator.
+    WrongAccountType,
+    /// The data ends before every field was read.
+    TooShort,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongAccountType => write!(f, "account has a different discriminator"),
+            DecodeError::TooShort => write!(f, "account data is too short"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// An account type of the `swap` program.
+pub trait SwapAccount: Sized {
+    /// The Rust type name in the program, which Anchor hashes into the
+    /// discriminator.
+    const NAME: &'static str;
+
+    fn discriminator() -> [u8; 8] {
+        discriminator("account", Self::NAME)
+    }
+
+    /// Decodes raw account data, discriminator included.
+    fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        if data.len() < DISCRIMINATOR_LEN {
+            return Err(DecodeError::TooShort);
+        }
+        if data[..DISCRIMINATOR_LEN] != Self::discriminator() {
+            return Err(DecodeError::WrongAccountType);
+        }
+        Self::decode_fields(&mut Reader::new(&data[DISCRIMINATOR_LEN..]), data.len())
+    }
+
+    #[doc(hidden)]
+    fn decode_fields(reader: &mut Reader<'_>, data_len: usize) -> Result<Self, DecodeError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Offer {
+    pub id: u64,
+    pub maker: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    /// Token B still wanted for whatever token A is left in the vault.
+    pub token_b_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub flags: u8,
+    pub min_fill_amount: u64,
+    pub created_slot: u64,
+    pub vesting_cliff_seconds: i64,
+    /// Zero unless token A goes to a vesting escrow on take.
+    pub vesting_duration_seconds: i64,
+    /// Keys the vesting escrow. Unique among the maker's offers.
+    pub nonce: u64,
+    /// Gets the offer and vault rent back when the offer closes. Offers from
+    /// before version 4 decode with the maker here, which is what
+    /// `migrate_offer` records for them.
+    pub rent_payer: Pubkey,
+}
+
+impl Offer {
+    pub fn is_all_or_none(&self) -> bool {
+        self.flags & OFFER_FLAG_ALL_OR_NONE != 0
+    }
+
+    pub fn is_post_only(&self) -> bool {
+        self.flags & OFFER_FLAG_POST_ONLY != 0
+    }
+
+    /// Whether `take_offer` needs the vesting escrow accounts.
+    pub fn vests(&self) -> bool {
+        self.vesting_duration_seconds > 0
+    }
+
+    /// Whether the account must go through `migrate_offer` before the
+    /// program will take or fill it.
+    pub fn needs_migration(&self) -> bool {
+        self.version < OFFER_VERSION
+    }
+}
+
+impl SwapAccount for Offer {
+    const NAME: &'static str = "Offer";
+
+    fn decode_fields(reader: &mut Reader<'_>, data_len: usize) -> Result<Self, DecodeError> {
+        let id = reader.u64()?;
+        let maker = reader.pubkey()?;
+        let token_mint_a = reader.pubkey()?;
+        let token_mint_b = reader.pubkey()?;
+        let token_b_wanted_amount = reader.u64()?;
+        let bump = reader.u8()?;
+
+        if data_len == OFFER_V0_SPACE {
+            return Ok(Offer {
+                id,
+                maker,
+                token_mint_a,
+                token_mint_b,
+                token_b_wanted_amount,
+                bump,
+                version: 0,
+                flags: 0,
+                min_fill_amount: 0,
+                created_slot: 0,
+                vesting_cliff_seconds: 0,
+                vesting_duration_seconds: 0,
+                nonce: 0,
+                rent_payer: maker,
+            });
+        }
+
+        Ok(Offer {
+            id,
+            maker,
+            token_mint_a,
+            token_mint_b,
+            token_b_wanted_amount,
+            bump,
+            version: reader.u8()?,
+            flags: reader.u8()?,
+            min_fill_amount: reader.u64()?,
+            created_slot: reader.u64()?,
+            vesting_cliff_seconds: reader.i64()?,
+            vesting_duration_seconds: reader.i64()?,
+            nonce: reader.u64()?,
+            rent_payer: if data_len == OFFER_V3_SPACE {
+                maker
+            } else {
+                reader.pubkey()?
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rfq {
+    pub id: u64,
+    pub requester: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_b_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    /// Unique among the requester's RFQs. Part of each quote's address.
+    pub nonce: u64,
+}
+
+impl SwapAccount for Rfq {
+    const NAME: &'static str = "Rfq";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(Rfq {
+            id: reader.u64()?,
+            requester: reader.pubkey()?,
+            token_mint_a: reader.pubkey()?,
+            token_mint_b: reader.pubkey()?,
+            token_b_wanted_amount: reader.u64()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+            nonce: reader.u64()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quote {
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub rfq_nonce: u64,
+}
+
+impl SwapAccount for Quote {
+    const NAME: &'static str = "Quote";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(Quote {
+            rfq: reader.pubkey()?,
+            maker: reader.pubkey()?,
+            token_mint_b: reader.pubkey()?,
+            token_a_wanted_amount: reader.u64()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+            rfq_nonce: reader.u64()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub max_open_offers_per_maker: u32,
+    pub offer_deposit_lamports: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SwapAccount for Config {
+    const NAME: &'static str = "Config";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(Config {
+            admin: reader.pubkey()?,
+            max_open_offers_per_maker: reader.u32()?,
+            offer_deposit_lamports: reader.u64()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MakerStats {
+    pub maker: Pubkey,
+    pub open_offers: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub offers_made: u64,
+}
+
+impl SwapAccount for MakerStats {
+    const NAME: &'static str = "MakerStats";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(MakerStats {
+            maker: reader.pubkey()?,
+            open_offers: reader.u32()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+            offers_made: reader.u64()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequesterStats {
+    pub requester: Pubkey,
+    pub rfqs_requested: u64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl SwapAccount for RequesterStats {
+    const NAME: &'static str = "RequesterStats";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(RequesterStats {
+            requester: reader.pubkey()?,
+            rfqs_requested: reader.u64()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+        })
+    }
+}
+
+/// Token A bought from a vesting offer, waiting to be claimed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VestingEscrow {
+    pub offer: Pubkey,
+    pub offer_nonce: u64,
+    pub beneficiary: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+    pub bump: u8,
+    pub version: u8,
+}
+
+impl VestingEscrow {
+    /// Amount unlocked at `now`, rounded down as the program does.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_timestamp);
+        if elapsed < self.cliff_seconds {
+            return 0;
+        }
+        if elapsed >= self.duration_seconds {
+            return self.total_amount;
+        }
+        (self.total_amount as u128 * elapsed as u128 / self.duration_seconds as u128) as u64
+    }
+
+    /// What `claim_vested` would release at `now`.
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.claimed_amount)
+    }
+}
+
+impl SwapAccount for VestingEscrow {
+    const NAME: &'static str = "VestingEscrow";
+
+    fn decode_fields(reader: &mut Reader<'_>, _data_len: usize) -> Result<Self, DecodeError> {
+        Ok(VestingEscrow {
+            offer: reader.pubkey()?,
+            offer_nonce: reader.u64()?,
+            beneficiary: reader.pubkey()?,
+            token_mint: reader.pubkey()?,
+            total_amount: reader.u64()?,
+            claimed_amount: reader.u64()?,
+            start_timestamp: reader.i64()?,
+            cliff_seconds: reader.i64()?,
+            duration_seconds: reader.i64()?,
+            bump: reader.u8()?,
+            version: reader.u8()?,
+        })
+    }
+}
+
+/// Reads Borsh-encoded fixed-size fields in order.
+#[doc(hidden)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        if self.data.len() < N {
+            return Err(DecodeError::TooShort);
+        }
+        let (head, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(head.try_into().expect("split_at returned N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, DecodeError> {
+        Ok(i64::from_le_bytes(self.take()?))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey, DecodeError> {
+        Ok(Pubkey::new_from_array(self.take()?))
+    }
+}
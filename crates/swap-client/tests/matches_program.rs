@@ -0,0 +1,784 @@
+//! Checks the client against the on-chain crate, so layout or account-order
+//! changes in `swap` fail here rather than in production.
+
+use anchor_lang::{AccountSerialize, Discriminator, InstructionData, ToAccountMetas};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use swap_client::filters::OfferFilters;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda;
+use swap_client::state::{self, SwapAccount};
+use swap_client::{ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID};
+
+const OFFER_V0_FIXTURE: &[u8] =
+    include_bytes!("../../../programs/swap/tests/fixtures/offer_v0.bin");
+
+fn program_offer() -> swap::Offer {
+    swap::Offer {
+        id: 7,
+        maker: Pubkey::new_unique(),
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_b_wanted_amount: 5_000,
+        bump: 251,
+        version: swap::OFFER_VERSION,
+        flags: swap::OFFER_FLAG_ALL_OR_NONE,
+        min_fill_amount: 10,
+        created_slot: 99,
+        vesting_cliff_seconds: 0,
+        vesting_duration_seconds: 0,
+        nonce: 7,
+        rent_payer: Pubkey::new_unique(),
+        reserved: [0; swap::OFFER_RESERVED_SPACE],
+    }
+}
+
+fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data
+}
+
+fn client_offer(offer: &swap::Offer) -> state::Offer {
+    state::Offer::decode(&serialize(offer)).unwrap()
+}
+
+fn assert_same_instruction(client: Instruction, program: Instruction) {
+    assert_eq!(client.program_id, program.program_id);
+    assert_eq!(client.accounts, program.accounts);
+    assert_eq!(client.data, program.data);
+}
+
+#[test]
+fn program_ids_match() {
+    assert_eq!(swap_client::ID, swap::ID);
+    assert_eq!(swap_client::TOKEN_PROGRAM_ID, anchor_spl::token::ID);
+    assert_eq!(
+        swap_client::TOKEN_2022_PROGRAM_ID,
+        anchor_spl::token_2022::ID
+    );
+    assert_eq!(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        anchor_spl::associated_token::ID
+    );
+}
+
+#[test]
+fn associated_token_addresses_match_spl() {
+    let owner = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(
+        pda::find_associated_token_address(&owner, &mint, &TOKEN_2022_PROGRAM_ID),
+        anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &owner,
+            &mint,
+            &TOKEN_2022_PROGRAM_ID,
+        )
+    );
+}
+
+#[test]
+fn account_sizes_match() {
+    assert_eq!(state::OFFER_SPACE, swap::Offer::SPACE);
+    assert_eq!(state::OFFER_V3_SPACE, swap::Offer::V3_SPACE);
+    assert_eq!(state::OFFER_V0_SPACE, swap::OfferV0::SPACE);
+    assert_eq!(state::RFQ_SPACE, swap::Rfq::SPACE);
+    assert_eq!(state::QUOTE_SPACE, swap::Quote::SPACE);
+    assert_eq!(state::CONFIG_SPACE, swap::Config::SPACE);
+    assert_eq!(state::MAKER_STATS_SPACE, swap::MakerStats::SPACE);
+    assert_eq!(state::REQUESTER_STATS_SPACE, swap::RequesterStats::SPACE);
+    assert_eq!(state::VESTING_ESCROW_SPACE, swap::VestingEscrow::SPACE);
+}
+
+#[test]
+fn account_discriminators_match() {
+    assert_eq!(state::Offer::discriminator(), swap::Offer::DISCRIMINATOR);
+    assert_eq!(state::Rfq::discriminator(), swap::Rfq::DISCRIMINATOR);
+    assert_eq!(state::Quote::discriminator(), swap::Quote::DISCRIMINATOR);
+    assert_eq!(state::Config::discriminator(), swap::Config::DISCRIMINATOR);
+    assert_eq!(
+        state::MakerStats::discriminator(),
+        swap::MakerStats::DISCRIMINATOR
+    );
+    assert_eq!(
+        state::RequesterStats::discriminator(),
+        swap::RequesterStats::DISCRIMINATOR
+    );
+    assert_eq!(
+        state::VestingEscrow::discriminator(),
+        swap::VestingEscrow::DISCRIMINATOR
+    );
+}
+
+#[test]
+fn decodes_offer_written_by_program() {
+    let offer = program_offer();
+    let decoded = client_offer(&offer);
+
+    assert_eq!(decoded.id, offer.id);
+    assert_eq!(decoded.maker, offer.maker);
+    assert_eq!(decoded.token_mint_a, offer.token_mint_a);
+    assert_eq!(decoded.token_mint_b, offer.token_mint_b);
+    assert_eq!(decoded.token_b_wanted_amount, offer.token_b_wanted_amount);
+    assert_eq!(decoded.bump, offer.bump);
+    assert_eq!(decoded.version, offer.version);
+    assert_eq!(decoded.flags, offer.flags);
+    assert_eq!(decoded.min_fill_amount, offer.min_fill_amount);
+    assert_eq!(decoded.created_slot, offer.created_slot);
+    assert_eq!(decoded.nonce, offer.nonce);
+    assert_eq!(decoded.rent_payer, offer.rent_payer);
+    assert!(decoded.is_all_or_none());
+    assert!(!decoded.needs_migration());
+    assert!(!decoded.vests());
+
+    let vesting = swap::Offer {
+        vesting_cliff_seconds: 60,
+        vesting_duration_seconds: 3_600,
+        ..program_offer()
+    };
+    let decoded = client_offer(&vesting);
+    assert_eq!(decoded.vesting_cliff_seconds, 60);
+    assert_eq!(decoded.vesting_duration_seconds, 3_600);
+    assert!(decoded.vests());
+}
+
+#[test]
+fn decodes_vesting_escrow_written_by_program() {
+    let escrow = swap::VestingEscrow {
+        offer: Pubkey::new_unique(),
+        offer_nonce: 7,
+        beneficiary: Pubkey::new_unique(),
+        token_mint: Pubkey::new_unique(),
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_timestamp: 1_700_000_000,
+        cliff_seconds: 100,
+        duration_seconds: 400,
+        bump: 253,
+        version: swap::VESTING_ESCROW_VERSION,
+        reserved: [0; swap::VESTING_ESCROW_RESERVED_SPACE],
+    };
+    let decoded = state::VestingEscrow::decode(&serialize(&escrow)).unwrap();
+
+    assert_eq!(decoded.offer, escrow.offer);
+    assert_eq!(decoded.offer_nonce, escrow.offer_nonce);
+    assert_eq!(decoded.beneficiary, escrow.beneficiary);
+    assert_eq!(decoded.token_mint, escrow.token_mint);
+    assert_eq!(decoded.total_amount, escrow.total_amount);
+    assert_eq!(decoded.claimed_amount, escrow.claimed_amount);
+    assert_eq!(decoded.start_timestamp, escrow.start_timestamp);
+    assert_eq!(decoded.cliff_seconds, escrow.cliff_seconds);
+    assert_eq!(decoded.duration_seconds, escrow.duration_seconds);
+    assert_eq!(decoded.bump, escrow.bump);
+    assert_eq!(decoded.version, escrow.version);
+
+    for elapsed in [0, 99, 100, 250, 399, 400, 10_000] {
+        let now = escrow.start_timestamp + elapsed;
+        assert_eq!(decoded.vested_amount(now), escrow.vested_amount(now));
+        assert_eq!(decoded.claimable_amount(now), escrow.claimable_amount(now));
+    }
+}
+
+#[test]
+fn decodes_rfq_and_quote_written_by_program() {
+    let rfq = swap::Rfq {
+        id: 3,
+        requester: Pubkey::new_unique(),
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_b_wanted_amount: 10_000,
+        bump: 254,
+        version: swap::RFQ_VERSION,
+        nonce: 12,
+        reserved: [0; swap::RFQ_RESERVED_SPACE],
+    };
+    let decoded = state::Rfq::decode(&serialize(&rfq)).unwrap();
+    assert_eq!(decoded.requester, rfq.requester);
+    assert_eq!(decoded.token_b_wanted_amount, rfq.token_b_wanted_amount);
+    assert_eq!(decoded.version, rfq.version);
+    assert_eq!(decoded.nonce, rfq.nonce);
+
+    let quote = swap::Quote {
+        rfq: Pubkey::new_unique(),
+        maker: Pubkey::new_unique(),
+        token_mint_b: rfq.token_mint_b,
+        token_a_wanted_amount: 400,
+        bump: 253,
+        version: swap::QUOTE_VERSION,
+        rfq_nonce: 12,
+        reserved: [0; swap::QUOTE_RESERVED_SPACE],
+    };
+    let decoded = state::Quote::decode(&serialize(&quote)).unwrap();
+    assert_eq!(decoded.maker, quote.maker);
+    assert_eq!(decoded.token_a_wanted_amount, quote.token_a_wanted_amount);
+    assert_eq!(decoded.version, quote.version);
+    assert_eq!(decoded.rfq_nonce, quote.rfq_nonce);
+}
+
+#[test]
+fn decodes_legacy_offer() {
+    let decoded = state::Offer::decode(OFFER_V0_FIXTURE).unwrap();
+
+    assert_eq!(decoded.id, 42);
+    assert_eq!(decoded.token_b_wanted_amount, 1_000_000);
+    assert_eq!(decoded.bump, 254);
+    assert_eq!(decoded.rent_payer, decoded.maker);
+    assert!(decoded.needs_migration());
+}
+
+#[test]
+fn decodes_offer_from_before_rent_payer() {
+    let offer = swap::Offer {
+        version: 3,
+        ..program_offer()
+    };
+    let mut data = serialize(&offer);
+    data.drain(
+        swap::Offer::V3_SPACE - swap::OFFER_RESERVED_SPACE
+            ..swap::Offer::SPACE - swap::OFFER_RESERVED_SPACE,
+    );
+    assert_eq!(data.len(), state::OFFER_V3_SPACE);
+
+    let decoded = state::Offer::decode(&data).unwrap();
+    assert_eq!(decoded.nonce, offer.nonce);
+    assert_eq!(decoded.rent_payer, offer.maker);
+    assert!(decoded.needs_migration());
+}
+
+#[test]
+fn rejects_other_accounts() {
+    let config = swap::Config {
+        admin: Pubkey::new_unique(),
+        max_open_offers_per_maker: 3,
+        offer_deposit_lamports: 1,
+        bump: 255,
+        version: swap::CONFIG_VERSION,
+        reserved: [0; swap::CONFIG_RESERVED_SPACE],
+    };
+    let data = serialize(&config);
+
+    assert_eq!(
+        state::Offer::decode(&data).unwrap_err(),
+        state::DecodeError::WrongAccountType
+    );
+    assert_eq!(
+        state::Config::decode(&data)
+            .unwrap()
+            .max_open_offers_per_maker,
+        3
+    );
+    assert_eq!(
+        state::Config::decode(&data[..20]).unwrap_err(),
+        state::DecodeError::TooShort
+    );
+}
+
+#[test]
+fn offer_filters_select_by_maker_and_mints() {
+    let offer = program_offer();
+    let data = serialize(&offer);
+
+    assert!(OfferFilters::new().matches(&data));
+    assert!(OfferFilters::new().maker(offer.maker).matches(&data));
+    assert!(OfferFilters::new()
+        .token_mint_a(offer.token_mint_a)
+        .token_mint_b(offer.token_mint_b)
+        .matches(&data));
+    assert!(!OfferFilters::new().maker(offer.token_mint_a).matches(&data));
+    assert!(!OfferFilters::new()
+        .token_mint_b(offer.token_mint_a)
+        .matches(&data));
+    assert!(OfferFilters::new().matches(OFFER_V0_FIXTURE));
+}
+
+#[test]
+fn make_offer_matches_program() {
+    let payer = Pubkey::new_unique();
+    let maker = Pubkey::new_unique();
+    let token_mint_a = Pubkey::new_unique();
+    let token_mint_b = Pubkey::new_unique();
+    let args = MakeOfferArgs {
+        id: 11,
+        token_a_offered_amount: 100,
+        token_b_wanted_amount: 200,
+        flags: swap::OFFER_FLAG_POST_ONLY,
+        min_fill_amount: 5,
+        vesting_cliff_seconds: 30,
+        vesting_duration_seconds: 90,
+    };
+    let offer = pda::find_offer_address(&maker, args.id).0;
+
+    let program_accounts = swap::accounts::MakeOffer {
+        payer,
+        maker,
+        token_mint_a,
+        token_mint_b,
+        maker_token_account_a: pda::find_associated_token_address(
+            &maker,
+            &token_mint_a,
+            &TOKEN_2022_PROGRAM_ID,
+        ),
+        offer,
+        vault: pda::find_offer_vault_address(
+            &maker,
+            args.id,
+            &token_mint_a,
+            &TOKEN_2022_PROGRAM_ID,
+        ),
+        config: pda::find_config_address().0,
+        maker_stats: pda::find_maker_stats_address(&maker).0,
+        system_program: SYSTEM_PROGRAM_ID,
+        token_program: TOKEN_2022_PROGRAM_ID,
+        associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+    let program_data = swap::instruction::MakeOffer {
+        id: args.id,
+        token_a_offered_amount: args.token_a_offered_amount,
+        token_b_wanted_amount: args.token_b_wanted_amount,
+        flags: args.flags,
+        min_fill_amount: args.min_fill_amount,
+        vesting_cliff_seconds: args.vesting_cliff_seconds,
+        vesting_duration_seconds: args.vesting_duration_seconds,
+    };
+
+    assert_same_instruction(
+        instruction::make_offer(
+            &payer,
+            &maker,
+            &token_mint_a,
+            &token_mint_b,
+            &TOKEN_2022_PROGRAM_ID,
+            &args,
+        ),
+        Instruction {
+            program_id: swap::ID,
+            accounts: program_accounts.to_account_metas(None),
+            data: program_data.data(),
+        },
+    );
+}
+
+fn take_offer_accounts(
+    payer: Pubkey,
+    taker: Pubkey,
+    offer: &swap::Offer,
+) -> swap::accounts::TakeOffer {
+    let offer_address = pda::find_offer_address(&offer.maker, offer.id).0;
+    let ata = |owner: &Pubkey, mint: &Pubkey| {
+        pda::find_associated_token_address(owner, mint, &TOKEN_2022_PROGRAM_ID)
+    };
+
+    swap::accounts::TakeOffer {
+        payer,
+        taker,
+        maker: offer.maker,
+        rent_payer: offer.rent_payer,
+        token_mint_a: offer.token_mint_a,
+        token_mint_b: offer.token_mint_b,
+        taker_token_account_a: ata(&taker, &offer.token_mint_a),
+        taker_token_account_b: ata(&taker, &offer.token_mint_b),
+        maker_token_account_b: ata(&offer.maker, &offer.token_mint_b),
+        offer: offer_address,
+        vault: ata(&offer_address, &offer.token_mint_a),
+        maker_stats: pda::find_maker_stats_address(&offer.maker).0,
+        system_program: SYSTEM_PROGRAM_ID,
+        token_program: TOKEN_2022_PROGRAM_ID,
+        associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        vesting_escrow: None,
+        vesting_vault: None,
+    }
+}
+
+fn fill_offer_accounts(accounts: swap::accounts::TakeOffer) -> swap::accounts::FillOffer {
+    swap::accounts::FillOffer {
+        payer: accounts.payer,
+        taker: accounts.taker,
+        maker: accounts.maker,
+        rent_payer: accounts.rent_payer,
+        token_mint_a: accounts.token_mint_a,
+        token_mint_b: accounts.token_mint_b,
+        taker_token_account_a: accounts.taker_token_account_a,
+        taker_token_account_b: accounts.taker_token_account_b,
+        maker_token_account_b: accounts.maker_token_account_b,
+        offer: accounts.offer,
+        vault: accounts.vault,
+        maker_stats: accounts.maker_stats,
+        system_program: accounts.system_program,
+        token_program: accounts.token_program,
+        associated_token_program: accounts.associated_token_program,
+    }
+}
+
+#[test]
+fn take_and_fill_offer_match_program() {
+    let payer = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let offer = program_offer();
+    let accounts = take_offer_accounts(payer, taker, &offer);
+
+    assert_same_instruction(
+        instruction::take_offer(
+            &payer,
+            &taker,
+            &client_offer(&offer),
+            &TOKEN_2022_PROGRAM_ID,
+        ),
+        Instruction {
+            program_id: swap::ID,
+            accounts: accounts.to_account_metas(None),
+            data: swap::instruction::TakeOffer {}.data(),
+        },
+    );
+
+    // `FillOffer` lists the same accounts, minus the vesting escrow.
+    assert_same_instruction(
+        instruction::fill_offer(
+            &payer,
+            &taker,
+            &client_offer(&offer),
+            &TOKEN_2022_PROGRAM_ID,
+            3,
+        ),
+        Instruction {
+            program_id: swap::ID,
+            accounts: fill_offer_accounts(accounts).to_account_metas(None),
+            data: swap::instruction::FillOffer { token_a_amount: 3 }.data(),
+        },
+    );
+}
+
+#[test]
+fn cancel_offer_matches_program() {
+    let offer = program_offer();
+    let offer_address = pda::find_offer_address(&offer.maker, offer.id).0;
+
+    assert_same_instruction(
+        instruction::cancel_offer(&client_offer(&offer), &TOKEN_2022_PROGRAM_ID),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::CancelOffer {
+                maker: offer.maker,
+                rent_payer: offer.rent_payer,
+                token_mint_a: offer.token_mint_a,
+                maker_token_account_a: pda::find_associated_token_address(
+                    &offer.maker,
+                    &offer.token_mint_a,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                offer: offer_address,
+                vault: pda::find_offer_vault_address(
+                    &offer.maker,
+                    offer.id,
+                    &offer.token_mint_a,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                maker_stats: pda::find_maker_stats_address(&offer.maker).0,
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::CancelOffer {}.data(),
+        },
+    );
+}
+
+#[test]
+fn vesting_instructions_match_program() {
+    let payer = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let offer = swap::Offer {
+        vesting_cliff_seconds: 60,
+        vesting_duration_seconds: 3_600,
+        ..program_offer()
+    };
+    let offer_address = pda::find_offer_address(&offer.maker, offer.id).0;
+    let escrow_address = pda::find_vesting_escrow_address(&offer_address, offer.nonce).0;
+    let escrow_vault = pda::find_associated_token_address(
+        &escrow_address,
+        &offer.token_mint_a,
+        &TOKEN_2022_PROGRAM_ID,
+    );
+
+    assert_same_instruction(
+        instruction::take_offer(
+            &payer,
+            &taker,
+            &client_offer(&offer),
+            &TOKEN_2022_PROGRAM_ID,
+        ),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::TakeOffer {
+                vesting_escrow: Some(escrow_address),
+                vesting_vault: Some(escrow_vault),
+                ..take_offer_accounts(payer, taker, &offer)
+            }
+            .to_account_metas(None),
+            data: swap::instruction::TakeOffer {}.data(),
+        },
+    );
+
+    let escrow = state::VestingEscrow {
+        offer: offer_address,
+        offer_nonce: offer.nonce,
+        beneficiary: taker,
+        token_mint: offer.token_mint_a,
+        total_amount: 1_000,
+        claimed_amount: 0,
+        start_timestamp: 0,
+        cliff_seconds: offer.vesting_cliff_seconds,
+        duration_seconds: offer.vesting_duration_seconds,
+        bump: 255,
+        version: swap::VESTING_ESCROW_VERSION,
+    };
+    assert_same_instruction(
+        instruction::claim_vested(&escrow, &TOKEN_2022_PROGRAM_ID),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::ClaimVested {
+                beneficiary: taker,
+                token_mint: offer.token_mint_a,
+                beneficiary_token_account: pda::find_associated_token_address(
+                    &taker,
+                    &offer.token_mint_a,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                vesting_escrow: escrow_address,
+                vesting_vault: escrow_vault,
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::ClaimVested {}.data(),
+        },
+    );
+}
+
+#[test]
+fn config_instructions_match_program() {
+    let payer = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let config = pda::find_config_address().0;
+
+    assert_same_instruction(
+        instruction::initialize(&payer, &admin, 4, 1_000),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::Initialize {
+                payer,
+                admin,
+                config,
+                program: swap::ID,
+                program_data: pda::find_program_data_address(),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::Initialize {
+                max_open_offers_per_maker: 4,
+                offer_deposit_lamports: 1_000,
+            }
+            .data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::update_config(&admin, 0, 0),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::UpdateConfig { admin, config }.to_account_metas(None),
+            data: swap::instruction::UpdateConfig {
+                max_open_offers_per_maker: 0,
+                offer_deposit_lamports: 0,
+            }
+            .data(),
+        },
+    );
+}
+
+#[test]
+fn migrate_offer_matches_program() {
+    let payer = Pubkey::new_unique();
+    let offer = Pubkey::new_unique();
+
+    assert_same_instruction(
+        instruction::migrate_offer(&payer, &offer),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::MigrateOffer {
+                payer,
+                offer,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::MigrateOffer {}.data(),
+        },
+    );
+}
+
+#[test]
+fn rfq_instructions_match_program() {
+    let payer = Pubkey::new_unique();
+    let requester = Pubkey::new_unique();
+    let maker = Pubkey::new_unique();
+    let token_mint_a = Pubkey::new_unique();
+    let token_mint_b = Pubkey::new_unique();
+    let ata = |owner: &Pubkey, mint: &Pubkey| {
+        pda::find_associated_token_address(owner, mint, &TOKEN_2022_PROGRAM_ID)
+    };
+    let rfq_address = pda::find_rfq_address(&requester, 3).0;
+    let quote_address = pda::find_quote_address(&rfq_address, 5, &maker).0;
+    let rfq = state::Rfq {
+        id: 3,
+        requester,
+        token_mint_a,
+        token_mint_b,
+        token_b_wanted_amount: 10_000,
+        bump: 255,
+        version: swap::RFQ_VERSION,
+        nonce: 5,
+    };
+    let quote = state::Quote {
+        rfq: rfq_address,
+        maker,
+        token_mint_b,
+        token_a_wanted_amount: 400,
+        bump: 255,
+        version: swap::QUOTE_VERSION,
+        rfq_nonce: 5,
+    };
+
+    assert_same_instruction(
+        instruction::request_quote(
+            &payer,
+            &requester,
+            &token_mint_a,
+            &token_mint_b,
+            &TOKEN_2022_PROGRAM_ID,
+            3,
+            10_000,
+        ),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::RequestQuote {
+                payer,
+                requester,
+                token_mint_a,
+                token_mint_b,
+                rfq: rfq_address,
+                requester_stats: pda::find_requester_stats_address(&requester).0,
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::RequestQuote {
+                id: 3,
+                token_b_wanted_amount: 10_000,
+            }
+            .data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::submit_quote(&payer, &maker, &rfq, &TOKEN_2022_PROGRAM_ID, 400),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::SubmitQuote {
+                payer,
+                maker,
+                rfq: rfq_address,
+                token_mint_b,
+                maker_token_account_b: ata(&maker, &token_mint_b),
+                quote: quote_address,
+                vault: ata(&quote_address, &token_mint_b),
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::SubmitQuote {
+                token_a_wanted_amount: 400,
+            }
+            .data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::accept_quote(&payer, &rfq, &quote, 400, &TOKEN_2022_PROGRAM_ID),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::AcceptQuote {
+                payer,
+                requester,
+                maker,
+                token_mint_a,
+                token_mint_b,
+                requester_token_account_a: ata(&requester, &token_mint_a),
+                requester_token_account_b: ata(&requester, &token_mint_b),
+                maker_token_account_a: ata(&maker, &token_mint_a),
+                rfq: rfq_address,
+                quote: quote_address,
+                vault: ata(&quote_address, &token_mint_b),
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+                associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::AcceptQuote {
+                max_token_a_amount: 400,
+            }
+            .data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::reject_quote(&rfq, &quote, &TOKEN_2022_PROGRAM_ID),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::RejectQuote {
+                requester,
+                maker,
+                token_mint_b,
+                maker_token_account_b: ata(&maker, &token_mint_b),
+                rfq: rfq_address,
+                quote: quote_address,
+                vault: ata(&quote_address, &token_mint_b),
+                token_program: TOKEN_2022_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::RejectQuote {}.data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::withdraw_quote(&quote, &TOKEN_2022_PROGRAM_ID),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::WithdrawQuote {
+                maker,
+                token_mint_b,
+                maker_token_account_b: ata(&maker, &token_mint_b),
+                quote: quote_address,
+                vault: ata(&quote_address, &token_mint_b),
+                token_program: TOKEN_2022_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::WithdrawQuote {}.data(),
+        },
+    );
+
+    assert_same_instruction(
+        instruction::cancel_rfq(&rfq),
+        Instruction {
+            program_id: swap::ID,
+            accounts: swap::accounts::CancelRfq {
+                requester,
+                rfq: rfq_address,
+            }
+            .to_account_metas(None),
+            data: swap::instruction::CancelRfq {}.data(),
+        },
+    );
+}
@@ -9,7 +9,13 @@ import { Input } from '@/components/ui/input'
 import { useSolana } from '@/components/solana/use-solana'
 import { PublicKey } from '@solana/web3.js'
 import { toast } from 'sonner'
-import { getProgramFromWalletUi, SystemProgram } from '@/lib/anchor'
+import {
+  ENTRY_INDEX_PAGE_SIZE,
+  findEntryIndexAddress,
+  findJournalEntryAddress,
+  getProgramFromWalletUi,
+  SystemProgram,
+} from '@/lib/anchor'
 
 const primary: {
   label: string
@@ -84,17 +90,30 @@ export default function DashboardFeature() {
     try {
       const program = await getProgramFromWalletUi(sol)
       const ownerPk = new PublicKey(account.address)
-      const [journalEntry] = PublicKey.findProgramAddressSync(
-        [Buffer.from(title), ownerPk.toBuffer()],
-        program.programId,
-      )
+      const [journalEntry] = await findJournalEntryAddress(ownerPk, title, program.programId)
       const [userProfile] = PublicKey.findProgramAddressSync(
         [Buffer.from('profile'), ownerPk.toBuffer()],
         program.programId,
       )
+      const profile: any = await (program.account as any).userProfile.fetch(userProfile)
+      const page = Math.floor(Number(profile.indexedEntries) / ENTRY_INDEX_PAGE_SIZE)
+      const [entryIndex] = findEntryIndexAddress(ownerPk, page, program.programId)
+      // Once the owner keeps a category registry, creates have to count in it
+      const [registry] = PublicKey.findProgramAddressSync(
+        [Buffer.from('categories'), ownerPk.toBuffer()],
+        program.programId,
+      )
+      const categoryRegistry = (await program.provider.connection.getAccountInfo(registry)) ? registry : null
       await program.methods
-        .createJournalEntry(title, content, category)
-        .accounts({ owner: ownerPk, journalEntry, userProfile, systemProgram: SystemProgram.programId })
+        .createJournalEntry(title, content, category, [])
+        .accounts({
+          owner: ownerPk,
+          journalEntry,
+          userProfile,
+          entryIndex,
+          systemProgram: SystemProgram.programId,
+          categoryRegistry,
+        })
         .rpc()
       toast.success('Entry created')
     } catch (e: any) {
@@ -42,6 +42,22 @@ export async function getProgramFromWalletUi(sol: any) {
     return new anchor.Program(idl as anchor.Idl, PROGRAM_ID, provider)
 }
 
+// Entries are seeded by sha256(title), since a raw title longer than 32 bytes
+// can't be a PDA seed.
+export async function findJournalEntryAddress(owner: PublicKey, title: string, programId: PublicKey = PROGRAM_ID) {
+    const titleHash = await crypto.subtle.digest('SHA-256', new TextEncoder().encode(title))
+    return PublicKey.findProgramAddressSync([Buffer.from(titleHash), owner.toBuffer()], programId)
+}
+
+// Entry addresses per EntryIndex page; new entries go on the last page.
+export const ENTRY_INDEX_PAGE_SIZE = 25
+
+export function findEntryIndexAddress(owner: PublicKey, page: number, programId: PublicKey = PROGRAM_ID) {
+    const pageSeed = Buffer.alloc(4)
+    pageSeed.writeUInt32LE(page)
+    return PublicKey.findProgramAddressSync([Buffer.from('index'), owner.toBuffer(), pageSeed], programId)
+}
+
 export { SystemProgram }
 
 
@@ -0,0 +1,78 @@
+use anchor_lang::solana_program::hash::hash;
+
+/// Seed for a journal entry PDA: the SHA-256 of its title.
+/// A seed is capped at 32 bytes, so the raw title would reject any title
+/// longer than that even though validation allows up to 100 bytes.
+pub fn title_seed(title: &str) -> [u8; 32] {
+    hash(title.as_bytes()).to_bytes()
+}
+
+/// Size of a `UserProfile` created before `next_entry_id` was added
+pub const LEGACY_USER_PROFILE_SPACE: usize = 8 + 32 + 4 + 8;
+
+/// Entry addresses per `EntryIndex` page
+/// A page is returned whole by `get_entry_index_page`, and return data is
+/// capped at 1024 bytes: 25 entries of 33 bytes each fit, 32 wouldn't
+pub const ENTRY_INDEX_PAGE_SIZE: usize = 25;
+
+/// Most bytes in an entry's title
+pub const MAX_TITLE_LEN: usize = 100;
+
+/// Most bytes in an entry's category
+pub const MAX_CATEGORY_LEN: usize = 50;
+
+/// Most content bytes `create_journal_entry` and `update_journal_entry` take
+pub const MAX_CONTENT_LEN: usize = 1000;
+
+/// Most content bytes an entry holds inline once grown by `append_content`
+/// Further content goes to `EntryPage` accounts
+pub const MAX_ENTRY_CONTENT_LEN: usize = 10 * 1024;
+
+/// Most content bytes one `EntryPage` holds
+pub const MAX_ENTRY_PAGE_CONTENT_LEN: usize = 10 * 1024;
+
+/// Scheme id of encrypted entries: XChaCha20-Poly1305 under a key derived
+/// from the owner's wallet signature (see `crates/journal-crypto`)
+pub const ENCRYPTION_SCHEME_XCHACHA20_POLY1305: u8 = 1;
+
+/// Bytes the XChaCha20-Poly1305 tag adds to the plaintext
+pub const ENCRYPTION_TAG_LEN: usize = 16;
+
+/// Most ciphertext bytes an encrypted entry holds: `MAX_CONTENT_LEN` bytes
+/// of plaintext plus the tag
+pub const MAX_CIPHERTEXT_LEN: usize = MAX_CONTENT_LEN + ENCRYPTION_TAG_LEN;
+
+/// Longest URI an off-chain entry stores
+pub const MAX_CONTENT_URI_LEN: usize = 200;
+
+/// URI schemes off-chain content may use: content-addressed storage, so the
+/// URI keeps pointing at the same payload
+pub const CONTENT_URI_SCHEMES: [&str; 2] = ["ar://", "ipfs://"];
+
+/// Most grantees an entry can be shared with
+/// `get_entry_grants` returns them all at once, within the 1024 bytes of
+/// return data
+pub const MAX_ENTRY_GRANTS: usize = 16;
+
+/// Longest comment `comment_on_entry` takes
+pub const MAX_COMMENT_LEN: usize = 500;
+
+/// Most members a notebook lists besides its admin
+pub const MAX_NOTEBOOK_MEMBERS: usize = 20;
+
+/// Longest notebook name
+pub const MAX_NOTEBOOK_NAME_LEN: usize = 50;
+
+/// Most categories a `CategoryRegistry` lists
+pub const MAX_CATEGORIES: usize = 20;
+
+/// Most tags one entry carries
+pub const MAX_ENTRY_TAGS: usize = 8;
+
+/// Longest tag
+pub const MAX_TAG_LEN: usize = 32;
+
+/// Entry addresses per `TagIndex` page
+/// `get_tag_entries` returns a page whole, within the 1024 bytes of return
+/// data, as `get_entry_index_page` does
+pub const TAG_INDEX_PAGE_SIZE: usize = 25;
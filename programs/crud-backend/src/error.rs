@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum JournalError {
+    #[msg("Title cannot be empty")]
+    TitleEmpty,
+    #[msg("Title is too long (max 100 characters)")]
+    TitleTooLong,
+    #[msg("Content is too long (max 1000 bytes on create or update; grow it with append_content)")]
+    ContentTooLong,
+    #[msg("Category is too long (max 50 characters)")]
+    CategoryTooLong,
+    #[msg("Title is this entry's address; use rename_journal_entry to change it")]
+    TitleIsAddress,
+    #[msg("User profile is already up to date")]
+    ProfileNotUpgradable,
+    #[msg("Entry is already up to date, or isn't the signer's entry")]
+    EntryNotUpgradable,
+    #[msg("Entry index page missing or not the one listing this entry")]
+    WrongEntryIndexPage,
+    #[msg("Remaining accounts must be the page's live entries, in order")]
+    EntryIndexAccountsMismatch,
+    #[msg("Entry content is full (max 10240 bytes inline); continue in an entry page")]
+    EntryContentFull,
+    #[msg("Entry page is full (max 10240 bytes); start the next page")]
+    EntryPageFull,
+    #[msg("Entry pages are created and appended in order; only the last page grows")]
+    EntryPageOutOfOrder,
+    #[msg("Entry has content pages, so its inline content and address are fixed")]
+    EntryHasPages,
+    #[msg("Remaining accounts must be all of the entry's content pages, in order")]
+    EntryPageAccountsMismatch,
+    #[msg("Entry is encrypted; write it with update_encrypted_journal_entry")]
+    EntryIsEncrypted,
+    #[msg("Unknown encryption scheme")]
+    UnknownEncryptionScheme,
+    #[msg("Ciphertext must be 16 to 1016 bytes: the tag plus up to 1000 bytes of content")]
+    CiphertextSize,
+    #[msg("Entry content is stored off-chain; point it at new content with update_off_chain_journal_entry")]
+    EntryIsOffChain,
+    #[msg("Content URI is too long (max 200 bytes)")]
+    ContentUriTooLong,
+    #[msg("Content URI must be ar://<id> or ipfs://<cid>")]
+    InvalidContentUri,
+    #[msg("Revision belongs to another entry")]
+    RevisionNotOfEntry,
+    #[msg("Entry is shared; revoke its grants first")]
+    EntryHasGrants,
+    #[msg("Entry is shared with too many grantees (max 16)")]
+    TooManyGrants,
+    #[msg("Remaining accounts must be the entry's grants, in order")]
+    EntryGrantAccountsMismatch,
+    #[msg("No grant, or the grant doesn't allow this")]
+    PermissionDenied,
+    #[msg("Comment cannot be empty")]
+    CommentEmpty,
+    #[msg("Comment is too long (max 500 bytes)")]
+    CommentTooLong,
+    #[msg("Notebook name cannot be empty")]
+    NotebookNameEmpty,
+    #[msg("Notebook name is too long (max 50 characters)")]
+    NotebookNameTooLong,
+    #[msg("Notebook has too many members (max 20)")]
+    TooManyNotebookMembers,
+    #[msg("Not a member of this notebook, or the role doesn't allow this")]
+    NotebookRoleDenied,
+    #[msg("The notebook's admin can't be added, changed or removed")]
+    NotebookAdminFixed,
+    #[msg("Not a member of this notebook")]
+    NotNotebookMember,
+    #[msg("Entry doesn't belong to this notebook")]
+    EntryNotInNotebook,
+    #[msg("Pass the owner's category registry")]
+    CategoryRegistryMissing,
+    #[msg("Category isn't in the registry, which is strict")]
+    UnknownCategory,
+    #[msg("Category registry is full (max 20 categories)")]
+    TooManyCategories,
+    #[msg("Category cannot be empty")]
+    CategoryEmpty,
+    #[msg("Category is already in the registry")]
+    CategoryExists,
+    #[msg("Category isn't in the registry")]
+    CategoryNotFound,
+    #[msg("Category still has entries")]
+    CategoryInUse,
+    #[msg("Entry is already counted in the category registry")]
+    EntryAlreadyCounted,
+    #[msg("Tag cannot be empty")]
+    TagEmpty,
+    #[msg("Tag is too long (max 32 characters)")]
+    TagTooLong,
+    #[msg("Entry has too many tags (max 8)")]
+    TooManyTags,
+    #[msg("Entry already has this tag")]
+    EntryAlreadyTagged,
+    #[msg("Entry doesn't have this tag")]
+    EntryNotTagged,
+    #[msg("Tag index page doesn't list the entry")]
+    WrongTagIndexPage,
+    #[msg("Tag index accounts don't match the entry's tags")]
+    TagIndexAccountsMismatch,
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+
+use crate::state::entry_grant::GrantPermission;
+use crate::state::notebook::NotebookRole;
+
+#[event]
+pub struct JournalEntryCreated {
+    pub owner: Pubkey,
+    pub title: String,
+    pub category: String,
+    pub created_at: i64,
+    pub entry_id: Option<u64>,
+}
+
+#[event]
+pub struct JournalEntryUpdated {
+    pub owner: Pubkey,
+    pub title: String,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct JournalEntryDeleted {
+    pub owner: Pubkey,
+    pub title: String,
+}
+
+#[event]
+pub struct JournalEntryArchived {
+    pub owner: Pubkey,
+    pub title: String,
+}
+
+#[event]
+pub struct JournalEntryUnarchived {
+    pub owner: Pubkey,
+    pub title: String,
+}
+
+#[event]
+pub struct JournalEntryRenamed {
+    pub owner: Pubkey,
+    pub old_title: String,
+    pub new_title: String,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct JournalEntryContentAppended {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub page: Option<u32>, // None for the inline content
+    pub bytes: u32,
+}
+
+#[event]
+pub struct JournalEntryRevised {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub revision: u64,
+    pub hash: [u8; 32], // The entry's new revision_hash
+}
+
+#[event]
+pub struct JournalEntryRestored {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub revision: u64, // The revision restored
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct EntryAccessGranted {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub grantee: Pubkey,
+    pub permission: GrantPermission,
+}
+
+#[event]
+pub struct EntryAccessRevoked {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub grantee: Pubkey,
+}
+
+#[event]
+pub struct JournalEntryCommented {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub author: Pubkey,
+    pub comment: String,
+    pub commented_at: i64,
+}
+
+#[event]
+pub struct NotebookCreated {
+    pub notebook: Pubkey,
+    pub admin: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct NotebookMemberSet {
+    pub notebook: Pubkey,
+    pub member: Pubkey,
+    pub role: NotebookRole,
+}
+
+#[event]
+pub struct NotebookMemberRemoved {
+    pub notebook: Pubkey,
+    pub member: Pubkey,
+}
+
+#[event]
+pub struct EntryTagged {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub tag: String,
+}
+
+#[event]
+pub struct EntryUntagged {
+    pub owner: Pubkey,
+    pub entry: Pubkey,
+    pub tag: String,
+}
+
+#[event]
+pub struct CategoryRegistryCreated {
+    pub owner: Pubkey,
+    pub strict: bool,
+}
+
+#[event]
+pub struct CategoryAdded {
+    pub owner: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct CategoryRemoved {
+    pub owner: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct UserProfileCreated {
+    pub owner: Pubkey,
+    pub created_at: i64,
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::CategoryAdded;
+use crate::instructions::manage_categories::ManageCategories;
+
+pub(crate) fn handler(
+    ctx: Context<ManageCategories>,
+    name: String,
+) -> Result<()> {
+    require!(!name.is_empty(), JournalError::CategoryEmpty);
+    require!(name.len() <= 50, JournalError::CategoryTooLong);
+
+    let category_registry = &mut ctx.accounts.category_registry;
+    require!(
+        category_registry.find(&name).is_none(),
+        JournalError::CategoryExists
+    );
+    category_registry.add(name.clone())?;
+
+    emit!(CategoryAdded {
+        owner: category_registry.owner,
+        name,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_CONTENT_LEN;
+use crate::error::JournalError;
+use crate::events::JournalEntryContentAppended;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for appending to an entry's inline content
+#[derive(Accounts)]
+#[instruction(chunk: String)]
+pub struct AppendContent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Grown by the chunk's length; Anchor refuses growth past 10 KiB in one
+    /// instruction
+    #[account(
+        mut,
+        has_one = owner,
+        realloc = JournalEntryState::grown_space(&journal_entry, chunk.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<AppendContent>,
+    chunk: String,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+
+    // Inline content comes first, so it is closed once pages exist
+    require!(journal_entry.content_pages == 0, JournalError::EntryHasPages);
+    require!(journal_entry.encrypted.is_none(), JournalError::EntryIsEncrypted);
+    require!(journal_entry.off_chain.is_none(), JournalError::EntryIsOffChain);
+    require!(
+        journal_entry.content.len() + chunk.len() <= MAX_ENTRY_CONTENT_LEN,
+        JournalError::EntryContentFull
+    );
+
+    journal_entry.content.push_str(&chunk);
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(JournalEntryContentAppended {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        page: None,
+        bytes: chunk.len() as u32,
+    });
+
+    Ok(())
+}
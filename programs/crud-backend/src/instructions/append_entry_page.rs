@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_PAGE_CONTENT_LEN;
+use crate::error::JournalError;
+use crate::events::JournalEntryContentAppended;
+use crate::state::entry_page::EntryPage;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for appending to an entry's last content page
+#[derive(Accounts)]
+#[instruction(page: u32, chunk: String)]
+pub struct AppendEntryPage<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        mut,
+        seeds = [b"entry_page", journal_entry.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+        realloc = EntryPage::space_for(entry_page.content.len() + chunk.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub entry_page: Account<'info, EntryPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<AppendEntryPage>,
+    page: u32,
+    chunk: String,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    require!(page + 1 == journal_entry.content_pages, JournalError::EntryPageOutOfOrder);
+
+    let entry_page = &mut ctx.accounts.entry_page;
+    require!(
+        entry_page.content.len() + chunk.len() <= MAX_ENTRY_PAGE_CONTENT_LEN,
+        JournalError::EntryPageFull
+    );
+    entry_page.content.push_str(&chunk);
+
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(JournalEntryContentAppended {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        page: Some(page),
+        bytes: chunk.len() as u32,
+    });
+
+    Ok(())
+}
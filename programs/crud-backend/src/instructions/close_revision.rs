@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::state::revision::Revision;
+
+/// Context for pruning a revision
+#[derive(Accounts)]
+#[instruction(revision_id: u64)]
+pub struct CloseRevision<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"revision", owner.key().as_ref(), revision_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = owner,
+        close = rent_payer,
+    )]
+    pub revision: Account<'info, Revision>,
+
+    /// Whoever paid for the revision: the owner, or the grantee whose
+    /// update recorded it
+    #[account(mut, address = revision.rent_payer)]
+    pub rent_payer: SystemAccount<'info>,
+}
+
+pub(crate) fn handler(
+    _ctx: Context<CloseRevision>,
+    revision_id: u64,
+) -> Result<()> {
+    // Account closure is handled by the 'close' constraint
+    msg!("Closed revision {}", revision_id);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_COMMENT_LEN;
+use crate::error::JournalError;
+use crate::events::JournalEntryCommented;
+use crate::instructions::shared::require_permission;
+use crate::state::entry_grant::{EntryGrant, GrantPermission};
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for commenting on an entry
+#[derive(Accounts)]
+pub struct CommentOnEntry<'info> {
+    /// The entry's owner, or a grantee
+    pub author: Signer<'info>,
+
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The author's grant - not needed when the owner comments
+    #[account(
+        seeds = [b"grant", journal_entry.key().as_ref(), author.key().as_ref()],
+        bump,
+    )]
+    pub grant: Option<Account<'info, EntryGrant>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CommentOnEntry>,
+    comment: String,
+) -> Result<()> {
+    require!(!comment.is_empty(), JournalError::CommentEmpty);
+    require!(comment.len() <= MAX_COMMENT_LEN, JournalError::CommentTooLong);
+
+    let journal_entry = &ctx.accounts.journal_entry;
+    let author = ctx.accounts.author.key();
+    if author != journal_entry.owner {
+        require_permission(ctx.accounts.grant.as_deref(), GrantPermission::Comment)?;
+    }
+
+    emit!(JournalEntryCommented {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        author,
+        comment,
+        commented_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
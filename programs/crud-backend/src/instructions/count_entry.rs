@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for back-filling an entry's category count
+#[derive(Accounts)]
+pub struct CountEntry<'info> {
+    pub owner: Signer<'info>,
+
+    /// Title-addressed or numbered
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub category_registry: Account<'info, CategoryRegistry>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CountEntry>,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    require!(!journal_entry.category_counted, JournalError::EntryAlreadyCounted);
+
+    ctx.accounts.category_registry.count(&journal_entry.category)?;
+    journal_entry.category_counted = true;
+    Ok(())
+}
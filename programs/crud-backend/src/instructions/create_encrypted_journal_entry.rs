@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_TITLE_LEN, title_seed};
+use crate::error::JournalError;
+use crate::events::JournalEntryCreated;
+use crate::instructions::shared::{recount_category, tag_new_entry};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::entry_index::EntryIndex;
+use crate::state::journal_entry::{EncryptedContent, JournalEntryState};
+use crate::state::user_profile::UserProfile;
+
+/// Context for creating an encrypted journal entry
+#[derive(Accounts)]
+#[instruction(title: String, category: String, encrypted: EncryptedContent, tags: Vec<String>)]
+pub struct CreateEncryptedJournalEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Addressed by title like a plaintext entry, sized for the ciphertext
+    #[account(
+        init,
+        seeds = [title_seed(&title).as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = JournalEntryState::encrypted_space_for(title.len(), encrypted.ciphertext.len(), category.len())
+            + JournalEntryState::space_for_tags(&tags),
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The user's last index page, created when the previous one fills up
+    #[account(
+        init_if_needed,
+        seeds = [b"index", owner.key().as_ref(), EntryIndex::page_of(user_profile.indexed_entries).to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + EntryIndex::INIT_SPACE,
+    )]
+    pub entry_index: Account<'info, EntryIndex>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateEncryptedJournalEntry<'info>>,
+    title: String,
+    category: String,
+    encrypted: EncryptedContent,
+    tags: Vec<String>,
+) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+    encrypted.validate()?;
+
+    recount_category(
+        &ctx.accounts.user_profile,
+        ctx.accounts.category_registry.as_deref_mut(),
+        &mut ctx.accounts.journal_entry,
+        &category,
+    )?;
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.owner = *ctx.accounts.owner.key;
+    journal_entry.title = title.clone();
+    journal_entry.content = String::new(); // The ciphertext takes its place
+    journal_entry.category = category;
+    journal_entry.is_archived = false;
+    journal_entry.entry_id = None;
+    journal_entry.encrypted = Some(encrypted);
+
+    let clock = Clock::get()?;
+    journal_entry.created_at = clock.unix_timestamp;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_entries += 1;
+    journal_entry.index_page = Some(ctx.accounts.entry_index.push(
+        user_profile,
+        journal_entry.key(),
+    ));
+
+    emit!(JournalEntryCreated {
+        owner: journal_entry.owner,
+        title,
+        category: journal_entry.category.clone(),
+        created_at: journal_entry.created_at,
+        entry_id: None,
+    });
+
+    tag_new_entry(
+        ctx.remaining_accounts,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        &mut ctx.accounts.journal_entry,
+        tags,
+    )
+}
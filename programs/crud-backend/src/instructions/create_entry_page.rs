@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_PAGE_CONTENT_LEN;
+use crate::error::JournalError;
+use crate::events::JournalEntryContentAppended;
+use crate::state::entry_page::EntryPage;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for starting an entry's next content page
+#[derive(Accounts)]
+#[instruction(page: u32, chunk: String)]
+pub struct CreateEntryPage<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Sized for its first chunk, grown by `append_entry_page`
+    #[account(
+        init,
+        seeds = [b"entry_page", journal_entry.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = EntryPage::space_for(chunk.len()),
+    )]
+    pub entry_page: Account<'info, EntryPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CreateEntryPage>,
+    page: u32,
+    chunk: String,
+) -> Result<()> {
+    require!(chunk.len() <= MAX_ENTRY_PAGE_CONTENT_LEN, JournalError::EntryPageFull);
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    require!(page == journal_entry.content_pages, JournalError::EntryPageOutOfOrder);
+    require!(journal_entry.encrypted.is_none(), JournalError::EntryIsEncrypted);
+    require!(journal_entry.off_chain.is_none(), JournalError::EntryIsOffChain);
+    journal_entry.content_pages += 1;
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    let entry_page = &mut ctx.accounts.entry_page;
+    entry_page.entry = journal_entry.key();
+    entry_page.page = page;
+    entry_page.content = chunk;
+
+    emit!(JournalEntryContentAppended {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        page: Some(page),
+        bytes: entry_page.content.len() as u32,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_CONTENT_LEN, MAX_TITLE_LEN, title_seed};
+use crate::error::JournalError;
+use crate::events::JournalEntryCreated;
+use crate::instructions::shared::{recount_category, tag_new_entry};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::entry_index::EntryIndex;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::user_profile::UserProfile;
+
+/// Context for creating a new journal entry
+#[derive(Accounts)]
+#[instruction(title: String, content: String, category: String, tags: Vec<String>)]
+pub struct CreateJournalEntry<'info> {
+    /// The user creating the entry
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// New journal entry account, sized for exactly this title, content and
+    /// category so short entries pay less rent
+    #[account(
+        init,
+        seeds = [title_seed(&title).as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = JournalEntryState::space_for(title.len(), content.len(), category.len())
+            + JournalEntryState::space_for_tags(&tags),
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// User profile for tracking stats
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The user's last index page, created when the previous one fills up
+    #[account(
+        init_if_needed,
+        seeds = [b"index", owner.key().as_ref(), EntryIndex::page_of(user_profile.indexed_entries).to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + EntryIndex::INIT_SPACE,
+    )]
+    pub entry_index: Account<'info, EntryIndex>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateJournalEntry<'info>>,
+    title: String,
+    content: String,
+    category: String,
+    tags: Vec<String>,
+) -> Result<()> {
+    // Basic input validation to prevent spam and ensure data quality
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(content.len() <= MAX_CONTENT_LEN, JournalError::ContentTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+
+    // Count the entry in the owner's category registry, if they keep one
+    recount_category(
+        &ctx.accounts.user_profile,
+        ctx.accounts.category_registry.as_deref_mut(),
+        &mut ctx.accounts.journal_entry,
+        &category,
+    )?;
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    
+    // Set the basic entry data
+    journal_entry.owner = *ctx.accounts.owner.key;
+    journal_entry.title = title.clone();
+    journal_entry.content = content;
+    journal_entry.category = category;
+    journal_entry.is_archived = false; // New entries are active by default
+    journal_entry.entry_id = None; // Addressed by title
+    
+    // Use Solana's clock for consistent timestamps across the network
+    let clock = Clock::get()?;
+    journal_entry.created_at = clock.unix_timestamp;
+    journal_entry.updated_at = clock.unix_timestamp;
+    
+    // Update the user's entry counter
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_entries += 1;
+
+    // List the entry on the user's last index page
+    journal_entry.index_page = Some(ctx.accounts.entry_index.push(
+        user_profile,
+        journal_entry.key(),
+    ));
+    
+    // Emit event for frontend to listen and update UI accordingly
+    emit!(JournalEntryCreated {
+        owner: journal_entry.owner,
+        title,
+        category: journal_entry.category.clone(),
+        created_at: journal_entry.created_at,
+        entry_id: None,
+    });
+
+    // List it under its tags
+    tag_new_entry(
+        ctx.remaining_accounts,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        &mut ctx.accounts.journal_entry,
+        tags,
+    )
+}
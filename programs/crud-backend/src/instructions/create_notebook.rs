@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_NOTEBOOK_NAME_LEN, title_seed};
+use crate::error::JournalError;
+use crate::events::NotebookCreated;
+use crate::state::notebook::Notebook;
+
+/// Context for creating a notebook
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateNotebook<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Addressed by admin and name, like title-addressed entries
+    #[account(
+        init,
+        seeds = [b"notebook", admin.key().as_ref(), title_seed(&name).as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + Notebook::INIT_SPACE,
+    )]
+    pub notebook: Account<'info, Notebook>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CreateNotebook>,
+    name: String,
+) -> Result<()> {
+    require!(!name.is_empty(), JournalError::NotebookNameEmpty);
+    require!(name.len() <= MAX_NOTEBOOK_NAME_LEN, JournalError::NotebookNameTooLong);
+
+    let notebook = &mut ctx.accounts.notebook;
+    notebook.admin = ctx.accounts.admin.key();
+    notebook.name = name;
+    notebook.members = Vec::new();
+    notebook.next_entry_id = 0;
+    notebook.created_at = Clock::get()?.unix_timestamp;
+
+    emit!(NotebookCreated {
+        notebook: notebook.key(),
+        admin: notebook.admin,
+        name: notebook.name.clone(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_CONTENT_LEN, MAX_TITLE_LEN};
+use crate::error::JournalError;
+use crate::events::JournalEntryCreated;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::notebook::{Notebook, NotebookRole};
+
+/// Context for creating a notebook entry
+#[derive(Accounts)]
+#[instruction(title: String, content: String, category: String)]
+pub struct CreateNotebookEntry<'info> {
+    /// Pays the rent, and is recorded as the entry's `rent_payer`; must be
+    /// an editor or admin
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    /// Hands out the id
+    #[account(mut)]
+    pub notebook: Account<'info, Notebook>,
+
+    /// New entry at the notebook's next id
+    #[account(
+        init,
+        seeds = [b"entry", notebook.key().as_ref(), notebook.next_entry_id.to_le_bytes().as_ref()],
+        bump,
+        payer = member,
+        space = JournalEntryState::space_for(title.len(), content.len(), category.len()) + 32,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<CreateNotebookEntry>,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    // Same validation as create
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(content.len() <= MAX_CONTENT_LEN, JournalError::ContentTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+
+    let notebook = &mut ctx.accounts.notebook;
+    notebook.require_role(&ctx.accounts.member.key(), NotebookRole::Editor)?;
+    let entry_id = notebook.next_entry_id;
+    notebook.next_entry_id += 1;
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.owner = notebook.key();
+    journal_entry.title = title.clone();
+    journal_entry.content = content;
+    journal_entry.category = category;
+    journal_entry.is_archived = false;
+    journal_entry.entry_id = Some(entry_id);
+    journal_entry.index_page = None; // Listed by id, not in a user's index
+    journal_entry.rent_payer = Some(ctx.accounts.member.key());
+
+    let clock = Clock::get()?;
+    journal_entry.created_at = clock.unix_timestamp;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(JournalEntryCreated {
+        owner: journal_entry.owner,
+        title,
+        category: journal_entry.category.clone(),
+        created_at: journal_entry.created_at,
+        entry_id: Some(entry_id),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::JournalEntryDeleted;
+use crate::instructions::shared::{index_page, release_tag_index, tag_indexes, uncount_category};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::entry_index::EntryIndex;
+use crate::state::entry_page::EntryPage;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::user_profile::UserProfile;
+
+/// Context for deleting a journal entry
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct DeleteJournalEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The entry to delete - will be closed and rent refunded to owner
+    /// Title-addressed, at `title`'s address, or numbered, at its id's
+    #[account(
+        mut,
+        has_one = owner, // CRITICAL: Prevents unauthorized deletions
+        address = journal_entry.address_for(&title),
+        close = owner,   // Automatically refunds rent to owner
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// User profile to update the entry count
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The index page listing the entry - needed unless the entry predates
+    /// the index
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub entry_index: Option<Account<'info, EntryIndex>>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeleteJournalEntry<'info>>,
+    _title: String, // Checked against the entry's address
+) -> Result<()> {
+    let journal_entry = &ctx.accounts.journal_entry;
+
+    // A grant outliving the entry would apply to whatever is created at
+    // the same address next
+    require!(journal_entry.grants == 0, JournalError::EntryHasGrants);
+
+    // Close the overflow pages along with the entry
+    let pages = journal_entry.content_pages as usize;
+    require!(
+        ctx.remaining_accounts.len() == pages + journal_entry.tags.len(),
+        JournalError::EntryPageAccountsMismatch
+    );
+    let (page_accounts, tag_accounts) = ctx.remaining_accounts.split_at(pages);
+    for (page, account) in page_accounts.iter().enumerate() {
+        let entry_page = Account::<EntryPage>::try_from(account)?;
+        require!(
+            entry_page.entry == journal_entry.key() && entry_page.page == page as u32,
+            JournalError::EntryPageAccountsMismatch
+        );
+        entry_page.close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    // Take the entry off its tags' lists
+    for mut tag_index in tag_indexes(tag_accounts, journal_entry)? {
+        tag_index.replace(&journal_entry.key(), Pubkey::default())?;
+        release_tag_index(tag_index, ctx.accounts.owner.to_account_info())?;
+    }
+    
+    // The category loses the entry
+    uncount_category(
+        &ctx.accounts.user_profile,
+        ctx.accounts.category_registry.as_deref_mut(),
+        journal_entry,
+    )?;
+    
+    // Update user's entry counter before deletion
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_entries = user_profile.total_entries.saturating_sub(1); // Prevent underflow
+
+    // Leave a gap in the index so the other entries keep their place
+    if let Some(page) = journal_entry.index_page {
+        let entry_index = index_page(&mut ctx.accounts.entry_index, page)?;
+        entry_index.replace(&journal_entry.key(), Pubkey::default())?;
+    }
+    
+    // Emit event before the account is closed
+    emit!(JournalEntryDeleted {
+        owner: journal_entry.owner,
+        title: journal_entry.title.clone(),
+    });
+    
+    // Account closure is handled automatically by Anchor's 'close' constraint
+    Ok(())
+}
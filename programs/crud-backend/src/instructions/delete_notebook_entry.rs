@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::JournalEntryDeleted;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::notebook::{Notebook, NotebookRole};
+
+/// Context for deleting a notebook entry
+#[derive(Accounts)]
+pub struct DeleteNotebookEntry<'info> {
+    /// Must be an admin
+    pub member: Signer<'info>,
+
+    pub notebook: Account<'info, Notebook>,
+
+    #[account(
+        mut,
+        constraint = journal_entry.owner == notebook.key() @ JournalError::EntryNotInNotebook,
+        close = rent_payer,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The member who created the entry, who gets the rent back
+    #[account(mut, address = journal_entry.rent_holder())]
+    pub rent_payer: SystemAccount<'info>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<DeleteNotebookEntry>,
+) -> Result<()> {
+    ctx.accounts
+        .notebook
+        .require_role(&ctx.accounts.member.key(), NotebookRole::Admin)?;
+
+    let journal_entry = &ctx.accounts.journal_entry;
+    // Notebook entries can't grow pages, but stay safe if that changes
+    require!(journal_entry.content_pages == 0, JournalError::EntryHasPages);
+
+    emit!(JournalEntryDeleted {
+        owner: journal_entry.owner,
+        title: journal_entry.title.clone(),
+    });
+
+    // Account closure is handled by the 'close' constraint
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::responses::{EntryGrantsResponse, GrantedAccess};
+use crate::state::entry_grant::{EntryGrant, EntryGrantList};
+
+/// Context for listing an entry's grants
+#[derive(Accounts)]
+pub struct GetEntryGrants<'info> {
+    /// Grants are as public as the entries they're for, so anyone may list
+    /// them
+    pub entry_grants: Account<'info, EntryGrantList>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetEntryGrants<'info>>,
+) -> Result<EntryGrantsResponse> {
+    let entry_grants = &ctx.accounts.entry_grants;
+    require!(
+        entry_grants.grantees.len() == ctx.remaining_accounts.len(),
+        JournalError::EntryGrantAccountsMismatch
+    );
+
+    let mut grants = Vec::with_capacity(ctx.remaining_accounts.len());
+    for (grantee, account) in entry_grants.grantees.iter().zip(ctx.remaining_accounts) {
+        let grant = Account::<EntryGrant>::try_from(account)?;
+        require!(
+            grant.entry == entry_grants.entry && grant.grantee == *grantee,
+            JournalError::EntryGrantAccountsMismatch
+        );
+        grants.push(GrantedAccess {
+            grantee: *grantee,
+            permission: grant.permission,
+            granted_at: grant.granted_at,
+        });
+    }
+
+    Ok(EntryGrantsResponse {
+        entry: entry_grants.entry,
+        grants,
+    })
+}
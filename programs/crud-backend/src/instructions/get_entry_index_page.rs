@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ENTRY_INDEX_PAGE_SIZE;
+use crate::error::JournalError;
+use crate::responses::{EntryIndexPageResponse, IndexedEntry};
+use crate::state::entry_index::EntryIndex;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::user_profile::UserProfile;
+
+/// Context for listing a page of a user's entries
+#[derive(Accounts)]
+pub struct GetEntryIndexPage<'info> {
+    /// CHECK: Only identifies whose entries to list; entries are public, so
+    /// anyone may list them
+    pub owner: UncheckedAccount<'info>,
+
+    /// Tells whether more pages follow
+    #[account(
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(has_one = owner)]
+    pub entry_index: Account<'info, EntryIndex>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetEntryIndexPage<'info>>,
+) -> Result<EntryIndexPageResponse> {
+    let entry_index = &ctx.accounts.entry_index;
+    let live = entry_index
+        .entries
+        .iter()
+        .filter(|entry| **entry != Pubkey::default());
+    require!(
+        live.clone().count() == ctx.remaining_accounts.len(),
+        JournalError::EntryIndexAccountsMismatch
+    );
+
+    let mut entries = Vec::with_capacity(ctx.remaining_accounts.len());
+    for (key, account) in live.zip(ctx.remaining_accounts) {
+        require_keys_eq!(*key, account.key(), JournalError::EntryIndexAccountsMismatch);
+        let journal_entry = Account::<JournalEntryState>::try_from(account)?;
+        entries.push(IndexedEntry {
+            entry: *key,
+            is_archived: journal_entry.is_archived,
+        });
+    }
+
+    let next_page = entry_index.page + 1;
+    let next_slot = u64::from(next_page) * ENTRY_INDEX_PAGE_SIZE as u64;
+    let has_next = next_slot < ctx.accounts.user_profile.indexed_entries;
+    Ok(EntryIndexPageResponse {
+        page: entry_index.page,
+        entries,
+        next_page: has_next.then_some(next_page),
+    })
+}
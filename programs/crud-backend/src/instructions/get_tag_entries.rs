@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{TAG_INDEX_PAGE_SIZE, title_seed};
+use crate::instructions::shared::initialized;
+use crate::responses::TagEntriesResponse;
+use crate::state::tag::{TagIndex, TagState};
+
+/// Context for listing a page of a tag's entries
+#[derive(Accounts)]
+#[instruction(tag: String, page: u32)]
+pub struct GetTagEntries<'info> {
+    /// CHECK: Only identifies whose entries to list; entries are public, so
+    /// anyone may list them
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: The tag's `TagState`, telling whether more pages follow; read
+    /// in the handler, as it doesn't exist for a tag never used
+    #[account(
+        seeds = [b"tag", owner.key().as_ref(), title_seed(&tag).as_ref()],
+        bump,
+    )]
+    pub tag_state: UncheckedAccount<'info>,
+
+    /// CHECK: The page to list; read in the handler, as it may not exist
+    #[account(
+        seeds = [b"tag", owner.key().as_ref(), title_seed(&tag).as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub tag_index: UncheckedAccount<'info>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<GetTagEntries>,
+    tag: String,
+    page: u32,
+) -> Result<TagEntriesResponse> {
+    let tagged = initialized::<TagState>(&ctx.accounts.tag_state)?
+        .map_or(0, |tag_state| tag_state.tagged);
+    let entries = initialized::<TagIndex>(&ctx.accounts.tag_index)?
+        .map_or(vec![], |tag_index| {
+            tag_index
+                .entries
+                .iter()
+                .filter(|entry| **entry != Pubkey::default())
+                .copied()
+                .collect()
+        });
+
+    let next_page = page + 1;
+    let next_slot = u64::from(next_page) * TAG_INDEX_PAGE_SIZE as u64;
+    Ok(TagEntriesResponse {
+        tag,
+        page,
+        entries,
+        next_page: (next_slot < tagged).then_some(next_page),
+    })
+}
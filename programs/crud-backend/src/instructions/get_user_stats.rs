@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::responses::UserStatsResponse;
+use crate::state::user_profile::UserProfile;
+
+/// Context for getting user statistics
+#[derive(Accounts)]
+pub struct GetUserStats<'info> {
+    pub owner: Signer<'info>,
+
+    /// Read-only access to user profile
+    #[account(
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<GetUserStats>,
+) -> Result<UserStatsResponse> {
+    let user_profile = &ctx.accounts.user_profile;
+    
+    Ok(UserStatsResponse {
+        total_entries: user_profile.total_entries,
+        member_since: user_profile.created_at,
+    })
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_GRANTS;
+use crate::error::JournalError;
+use crate::events::EntryAccessGranted;
+use crate::state::entry_grant::{EntryGrant, EntryGrantList, GrantPermission};
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for sharing an entry
+#[derive(Accounts)]
+#[instruction(grantee: Pubkey)]
+pub struct GrantEntryAccess<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The entry to share - counts its grants
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Lists the entry's grantees, created with the first grant
+    #[account(
+        init_if_needed,
+        seeds = [b"grants", journal_entry.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + EntryGrantList::INIT_SPACE,
+    )]
+    pub entry_grants: Account<'info, EntryGrantList>,
+
+    /// Created on the first grant to `grantee`, updated after that
+    #[account(
+        init_if_needed,
+        seeds = [b"grant", journal_entry.key().as_ref(), grantee.as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + EntryGrant::INIT_SPACE,
+    )]
+    pub grant: Account<'info, EntryGrant>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<GrantEntryAccess>,
+    grantee: Pubkey,
+    permission: GrantPermission,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let entry_grants = &mut ctx.accounts.entry_grants;
+    let grant = &mut ctx.accounts.grant;
+
+    if entry_grants.entry == Pubkey::default() {
+        entry_grants.owner = journal_entry.owner;
+        entry_grants.entry = journal_entry.key();
+    }
+    // A fresh grant account; an existing one keeps its place in the list
+    if grant.entry == Pubkey::default() {
+        require!(
+            entry_grants.grantees.len() < MAX_ENTRY_GRANTS,
+            JournalError::TooManyGrants
+        );
+        entry_grants.grantees.push(grantee);
+        journal_entry.grants += 1;
+    }
+
+    grant.owner = journal_entry.owner;
+    grant.entry = journal_entry.key();
+    grant.grantee = grantee;
+    grant.permission = permission;
+    grant.granted_at = Clock::get()?.unix_timestamp;
+
+    emit!(EntryAccessGranted {
+        owner: grant.owner,
+        entry: grant.entry,
+        grantee,
+        permission,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::events::CategoryRegistryCreated;
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::user_profile::UserProfile;
+
+/// Context for starting a category registry
+#[derive(Accounts)]
+pub struct InitializeCategoryRegistry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Records that the owner's entries are counted from now on
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        init,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + CategoryRegistry::INIT_SPACE,
+    )]
+    pub category_registry: Account<'info, CategoryRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<InitializeCategoryRegistry>,
+    strict: bool,
+) -> Result<()> {
+    let category_registry = &mut ctx.accounts.category_registry;
+    category_registry.owner = ctx.accounts.owner.key();
+    category_registry.strict = strict;
+    category_registry.categories = Vec::new();
+    ctx.accounts.user_profile.category_registry = true;
+
+    emit!(CategoryRegistryCreated {
+        owner: category_registry.owner,
+        strict,
+    });
+
+    Ok(())
+}
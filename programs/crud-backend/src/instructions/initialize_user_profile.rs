@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::events::UserProfileCreated;
+use crate::state::user_profile::UserProfile;
+
+/// Context for initializing a user profile
+#[derive(Accounts)]
+pub struct InitializeUserProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// User profile account - one per user
+    #[account(
+        init,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + UserProfile::INIT_SPACE,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<InitializeUserProfile>,
+) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    
+    user_profile.owner = *ctx.accounts.owner.key;
+    user_profile.total_entries = 0;
+    user_profile.next_entry_id = 0;
+    user_profile.indexed_entries = 0;
+    user_profile.next_revision_id = 0;
+    user_profile.category_registry = false;
+    
+    let clock = Clock::get()?;
+    user_profile.created_at = clock.unix_timestamp;
+    
+    emit!(UserProfileCreated {
+        owner: user_profile.owner,
+        created_at: user_profile.created_at,
+    });
+    
+    Ok(())
+}
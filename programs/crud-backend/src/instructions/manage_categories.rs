@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+use crate::state::category_registry::CategoryRegistry;
+
+/// Context for changing a category registry
+#[derive(Accounts)]
+pub struct ManageCategories<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub category_registry: Account<'info, CategoryRegistry>,
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::state::notebook::Notebook;
+
+/// Context for changing a notebook's members
+/// Roles are checked by the instruction
+#[derive(Accounts)]
+pub struct ManageNotebook<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub notebook: Account<'info, Notebook>,
+}
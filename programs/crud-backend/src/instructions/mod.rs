@@ -0,0 +1,122 @@
+pub mod add_category;
+
+pub mod append_content;
+pub use append_content::*;
+
+pub mod append_entry_page;
+pub use append_entry_page::*;
+
+pub mod close_revision;
+pub use close_revision::*;
+
+pub mod comment_on_entry;
+pub use comment_on_entry::*;
+
+pub mod count_entry;
+pub use count_entry::*;
+
+pub mod create_encrypted_journal_entry;
+pub use create_encrypted_journal_entry::*;
+
+pub mod create_entry_page;
+pub use create_entry_page::*;
+
+pub mod create_journal_entry;
+pub use create_journal_entry::*;
+
+pub mod create_notebook;
+pub use create_notebook::*;
+
+pub mod create_notebook_entry;
+pub use create_notebook_entry::*;
+
+pub mod create_numbered_journal_entry;
+pub use create_numbered_journal_entry::*;
+
+pub mod create_off_chain_journal_entry;
+pub use create_off_chain_journal_entry::*;
+
+pub mod delete_journal_entry;
+pub use delete_journal_entry::*;
+
+pub mod delete_notebook_entry;
+pub use delete_notebook_entry::*;
+
+pub mod get_entry_grants;
+pub use get_entry_grants::*;
+
+pub mod get_entry_index_page;
+pub use get_entry_index_page::*;
+
+pub mod get_tag_entries;
+pub use get_tag_entries::*;
+
+pub mod get_user_stats;
+pub use get_user_stats::*;
+
+pub mod grant_entry_access;
+pub use grant_entry_access::*;
+
+pub mod initialize_category_registry;
+pub use initialize_category_registry::*;
+
+pub mod initialize_user_profile;
+pub use initialize_user_profile::*;
+
+pub mod manage_categories;
+pub use manage_categories::*;
+
+pub mod manage_notebook;
+pub use manage_notebook::*;
+
+pub mod remove_category;
+
+pub mod remove_notebook_member;
+
+pub mod rename_journal_entry;
+pub use rename_journal_entry::*;
+
+pub mod restore_revision;
+pub use restore_revision::*;
+
+pub mod revoke_entry_access;
+pub use revoke_entry_access::*;
+
+pub mod set_category_strict;
+
+pub mod set_notebook_member;
+
+pub mod shared;
+
+pub mod tag_entry;
+pub use tag_entry::*;
+
+pub mod toggle_archive_entry;
+pub use toggle_archive_entry::*;
+
+pub mod toggle_archive_entry_as_grantee;
+pub use toggle_archive_entry_as_grantee::*;
+
+pub mod untag_entry;
+pub use untag_entry::*;
+
+pub mod update_encrypted_journal_entry;
+pub use update_encrypted_journal_entry::*;
+
+pub mod update_journal_entry;
+pub use update_journal_entry::*;
+
+pub mod update_journal_entry_as_grantee;
+pub use update_journal_entry_as_grantee::*;
+
+pub mod update_notebook_entry;
+pub use update_notebook_entry::*;
+
+pub mod update_off_chain_journal_entry;
+pub use update_off_chain_journal_entry::*;
+
+pub mod upgrade_entry;
+pub use upgrade_entry::*;
+
+pub mod upgrade_user_profile;
+pub use upgrade_user_profile::*;
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::CategoryRemoved;
+use crate::instructions::manage_categories::ManageCategories;
+
+pub(crate) fn handler(
+    ctx: Context<ManageCategories>,
+    name: String,
+) -> Result<()> {
+    let category_registry = &mut ctx.accounts.category_registry;
+    let position = category_registry
+        .find(&name)
+        .ok_or(JournalError::CategoryNotFound)?;
+    require!(
+        category_registry.categories[position].entries == 0,
+        JournalError::CategoryInUse
+    );
+    category_registry.categories.remove(position);
+
+    emit!(CategoryRemoved {
+        owner: category_registry.owner,
+        name,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::NotebookMemberRemoved;
+use crate::instructions::manage_notebook::ManageNotebook;
+use crate::state::notebook::NotebookRole;
+
+pub(crate) fn handler(
+    ctx: Context<ManageNotebook>,
+    member: Pubkey,
+) -> Result<()> {
+    let notebook = &mut ctx.accounts.notebook;
+    require_keys_neq!(member, notebook.admin, JournalError::NotebookAdminFixed);
+    let signer = ctx.accounts.signer.key();
+    if signer != member {
+        notebook.require_role(&signer, NotebookRole::Admin)?;
+    }
+
+    let members = notebook.members.len();
+    notebook.members.retain(|m| m.member != member);
+    require!(notebook.members.len() < members, JournalError::NotNotebookMember);
+
+    emit!(NotebookMemberRemoved {
+        notebook: notebook.key(),
+        member,
+    });
+
+    Ok(())
+}
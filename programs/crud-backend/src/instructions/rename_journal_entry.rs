@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_TITLE_LEN, title_seed};
+use crate::error::JournalError;
+use crate::events::JournalEntryRenamed;
+use crate::instructions::shared::{index_page, tag_indexes};
+use crate::state::entry_index::EntryIndex;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for renaming a journal entry
+#[derive(Accounts)]
+#[instruction(old_title: String, new_title: String)]
+pub struct RenameJournalEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The entry under its current title - closed once its data has moved
+    #[account(
+        mut,
+        seeds = [title_seed(&old_title).as_ref(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The same entry under its new title - creating it fails if the owner
+    /// already has an entry with that title, including this one
+    /// Sized for the moved content under the new title
+    #[account(
+        init,
+        seeds = [title_seed(&new_title).as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = journal_entry.space_with_title(new_title.len()),
+    )]
+    pub new_journal_entry: Account<'info, JournalEntryState>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The index page listing the entry - needed unless the entry predates
+    /// the index
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub entry_index: Option<Account<'info, EntryIndex>>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RenameJournalEntry<'info>>,
+    old_title: String,
+    new_title: String,
+) -> Result<()> {
+    // Same title rules as create
+    require!(new_title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(!new_title.is_empty(), JournalError::TitleEmpty);
+
+    let old_entry = &ctx.accounts.journal_entry;
+    let new_entry = &mut ctx.accounts.new_journal_entry;
+
+    // Pages are addressed by the entry, so they can't follow it
+    require!(old_entry.content_pages == 0, JournalError::EntryHasPages);
+    // Grants are keyed by the entry's address too
+    require!(old_entry.grants == 0, JournalError::EntryHasGrants);
+
+    // Move everything but the title over to the new account
+    new_entry.owner = old_entry.owner;
+    new_entry.title = new_title.clone();
+    new_entry.content = old_entry.content.clone();
+    new_entry.category = old_entry.category.clone();
+    new_entry.category_counted = old_entry.category_counted;
+    new_entry.encrypted = old_entry.encrypted.clone();
+    new_entry.off_chain = old_entry.off_chain.clone();
+
+    // So do the tags, with the new address in their lists
+    new_entry.tags = old_entry.tags.clone();
+    for mut tag_index in tag_indexes(ctx.remaining_accounts, old_entry)? {
+        tag_index.replace(&old_entry.key(), new_entry.key())?;
+        tag_index.exit(&crate::ID)?;
+    }
+
+    // The history follows the entry to its new address
+    new_entry.history = old_entry.history;
+    new_entry.last_revision = old_entry.last_revision;
+    new_entry.revision_hash = old_entry.revision_hash;
+    new_entry.is_archived = old_entry.is_archived;
+    new_entry.created_at = old_entry.created_at;
+    new_entry.entry_id = None;
+
+    // The new address takes the old one's place in the index
+    new_entry.index_page = old_entry.index_page;
+    if let Some(page) = old_entry.index_page {
+        let entry_index = index_page(&mut ctx.accounts.entry_index, page)?;
+        entry_index.replace(&old_entry.key(), new_entry.key())?;
+    }
+
+    // A rename counts as a modification
+    let clock = Clock::get()?;
+    new_entry.updated_at = clock.unix_timestamp;
+
+    // The entry count is unchanged: one entry closes as the other opens
+    emit!(JournalEntryRenamed {
+        owner: new_entry.owner,
+        old_title,
+        new_title,
+        updated_at: new_entry.updated_at,
+    });
+
+    Ok(())
+}
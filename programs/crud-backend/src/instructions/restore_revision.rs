@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::events::JournalEntryRestored;
+use crate::instructions::shared::{record_revision, recount_category};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::revision::Revision;
+use crate::state::user_profile::UserProfile;
+
+/// Context for rolling an entry back to a revision
+#[derive(Accounts)]
+#[instruction(revision_id: u64)]
+pub struct RestoreRevision<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Resized to the restored version
+    #[account(
+        mut,
+        has_one = owner,
+        realloc = restored_revision.restored_space(&journal_entry),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The version to go back to
+    #[account(
+        seeds = [b"revision", owner.key().as_ref(), revision_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub restored_revision: Account<'info, Revision>,
+
+    /// Hands out the revision id
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The entry as it was before this update
+    #[account(
+        init,
+        seeds = [b"revision", owner.key().as_ref(), user_profile.next_revision_id.to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = Revision::space_for(&journal_entry),
+    )]
+    pub revision: Account<'info, Revision>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<RestoreRevision>,
+    revision_id: u64,
+) -> Result<()> {
+    let restored = &ctx.accounts.restored_revision;
+    let journal_entry = &ctx.accounts.journal_entry;
+    require!(
+        journal_entry.history == Some(restored.history),
+        JournalError::RevisionNotOfEntry
+    );
+    // Replacing the inline content would orphan the pages after it, as in
+    // `overwrite_entry`
+    require!(journal_entry.content_pages == 0, JournalError::EntryHasPages);
+
+    record_revision(
+        &mut ctx.accounts.journal_entry,
+        &mut ctx.accounts.user_profile,
+        &mut ctx.accounts.revision,
+        ctx.accounts.owner.key(),
+    )?;
+    recount_category(
+        &ctx.accounts.user_profile,
+        ctx.accounts.category_registry.as_deref_mut(),
+        &mut ctx.accounts.journal_entry,
+        &ctx.accounts.restored_revision.category,
+    )?;
+
+    let restored = &ctx.accounts.restored_revision;
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    if journal_entry.entry_id.is_some() {
+        journal_entry.title = restored.title.clone();
+    }
+    journal_entry.content = restored.content.clone();
+    journal_entry.category = restored.category.clone();
+    journal_entry.encrypted = restored.encrypted.clone();
+    journal_entry.off_chain = restored.off_chain.clone();
+
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(JournalEntryRestored {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        revision: revision_id,
+        updated_at: journal_entry.updated_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::events::EntryAccessRevoked;
+use crate::state::entry_grant::{EntryGrant, EntryGrantList};
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for withdrawing a grant
+#[derive(Accounts)]
+#[instruction(grantee: Pubkey)]
+pub struct RevokeEntryAccess<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Only its grant count changes; deletes and renames wait for it to
+    /// reach zero, so the entry is still there
+    #[account(
+        mut,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        mut,
+        seeds = [b"grants", journal_entry.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub entry_grants: Account<'info, EntryGrantList>,
+
+    /// Closed, and rent refunded to the owner
+    #[account(
+        mut,
+        seeds = [b"grant", journal_entry.key().as_ref(), grantee.as_ref()],
+        bump,
+        has_one = owner,
+        close = owner,
+    )]
+    pub grant: Account<'info, EntryGrant>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<RevokeEntryAccess>,
+    grantee: Pubkey,
+) -> Result<()> {
+    let entry_grants = &mut ctx.accounts.entry_grants;
+    entry_grants.grantees.retain(|key| *key != grantee);
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    journal_entry.grants -= 1;
+
+    // Account closure is handled by the 'close' constraint
+    emit!(EntryAccessRevoked {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        grantee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::manage_categories::ManageCategories;
+
+pub(crate) fn handler(
+    ctx: Context<ManageCategories>,
+    strict: bool,
+) -> Result<()> {
+    ctx.accounts.category_registry.strict = strict;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_NOTEBOOK_MEMBERS;
+use crate::error::JournalError;
+use crate::events::NotebookMemberSet;
+use crate::instructions::manage_notebook::ManageNotebook;
+use crate::state::notebook::{NotebookMember, NotebookRole};
+
+pub(crate) fn handler(
+    ctx: Context<ManageNotebook>,
+    member: Pubkey,
+    role: NotebookRole,
+) -> Result<()> {
+    let notebook = &mut ctx.accounts.notebook;
+    notebook.require_role(&ctx.accounts.signer.key(), NotebookRole::Admin)?;
+    // The admin's role is fixed
+    require_keys_neq!(member, notebook.admin, JournalError::NotebookAdminFixed);
+
+    match notebook.members.iter_mut().find(|m| m.member == member) {
+        Some(existing) => existing.role = role,
+        None => {
+            require!(
+                notebook.members.len() < MAX_NOTEBOOK_MEMBERS,
+                JournalError::TooManyNotebookMembers
+            );
+            notebook.members.push(NotebookMember { member, role });
+        }
+    }
+
+    emit!(NotebookMemberSet {
+        notebook: notebook.key(),
+        member,
+        role,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,419 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::constants::{MAX_ENTRY_TAGS, MAX_TAG_LEN, title_seed};
+use crate::error::JournalError;
+use crate::events::{
+    EntryTagged,
+    JournalEntryArchived,
+    JournalEntryRevised,
+    JournalEntryUnarchived,
+    JournalEntryUpdated,
+};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::entry_grant::{EntryGrant, GrantPermission};
+use crate::state::entry_index::EntryIndex;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::revision::Revision;
+use crate::state::tag::{TagIndex, TagState};
+use crate::state::user_profile::UserProfile;
+
+/// Snapshots an entry into `revision` before it is overwritten and links it
+/// into the entry's hash chain
+pub(crate) fn record_revision(
+    journal_entry: &mut Account<JournalEntryState>,
+    user_profile: &mut UserProfile,
+    revision: &mut Revision,
+    rent_payer: Pubkey,
+) -> Result<()> {
+    let id = user_profile.next_revision_id;
+    user_profile.next_revision_id += 1;
+
+    revision.owner = journal_entry.owner;
+    revision.entry = journal_entry.key();
+    revision.id = id;
+    revision.history = *journal_entry.history.get_or_insert(id); // Started by the first revision
+    revision.previous = journal_entry.last_revision;
+    revision.previous_hash = journal_entry.revision_hash;
+    revision.title = journal_entry.title.clone();
+    revision.content = journal_entry.content.clone();
+    revision.category = journal_entry.category.clone();
+    revision.encrypted = journal_entry.encrypted.clone();
+    revision.off_chain = journal_entry.off_chain.clone();
+    revision.updated_at = journal_entry.updated_at;
+    revision.replaced_at = Clock::get()?.unix_timestamp;
+    revision.rent_payer = rent_payer;
+
+    journal_entry.last_revision = Some(id);
+    journal_entry.revision_hash = revision.hash();
+
+    emit!(JournalEntryRevised {
+        owner: revision.owner,
+        entry: revision.entry,
+        revision: id,
+        hash: journal_entry.revision_hash,
+    });
+    Ok(())
+}
+
+/// Resizes an entry to `space`, with `payer` covering any extra rent and
+/// `rent_holder` - whoever the entry's rent belongs to - getting back what
+/// a shrink frees
+pub(crate) fn resize_entry<'info>(
+    journal_entry: &AccountInfo<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    rent_holder: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let balance = journal_entry.lamports();
+    if rent > balance {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: journal_entry.clone(),
+                },
+            ),
+            rent - balance,
+        )?;
+    } else if balance > rent {
+        journal_entry.sub_lamports(balance - rent)?;
+        rent_holder.add_lamports(balance - rent)?;
+    }
+
+    journal_entry.resize(space)?;
+    Ok(())
+}
+
+/// Snapshots an entry, recounts its category and writes its new title,
+/// content and category
+/// Callers check the lengths first
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_entry(
+    journal_entry: &mut Account<JournalEntryState>,
+    user_profile: &mut UserProfile,
+    category_registry: Option<&mut CategoryRegistry>,
+    revision: &mut Revision,
+    rent_payer: Pubkey,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    // Keep the version being replaced
+    record_revision(journal_entry, user_profile, revision, rent_payer)?;
+    recount_category(user_profile, category_registry, journal_entry, &category)?;
+    overwrite_entry(journal_entry, title, content, category)
+}
+
+/// Writes an entry's new title, content and category, without a revision
+pub(crate) fn overwrite_entry(
+    journal_entry: &mut JournalEntryState,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    // A title-addressed entry can only change its title by moving accounts
+    require!(
+        journal_entry.entry_id.is_some() || journal_entry.title == title,
+        JournalError::TitleIsAddress
+    );
+
+    // Never write plaintext over an encrypted entry
+    require!(journal_entry.encrypted.is_none(), JournalError::EntryIsEncrypted);
+
+    // Pages continue the inline content, so replacing it would orphan them
+    require!(journal_entry.content_pages == 0, JournalError::EntryHasPages);
+
+    // Update the entry data
+    journal_entry.title = title.clone();
+    journal_entry.content = content;
+    journal_entry.category = category;
+    journal_entry.off_chain = None; // The content is back on-chain
+
+    // Update timestamp to track when entry was last modified
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    // Emit event for frontend state management
+    emit!(JournalEntryUpdated {
+        owner: journal_entry.owner,
+        title,
+        updated_at: journal_entry.updated_at,
+    });
+
+    Ok(())
+}
+
+/// Adds `tag` to an entry's tags
+/// Callers list the entry in the tag's index
+pub(crate) fn add_tag(journal_entry: &mut JournalEntryState, tag: &str) -> Result<()> {
+    require!(!tag.is_empty(), JournalError::TagEmpty);
+    require!(tag.len() <= MAX_TAG_LEN, JournalError::TagTooLong);
+    require!(
+        journal_entry.tags.len() < MAX_ENTRY_TAGS,
+        JournalError::TooManyTags
+    );
+    require!(
+        !journal_entry.tags.iter().any(|t| t == tag),
+        JournalError::EntryAlreadyTagged
+    );
+    journal_entry.tags.push(tag.to_string());
+    Ok(())
+}
+
+/// Tags a new entry with `tags`, as `tag_entry` would one at a time
+/// The remaining accounts hold each tag's `TagState` and last `TagIndex`
+/// page, in pairs in the order of `tags`; either is created if it doesn't
+/// exist yet, at the owner's expense
+pub(crate) fn tag_new_entry<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    journal_entry: &mut Account<'info, JournalEntryState>,
+    tags: Vec<String>,
+) -> Result<()> {
+    require!(
+        accounts.len() == 2 * tags.len(),
+        JournalError::TagIndexAccountsMismatch
+    );
+    for (tag, pair) in tags.into_iter().zip(accounts.chunks(2)) {
+        add_tag(journal_entry, &tag)?;
+
+        let tag_seed = title_seed(&tag);
+        let mut tag_state = init_if_needed::<TagState>(
+            &pair[0],
+            &[b"tag", owner.key.as_ref(), &tag_seed],
+            8 + TagState::INIT_SPACE,
+            owner,
+            system_program,
+        )?;
+        if tag_state.owner == Pubkey::default() {
+            tag_state.owner = owner.key();
+            tag_state.tag = tag.clone();
+        }
+        let page = TagIndex::page_of(tag_state.tagged).to_le_bytes();
+        let mut tag_index = init_if_needed::<TagIndex>(
+            &pair[1],
+            &[b"tag", owner.key.as_ref(), &tag_seed, &page],
+            8 + TagIndex::INIT_SPACE,
+            owner,
+            system_program,
+        )?;
+        tag_index.push(&mut tag_state, journal_entry.key());
+        tag_state.exit(&crate::ID)?;
+        tag_index.exit(&crate::ID)?;
+
+        emit!(EntryTagged {
+            owner: owner.key(),
+            entry: journal_entry.key(),
+            tag,
+        });
+    }
+    Ok(())
+}
+
+/// Loads the program account at the PDA of `seeds`, creating it first if
+/// it doesn't exist - `init_if_needed` for accounts Anchor can't see, such
+/// as remaining accounts
+/// Callers `exit` the account once they've written it
+pub(crate) fn init_if_needed<'info, T>(
+    account: &'info AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<Account<'info, T>>
+where
+    T: AccountSerialize + AccountDeserialize + Discriminator + Owner + Clone,
+{
+    let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    require_keys_eq!(account.key(), address, JournalError::TagIndexAccountsMismatch);
+
+    if account.owner != &crate::ID {
+        let bump = [bump];
+        let signer_seeds = [seeds, &[&bump]].concat();
+        let signer = [signer_seeds.as_slice()];
+        let rent = Rent::get()?.minimum_balance(space);
+
+        // Like Anchor's `init`, take over an address someone sent lamports to
+        let shortfall = rent.saturating_sub(account.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: account.clone(),
+                },
+                &signer,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: account.clone(),
+                },
+                &signer,
+            ),
+            &crate::ID,
+        )?;
+        account.try_borrow_mut_data()?[..T::DISCRIMINATOR.len()].copy_from_slice(T::DISCRIMINATOR);
+    }
+
+    Account::try_from(account)
+}
+
+/// `account` as a `T`, or None if nothing was ever created there
+/// Views take such accounts unchecked so a missing one reads as empty
+pub(crate) fn initialized<T>(account: &AccountInfo) -> Result<Option<T>>
+where
+    T: AccountDeserialize + Owner,
+{
+    if account.owner != &T::owner() {
+        return Ok(None);
+    }
+    T::try_deserialize(&mut &account.try_borrow_data()?[..]).map(Some)
+}
+
+/// Loads the `TagIndex` pages listing `journal_entry` for each of its tags,
+/// passed in the order of `tags`
+pub(crate) fn tag_indexes<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    journal_entry: &Account<JournalEntryState>,
+) -> Result<Vec<Account<'info, TagIndex>>> {
+    require!(
+        accounts.len() == journal_entry.tags.len(),
+        JournalError::TagIndexAccountsMismatch
+    );
+    accounts
+        .iter()
+        .zip(&journal_entry.tags)
+        .map(|(account, tag)| {
+            let tag_index = Account::<TagIndex>::try_from(account)?;
+            require!(
+                tag_index.owner == journal_entry.owner
+                    && tag_index.tag == *tag
+                    && tag_index.entries.contains(&journal_entry.key()),
+                JournalError::TagIndexAccountsMismatch
+            );
+            Ok(tag_index)
+        })
+        .collect()
+}
+
+/// Saves a `TagIndex` page taken from the remaining accounts, or closes it
+/// to `owner` once it is spent
+pub(crate) fn release_tag_index<'info>(tag_index: Account<'info, TagIndex>, owner: AccountInfo<'info>) -> Result<()> {
+    if tag_index.is_spent() {
+        tag_index.close(owner)
+    } else {
+        tag_index.exit(&crate::ID)
+    }
+}
+
+/// The owner's category registry, which they must pass once they have one
+/// so their counts can't drift
+pub(crate) fn owner_registry<'a>(
+    user_profile: &UserProfile,
+    category_registry: Option<&'a mut CategoryRegistry>,
+) -> Result<Option<&'a mut CategoryRegistry>> {
+    require!(
+        category_registry.is_some() || !user_profile.category_registry,
+        JournalError::CategoryRegistryMissing
+    );
+    Ok(category_registry)
+}
+
+/// Counts an entry under `category` instead of the category it was counted
+/// under, if the owner keeps a category registry
+/// Call before the entry's `category` changes
+pub(crate) fn recount_category(
+    user_profile: &UserProfile,
+    category_registry: Option<&mut CategoryRegistry>,
+    journal_entry: &mut JournalEntryState,
+    category: &str,
+) -> Result<()> {
+    let Some(registry) = owner_registry(user_profile, category_registry)? else {
+        return Ok(());
+    };
+    if journal_entry.category_counted {
+        registry.release(&journal_entry.category);
+    }
+    registry.count(category)?;
+    journal_entry.category_counted = true;
+    Ok(())
+}
+
+/// Takes an entry out of its category's count, if it was counted
+pub(crate) fn uncount_category(
+    user_profile: &UserProfile,
+    category_registry: Option<&mut CategoryRegistry>,
+    journal_entry: &JournalEntryState,
+) -> Result<()> {
+    if let Some(registry) = owner_registry(user_profile, category_registry)? {
+        if journal_entry.category_counted {
+            registry.release(&journal_entry.category);
+        }
+    }
+    Ok(())
+}
+
+/// Flips an entry's archived flag
+pub(crate) fn toggle_archive(journal_entry: &mut JournalEntryState) -> Result<()> {
+    // Toggle the archive status
+    journal_entry.is_archived = !journal_entry.is_archived;
+
+    // Update timestamp
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    // Emit appropriate event based on new state
+    if journal_entry.is_archived {
+        emit!(JournalEntryArchived {
+            owner: journal_entry.owner,
+            title: journal_entry.title.clone(),
+        });
+    } else {
+        emit!(JournalEntryUnarchived {
+            owner: journal_entry.owner,
+            title: journal_entry.title.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that a grant allows at least `needed`
+/// The grant's seeds already tie it to the entry and the signer
+pub(crate) fn require_permission(grant: Option<&EntryGrant>, needed: GrantPermission) -> Result<()> {
+    match grant {
+        Some(grant) if grant.permission >= needed => Ok(()),
+        _ => err!(JournalError::PermissionDenied),
+    }
+}
+
+/// The index page an entry was listed on, checking it's the one passed in
+pub(crate) fn index_page<'a>(
+    entry_index: &'a mut Option<Account<'_, EntryIndex>>,
+    page: u32,
+) -> Result<&'a mut EntryIndex> {
+    match entry_index {
+        Some(entry_index) if entry_index.page == page => Ok(entry_index),
+        _ => err!(JournalError::WrongEntryIndexPage),
+    }
+}
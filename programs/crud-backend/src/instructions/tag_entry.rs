@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::title_seed;
+use crate::events::EntryTagged;
+use crate::instructions::shared::add_tag;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::tag::{TagIndex, TagState};
+
+/// Context for tagging an entry
+#[derive(Accounts)]
+#[instruction(tag: String)]
+pub struct TagEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Grown to fit the tag
+    #[account(
+        mut,
+        has_one = owner,
+        realloc = journal_entry.space_with_title(journal_entry.title.len()) + 4 + tag.len(),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Tells which page is the tag's last
+    #[account(
+        init_if_needed,
+        seeds = [b"tag", owner.key().as_ref(), title_seed(&tag).as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + TagState::INIT_SPACE,
+    )]
+    pub tag_state: Account<'info, TagState>,
+
+    /// The tag's last page, created when the previous one fills up
+    #[account(
+        init_if_needed,
+        seeds = [b"tag", owner.key().as_ref(), title_seed(&tag).as_ref(), TagIndex::page_of(tag_state.tagged).to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + TagIndex::INIT_SPACE,
+    )]
+    pub tag_index: Account<'info, TagIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<TagEntry>,
+    tag: String,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    add_tag(journal_entry, &tag)?;
+
+    let tag_state = &mut ctx.accounts.tag_state;
+    if tag_state.owner == Pubkey::default() {
+        tag_state.owner = journal_entry.owner;
+        tag_state.tag = tag.clone();
+    }
+    ctx.accounts.tag_index.push(tag_state, journal_entry.key());
+
+    emit!(EntryTagged {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        tag,
+    });
+
+    Ok(())
+}
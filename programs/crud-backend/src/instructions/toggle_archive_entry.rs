@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::shared::toggle_archive;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for toggling archive state on an existing journal entry
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct ToggleArchiveEntry<'info> {
+    /// Owner must sign to prove they have permission
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The journal entry to archive/unarchive - must exist and belong to signer
+    /// Title-addressed, at `title`'s address, or numbered, at its id's
+    #[account(
+        mut,
+        has_one = owner,
+        address = journal_entry.address_for(&title),
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ToggleArchiveEntry>,
+    _title: String, // Checked against the entry's address
+) -> Result<()> {
+    toggle_archive(&mut ctx.accounts.journal_entry)
+}
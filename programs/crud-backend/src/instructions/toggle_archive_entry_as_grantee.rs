@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::shared::{require_permission, toggle_archive};
+use crate::state::entry_grant::{EntryGrant, GrantPermission};
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for a grantee archiving or unarchiving a shared entry
+#[derive(Accounts)]
+pub struct ToggleArchiveEntryAsGrantee<'info> {
+    pub grantee: Signer<'info>,
+
+    #[account(mut)]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Exists only if the owner shared the entry with the signer
+    #[account(
+        seeds = [b"grant", journal_entry.key().as_ref(), grantee.key().as_ref()],
+        bump,
+    )]
+    pub grant: Account<'info, EntryGrant>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ToggleArchiveEntryAsGrantee>,
+) -> Result<()> {
+    require_permission(Some(&ctx.accounts.grant), GrantPermission::Archive)?;
+    toggle_archive(&mut ctx.accounts.journal_entry)
+}
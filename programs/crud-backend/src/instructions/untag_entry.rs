@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::title_seed;
+use crate::error::JournalError;
+use crate::events::EntryUntagged;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::tag::TagIndex;
+
+/// Context for untagging an entry
+#[derive(Accounts)]
+#[instruction(tag: String, page: u32)]
+pub struct UntagEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Shrunk by the tag, refunding the owner
+    #[account(
+        mut,
+        has_one = owner,
+        realloc = journal_entry
+            .space_with_title(journal_entry.title.len())
+            .saturating_sub(4 + tag.len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The page listing the entry
+    #[account(
+        mut,
+        seeds = [b"tag", owner.key().as_ref(), title_seed(&tag).as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub tag_index: Account<'info, TagIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UntagEntry>,
+    tag: String,
+    page: u32,
+) -> Result<()> {
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    let position = journal_entry
+        .tags
+        .iter()
+        .position(|t| *t == tag)
+        .ok_or(JournalError::EntryNotTagged)?;
+    journal_entry.tags.remove(position);
+
+    let tag_index = &mut ctx.accounts.tag_index;
+    require!(tag_index.page == page, JournalError::WrongTagIndexPage);
+    tag_index.replace(&journal_entry.key(), Pubkey::default())?;
+    if tag_index.is_spent() {
+        tag_index.close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    emit!(EntryUntagged {
+        owner: journal_entry.owner,
+        entry: journal_entry.key(),
+        tag,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_CONTENT_LEN, MAX_TITLE_LEN};
+use crate::error::JournalError;
+use crate::instructions::shared::write_entry;
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::revision::Revision;
+use crate::state::user_profile::UserProfile;
+
+/// Context for updating an existing journal entry
+#[derive(Accounts)]
+#[instruction(title: String, content: String, category: String)]
+pub struct UpdateJournalEntry<'info> {
+    /// Owner must sign to prove they have permission
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The journal entry to update - must exist and belong to signer
+    /// Either kind of entry: title-addressed, at `title`'s address, or
+    /// numbered, at its id's
+    /// Resized to the new payload: the owner pays for growth and gets the
+    /// rent back when it shrinks
+    #[account(
+        mut,
+        has_one = owner, // CRITICAL: Ensures only owner can update
+        address = journal_entry.address_for(&title),
+        realloc = JournalEntryState::space_for(title.len(), content.len(), category.len())
+            + journal_entry.tag_space(),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Hands out the revision id
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The entry as it was before this update
+    #[account(
+        init,
+        seeds = [b"revision", owner.key().as_ref(), user_profile.next_revision_id.to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = Revision::space_for(&journal_entry),
+    )]
+    pub revision: Account<'info, Revision>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpdateJournalEntry>,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    // Same validation as create to maintain data integrity
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(content.len() <= MAX_CONTENT_LEN, JournalError::ContentTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+
+    let accounts = ctx.accounts;
+    write_entry(
+        &mut accounts.journal_entry,
+        &mut accounts.user_profile,
+        accounts.category_registry.as_deref_mut(),
+        &mut accounts.revision,
+        accounts.owner.key(),
+        title,
+        content,
+        category,
+    )
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_CONTENT_LEN, MAX_TITLE_LEN};
+use crate::error::JournalError;
+use crate::instructions::shared::{require_permission, resize_entry, write_entry};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::entry_grant::{EntryGrant, GrantPermission};
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::revision::Revision;
+use crate::state::user_profile::UserProfile;
+
+/// Context for a grantee updating a shared entry
+/// The profile and revision are the owner's, as in `UpdateJournalEntry`;
+/// the grantee pays instead of the owner
+#[derive(Accounts)]
+pub struct UpdateJournalEntryAsGrantee<'info> {
+    #[account(mut)]
+    pub grantee: Signer<'info>,
+
+    /// Resized to the new payload in the handler: growth at the grantee's
+    /// expense, shrinking to the owner's benefit
+    #[account(mut)]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The entry's owner, who holds its rent
+    #[account(mut, address = journal_entry.owner)]
+    pub owner: SystemAccount<'info>,
+
+    /// Exists only if the owner shared the entry with the signer
+    #[account(
+        seeds = [b"grant", journal_entry.key().as_ref(), grantee.key().as_ref()],
+        bump,
+    )]
+    pub grant: Account<'info, EntryGrant>,
+
+    /// The owner's profile, which hands out the revision id
+    #[account(
+        mut,
+        seeds = [b"profile", journal_entry.owner.as_ref()],
+        bump,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The entry as it was before this update - owned, and closable, by
+    /// the entry's owner like any other revision
+    #[account(
+        init,
+        seeds = [b"revision", journal_entry.owner.as_ref(), user_profile.next_revision_id.to_le_bytes().as_ref()],
+        bump,
+        payer = grantee,
+        space = Revision::space_for(&journal_entry),
+    )]
+    pub revision: Account<'info, Revision>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", journal_entry.owner.as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpdateJournalEntryAsGrantee>,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(content.len() <= MAX_CONTENT_LEN, JournalError::ContentTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+    require_permission(Some(&ctx.accounts.grant), GrantPermission::Edit)?;
+
+    let space = JournalEntryState::space_for(title.len(), content.len(), category.len())
+        + ctx.accounts.journal_entry.tag_space();
+    let accounts = ctx.accounts;
+    write_entry(
+        &mut accounts.journal_entry,
+        &mut accounts.user_profile,
+        accounts.category_registry.as_deref_mut(),
+        &mut accounts.revision,
+        accounts.grantee.key(),
+        title,
+        content,
+        category,
+    )?;
+
+    resize_entry(
+        &accounts.journal_entry.to_account_info(),
+        space,
+        &accounts.grantee,
+        &accounts.owner.to_account_info(),
+        &accounts.system_program,
+    )
+}
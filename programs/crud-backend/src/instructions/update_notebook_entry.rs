@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_CONTENT_LEN, MAX_TITLE_LEN};
+use crate::error::JournalError;
+use crate::instructions::shared::{overwrite_entry, resize_entry};
+use crate::state::journal_entry::JournalEntryState;
+use crate::state::notebook::{Notebook, NotebookRole};
+
+/// Context for updating a notebook entry
+#[derive(Accounts)]
+pub struct UpdateNotebookEntry<'info> {
+    /// Must be an editor or admin; pays for growth
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    pub notebook: Account<'info, Notebook>,
+
+    /// Belongs to the notebook, which is its owner
+    /// Resized to the new payload in the handler
+    #[account(
+        mut,
+        constraint = journal_entry.owner == notebook.key() @ JournalError::EntryNotInNotebook,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// The member who created the entry, refunded if it shrinks
+    #[account(mut, address = journal_entry.rent_holder())]
+    pub rent_payer: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpdateNotebookEntry>,
+    title: String,
+    content: String,
+    category: String,
+) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(content.len() <= MAX_CONTENT_LEN, JournalError::ContentTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+    ctx.accounts
+        .notebook
+        .require_role(&ctx.accounts.member.key(), NotebookRole::Editor)?;
+
+    let space = JournalEntryState::space_for(title.len(), content.len(), category.len())
+        + ctx.accounts.journal_entry.rent_payer_space();
+    let accounts = ctx.accounts;
+    overwrite_entry(&mut accounts.journal_entry, title, content, category)?;
+
+    resize_entry(
+        &accounts.journal_entry.to_account_info(),
+        space,
+        &accounts.member,
+        &accounts.rent_payer.to_account_info(),
+        &accounts.system_program,
+    )
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_TITLE_LEN};
+use crate::error::JournalError;
+use crate::events::JournalEntryUpdated;
+use crate::instructions::shared::{record_revision, recount_category};
+use crate::state::category_registry::CategoryRegistry;
+use crate::state::journal_entry::{JournalEntryState, OffChainContent};
+use crate::state::revision::Revision;
+use crate::state::user_profile::UserProfile;
+
+/// Context for pointing an entry at new off-chain content
+#[derive(Accounts)]
+#[instruction(title: String, category: String, off_chain: OffChainContent)]
+pub struct UpdateOffChainJournalEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Title-addressed or numbered, resized to the new URI
+    #[account(
+        mut,
+        has_one = owner,
+        realloc = JournalEntryState::off_chain_space_for(title.len(), off_chain.uri.len(), category.len())
+            + journal_entry.tag_space(),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    /// Hands out the revision id
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The entry as it was before this update
+    #[account(
+        init,
+        seeds = [b"revision", owner.key().as_ref(), user_profile.next_revision_id.to_le_bytes().as_ref()],
+        bump,
+        payer = owner,
+        space = Revision::space_for(&journal_entry),
+    )]
+    pub revision: Account<'info, Revision>,
+
+    pub system_program: Program<'info, System>,
+
+    /// The owner's category registry - needed once they have one
+    #[account(
+        mut,
+        seeds = [b"categories", owner.key().as_ref()],
+        bump,
+    )]
+    pub category_registry: Option<Account<'info, CategoryRegistry>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpdateOffChainJournalEntry>,
+    title: String,
+    category: String,
+    off_chain: OffChainContent,
+) -> Result<()> {
+    require!(title.len() <= MAX_TITLE_LEN, JournalError::TitleTooLong);
+    require!(!title.is_empty(), JournalError::TitleEmpty);
+    require!(category.len() <= MAX_CATEGORY_LEN, JournalError::CategoryTooLong);
+    off_chain.validate()?;
+
+    // Keep the version being replaced
+    record_revision(
+        &mut ctx.accounts.journal_entry,
+        &mut ctx.accounts.user_profile,
+        &mut ctx.accounts.revision,
+        ctx.accounts.owner.key(),
+    )?;
+
+    recount_category(
+        &ctx.accounts.user_profile,
+        ctx.accounts.category_registry.as_deref_mut(),
+        &mut ctx.accounts.journal_entry,
+        &category,
+    )?;
+
+    let journal_entry = &mut ctx.accounts.journal_entry;
+    require!(
+        journal_entry.entry_id.is_some() || journal_entry.title == title,
+        JournalError::TitleIsAddress
+    );
+    // Pages would be left holding content the URI replaced
+    require!(journal_entry.content_pages == 0, JournalError::EntryHasPages);
+
+    journal_entry.title = title.clone();
+    journal_entry.content = String::new();
+    journal_entry.category = category;
+    journal_entry.encrypted = None;
+    journal_entry.off_chain = Some(off_chain);
+
+    let clock = Clock::get()?;
+    journal_entry.updated_at = clock.unix_timestamp;
+
+    emit!(JournalEntryUpdated {
+        owner: journal_entry.owner,
+        title,
+        updated_at: journal_entry.updated_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::error::JournalError;
+use crate::instructions::shared::resize_entry;
+use crate::state::journal_entry::JournalEntryState;
+
+/// Context for upgrading an entry to the current layout
+#[derive(Accounts)]
+pub struct UpgradeEntry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: An older layout doesn't deserialize as `JournalEntryState`;
+    /// the handler checks the program owns it and the signer owns the entry
+    /// Title-addressed or numbered
+    #[account(mut)]
+    pub journal_entry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpgradeEntry>,
+) -> Result<()> {
+    let journal_entry = ctx.accounts.journal_entry.to_account_info();
+    require_keys_eq!(*journal_entry.owner, crate::ID, JournalError::EntryNotUpgradable);
+    let entry = {
+        let data = journal_entry.try_borrow_data()?;
+        require!(
+            data.starts_with(JournalEntryState::DISCRIMINATOR),
+            JournalError::EntryNotUpgradable
+        );
+        // The appended fields all read zero bytes as their default, so
+        // an older entry padded out to the largest layout decodes as the
+        // current one
+        let mut padded = data.to_vec();
+        padded.resize(padded.len().max(8 + JournalEntryState::INIT_SPACE), 0);
+        JournalEntryState::try_deserialize(&mut padded.as_slice())?
+    };
+    require_keys_eq!(entry.owner, ctx.accounts.owner.key(), JournalError::EntryNotUpgradable);
+
+    let space = entry.space_with_title(entry.title.len());
+    require!(journal_entry.data_len() != space, JournalError::EntryNotUpgradable);
+    resize_entry(
+        &journal_entry,
+        space,
+        &ctx.accounts.owner,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program,
+    )?;
+    let mut data = journal_entry.try_borrow_mut_data()?;
+    entry.try_serialize(&mut &mut data[..])
+}
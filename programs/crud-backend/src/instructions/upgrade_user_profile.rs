@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::error::JournalError;
+use crate::state::user_profile::UserProfile;
+
+/// Context for upgrading a user profile to the current layout
+#[derive(Accounts)]
+pub struct UpgradeUserProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The old layout doesn't deserialize as `UserProfile`; the
+    /// seeds tie it to the owner and the handler checks the rest
+    #[account(
+        mut,
+        seeds = [b"profile", owner.key().as_ref()],
+        bump,
+    )]
+    pub user_profile: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<UpgradeUserProfile>,
+) -> Result<()> {
+    let user_profile = ctx.accounts.user_profile.to_account_info();
+    require_keys_eq!(*user_profile.owner, crate::ID, JournalError::ProfileNotUpgradable);
+    {
+        let data = user_profile.try_borrow_data()?;
+        require!(
+            data.starts_with(UserProfile::DISCRIMINATOR),
+            JournalError::ProfileNotUpgradable
+        );
+        require!(
+            data.len() < 8 + UserProfile::INIT_SPACE,
+            JournalError::ProfileNotUpgradable
+        );
+    }
+
+    let space = 8 + UserProfile::INIT_SPACE;
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(user_profile.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: user_profile.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    // New bytes are zeroed, which reads as 0 for the new counters
+    user_profile.resize(space)?;
+
+    Ok(())
+}
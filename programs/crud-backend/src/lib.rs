@@ -1,54 +1,56 @@
+// The IDL instructions `#[program]` generates at the crate root still call
+// the deprecated `AccountInfo::realloc`, which an attribute on the module
+// doesn't reach.
+#![allow(deprecated)]
+
+pub mod constants;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod responses;
+pub mod state;
+
 use anchor_lang::prelude::*;
 
+pub use constants::*;
+pub use error::*;
+pub use events::*;
+pub use instructions::*;
+pub use responses::*;
+pub use state::*;
+
 declare_id!("933zmSGvoLgSaxNVCJURuXTSx4j1t4kdJbELGTxoX1u9");
 
 #[program]
 pub mod crud_backend {
     use super::*;
 
-    
     /// Creates a new journal entry for the user
-    /// Uses title + owner as seed to ensure unique entries per user
-
-    pub fn create_journal_entry(
-        ctx: Context<CreateJournalEntry>,
+    /// Uses hash(title) + owner as seed to ensure unique entries per user
+    /// For each of `tags`, pass the tag's `TagState` and last `TagIndex`
+    /// page as remaining accounts (see `tag_new_entry`)
+    pub fn create_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateJournalEntry<'info>>,
         title: String,
         content: String,
         category: String, // Added category for better organization
+        tags: Vec<String>,
+    ) -> Result<()> {
+        instructions::create_journal_entry::handler(ctx, title, content, category, tags)
+    }
+
+    /// Creates a new journal entry addressed by a per-user id instead of its title
+    /// The id comes from `next_entry_id`, which never decreases, so ids are
+    /// never reused and the title is plain data that update can change
+    /// Tags are passed as in `create_journal_entry`
+    pub fn create_numbered_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateNumberedJournalEntry<'info>>,
+        title: String,
+        content: String,
+        category: String,
+        tags: Vec<String>,
     ) -> Result<()> {
-        // Basic input validation to prevent spam and ensure data quality
-        require!(title.len() <= 100, JournalError::TitleTooLong);
-        require!(content.len() <= 1000, JournalError::ContentTooLong);
-        require!(!title.is_empty(), JournalError::TitleEmpty);
-        require!(category.len() <= 50, JournalError::CategoryTooLong);
-
-        let journal_entry = &mut ctx.accounts.journal_entry;
-        
-        // Set the basic entry data
-        journal_entry.owner = *ctx.accounts.owner.key;
-        journal_entry.title = title.clone();
-        journal_entry.content = content;
-        journal_entry.category = category;
-        journal_entry.is_archived = false; // New entries are active by default
-        
-        // Use Solana's clock for consistent timestamps across the network
-        let clock = Clock::get()?;
-        journal_entry.created_at = clock.unix_timestamp;
-        journal_entry.updated_at = clock.unix_timestamp;
-        
-        // Update the user's entry counter
-        let user_profile = &mut ctx.accounts.user_profile;
-        user_profile.total_entries += 1;
-        
-        // Emit event for frontend to listen and update UI accordingly
-        emit!(JournalEntryCreated {
-            owner: journal_entry.owner,
-            title: title,
-            category: journal_entry.category.clone(),
-            created_at: journal_entry.created_at,
-        });
-        
-        Ok(())
+        instructions::create_numbered_journal_entry::handler(ctx, title, content, category, tags)
     }
 
     /// Updates an existing journal entry
@@ -59,53 +61,93 @@ pub mod crud_backend {
         content: String,
         category: String,
     ) -> Result<()> {
-        // Same validation as create to maintain data integrity
-        require!(title.len() <= 100, JournalError::TitleTooLong);
-        require!(content.len() <= 1000, JournalError::ContentTooLong);
-        require!(!title.is_empty(), JournalError::TitleEmpty);
-        require!(category.len() <= 50, JournalError::CategoryTooLong);
-
-        let journal_entry = &mut ctx.accounts.journal_entry;
-        
-        // Update the entry data
-        journal_entry.title = title.clone();
-        journal_entry.content = content;
-        journal_entry.category = category;
-        
-        // Update timestamp to track when entry was last modified
-        let clock = Clock::get()?;
-        journal_entry.updated_at = clock.unix_timestamp;
-        
-        // Emit event for frontend state management
-        emit!(JournalEntryUpdated {
-            owner: journal_entry.owner,
-            title: title,
-            updated_at: journal_entry.updated_at,
-        });
-        
-        Ok(())
+        instructions::update_journal_entry::handler(ctx, title, content, category)
+    }
+
+    /// Creates a journal entry whose content is encrypted client-side
+    /// The program only checks the scheme and ciphertext size; title and
+    /// category stay public, as the title is part of the address, and so
+    /// do tags, passed as in `create_journal_entry`
+    pub fn create_encrypted_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateEncryptedJournalEntry<'info>>,
+        title: String,
+        category: String,
+        encrypted: EncryptedContent,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        instructions::create_encrypted_journal_entry::handler(ctx, title, category, encrypted, tags)
+    }
+
+    /// Replaces an entry's content with new ciphertext
+    /// Also encrypts a plaintext entry, dropping its content; the old text
+    /// remains in the chain's history
+    pub fn update_encrypted_journal_entry(
+        ctx: Context<UpdateEncryptedJournalEntry>,
+        title: String,
+        category: String,
+        encrypted: EncryptedContent,
+    ) -> Result<()> {
+        instructions::update_encrypted_journal_entry::handler(ctx, title, category, encrypted)
+    }
+
+    /// Creates a journal entry whose content lives off-chain
+    /// The entry stores only the content's URI and SHA-256, so the content
+    /// isn't limited by account size; clients check what they fetch against
+    /// the digest (see `crates/journal-storage`). Tags are passed as in
+    /// `create_journal_entry`
+    pub fn create_off_chain_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateOffChainJournalEntry<'info>>,
+        title: String,
+        category: String,
+        off_chain: OffChainContent,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        instructions::create_off_chain_journal_entry::handler(ctx, title, category, off_chain, tags)
+    }
+
+    /// Points an entry at new off-chain content
+    /// Also moves a plaintext or encrypted entry's content off-chain, dropping
+    /// what the account held
+    pub fn update_off_chain_journal_entry(
+        ctx: Context<UpdateOffChainJournalEntry>,
+        title: String,
+        category: String,
+        off_chain: OffChainContent,
+    ) -> Result<()> {
+        instructions::update_off_chain_journal_entry::handler(ctx, title, category, off_chain)
+    }
+
+    /// Rolls an entry back to one of its revisions
+    /// The version being replaced is recorded as a revision first, so the
+    /// history stays complete and a restore can itself be undone.
+    /// Title-addressed entries keep their current title
+    pub fn restore_revision(
+        ctx: Context<RestoreRevision>,
+        revision_id: u64,
+    ) -> Result<()> {
+        instructions::restore_revision::handler(ctx, revision_id)
+    }
+
+    /// Closes a revision, refunding its rent to whoever paid for it
+    /// Revisions outlive their entry, as an audit trail needs, so this is how
+    /// old history is pruned. The gap shows in the hash chain
+    pub fn close_revision(
+        _ctx: Context<CloseRevision>,
+        revision_id: u64,
+    ) -> Result<()> {
+        instructions::close_revision::handler(_ctx, revision_id)
     }
 
     /// Deletes a journal entry permanently
     /// The 'close = owner' constraint automatically refunds rent to the owner
-    pub fn delete_journal_entry(
-        ctx: Context<DeleteJournalEntry>,
+    /// Pass the entry's content pages, in order, as remaining accounts,
+    /// followed by the `TagIndex` page listing it for each of its tags, in
+    /// the order of `tags`
+    pub fn delete_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeleteJournalEntry<'info>>,
         title: String,
     ) -> Result<()> {
-        let journal_entry = &ctx.accounts.journal_entry;
-        
-        // Update user's entry counter before deletion
-        let user_profile = &mut ctx.accounts.user_profile;
-        user_profile.total_entries = user_profile.total_entries.saturating_sub(1); // Prevent underflow
-        
-        // Emit event before the account is closed
-        emit!(JournalEntryDeleted {
-            owner: journal_entry.owner,
-            title: journal_entry.title.clone(),
-        });
-        
-        // Account closure is handled automatically by Anchor's 'close' constraint
-        Ok(())
+        instructions::delete_journal_entry::handler(ctx, title)
     }
 
     /// Archives/unarchives a journal entry instead of deleting
@@ -114,305 +156,302 @@ pub mod crud_backend {
         ctx: Context<ToggleArchiveEntry>,
         title: String,
     ) -> Result<()> {
-        let journal_entry = &mut ctx.accounts.journal_entry;
-        
-        // Toggle the archive status
-        journal_entry.is_archived = !journal_entry.is_archived;
-        
-        // Update timestamp
-        let clock = Clock::get()?;
-        journal_entry.updated_at = clock.unix_timestamp;
-        
-        // Emit appropriate event based on new state
-        if journal_entry.is_archived {
-            emit!(JournalEntryArchived {
-                owner: journal_entry.owner,
-                title: journal_entry.title.clone(),
-            });
-        } else {
-            emit!(JournalEntryUnarchived {
-                owner: journal_entry.owner,
-                title: journal_entry.title.clone(),
-            });
-        }
-        
-        Ok(())
+        instructions::toggle_archive_entry::handler(ctx, title)
     }
 
-    /// Initializes a user profile to track their journal statistics
-    /// This is called once per user to set up their profile
-    pub fn initialize_user_profile(
-        ctx: Context<InitializeUserProfile>,
+    /// Renames a journal entry
+    /// The title is part of the PDA seed, so the entry moves to a new account
+    /// and the old one is closed, refunding its rent to the owner
+    /// Pass the `TagIndex` page listing the entry for each of its tags, in
+    /// the order of `tags`, as remaining accounts
+    pub fn rename_journal_entry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RenameJournalEntry<'info>>,
+        old_title: String,
+        new_title: String,
     ) -> Result<()> {
-        let user_profile = &mut ctx.accounts.user_profile;
-        
-        user_profile.owner = *ctx.accounts.owner.key;
-        user_profile.total_entries = 0;
-        
-        let clock = Clock::get()?;
-        user_profile.created_at = clock.unix_timestamp;
-        
-        emit!(UserProfileCreated {
-            owner: user_profile.owner,
-            created_at: user_profile.created_at,
-        });
-        
-        Ok(())
+        instructions::rename_journal_entry::handler(ctx, old_title, new_title)
     }
 
-    /// Gets the count of entries for a user (for pagination purposes)
-    /// This is a view function that doesn't modify state
-    pub fn get_user_stats(
-        ctx: Context<GetUserStats>,
-    ) -> Result<UserStatsResponse> {
-        let user_profile = &ctx.accounts.user_profile;
-        
-        Ok(UserStatsResponse {
-            total_entries: user_profile.total_entries,
-            member_since: user_profile.created_at,
-        })
+    /// Appends to an entry's inline content, growing the account to fit
+    /// The owner pays the extra rent. Content can grow to
+    /// `MAX_ENTRY_CONTENT_LEN` this way; after that, use `create_entry_page`
+    pub fn append_content(
+        ctx: Context<AppendContent>,
+        chunk: String,
+    ) -> Result<()> {
+        instructions::append_content::handler(ctx, chunk)
     }
-}
 
-// ============================================================================
-// ACCOUNT CONTEXTS - Define the accounts needed for each instruction
-// ============================================================================
-
-
-
-
-/// Context for creating a new journal entry
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct CreateJournalEntry<'info> {
-    /// The user creating the entry
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    /// New journal entry account
-    #[account(
-        init,
-        seeds = [title.as_bytes(), owner.key().as_ref()],
-        bump,
-        payer = owner,
-        space = 8 + JournalEntryState::INIT_SPACE,
-    )]
-    pub journal_entry: Account<'info, JournalEntryState>,
-
-    /// User profile for tracking stats
-    #[account(
-        mut,
-        seeds = [b"profile", owner.key().as_ref()],
-        bump,
-        has_one = owner,
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Starts the next overflow page of an entry's content
+    /// The content reads as the inline content followed by pages 0, 1, ...
+    pub fn create_entry_page(
+        ctx: Context<CreateEntryPage>,
+        page: u32,
+        chunk: String,
+    ) -> Result<()> {
+        instructions::create_entry_page::handler(ctx, page, chunk)
+    }
 
+    /// Appends to an entry's last overflow page, growing it to fit
+    pub fn append_entry_page(
+        ctx: Context<AppendEntryPage>,
+        page: u32,
+        chunk: String,
+    ) -> Result<()> {
+        instructions::append_entry_page::handler(ctx, page, chunk)
+    }
 
+    /// Shares an entry with `grantee` at `permission`, or changes the
+    /// permission of an existing grant
+    /// Permissions are levels: edit includes comment, archive includes both
+    pub fn grant_entry_access(
+        ctx: Context<GrantEntryAccess>,
+        grantee: Pubkey,
+        permission: GrantPermission,
+    ) -> Result<()> {
+        instructions::grant_entry_access::handler(ctx, grantee, permission)
+    }
 
-/// Context for updating an existing journal entry
-#[derive(Accounts)]
-#[instruction(title: String, content: String, category: String)]
-pub struct UpdateJournalEntry<'info> {
-    /// Owner must sign to prove they have permission
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// Withdraws a grant, refunding its rent to the owner
+    pub fn revoke_entry_access(
+        ctx: Context<RevokeEntryAccess>,
+        grantee: Pubkey,
+    ) -> Result<()> {
+        instructions::revoke_entry_access::handler(ctx, grantee)
+    }
 
-    /// The journal entry to update - must exist and belong to signer
-    #[account(
-        mut,
-        seeds = [title.as_bytes(), owner.key().as_ref()],
-        bump,
-        has_one = owner, // CRITICAL: Ensures only owner can update
-    )]
-    pub journal_entry: Account<'info, JournalEntryState>,
+    /// Lists who an entry is shared with, and at which permission
+    /// This is a view function; pass the grant accounts, in the order of
+    /// `EntryGrantList.grantees`, as remaining accounts
+    pub fn get_entry_grants<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetEntryGrants<'info>>,
+    ) -> Result<EntryGrantsResponse> {
+        instructions::get_entry_grants::handler(ctx)
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Comments on an entry, as its owner or a grantee with comment access
+    /// Comments live in the transaction log as `JournalEntryCommented`
+    /// events; nothing is stored, so the entry's rent doesn't grow
+    pub fn comment_on_entry(
+        ctx: Context<CommentOnEntry>,
+        comment: String,
+    ) -> Result<()> {
+        instructions::comment_on_entry::handler(ctx, comment)
+    }
 
-/// Context for toggling archive state on an existing journal entry
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct ToggleArchiveEntry<'info> {
-    /// Owner must sign to prove they have permission
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    /// The journal entry to archive/unarchive - must exist and belong to signer
-    #[account(
-        mut,
-        seeds = [title.as_bytes(), owner.key().as_ref()],
-        bump,
-        has_one = owner,
-    )]
-    pub journal_entry: Account<'info, JournalEntryState>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Updates an entry shared with the signer at edit access
+    /// Works like `update_journal_entry`; the grantee pays for any growth
+    /// and the revision. The entry's rent is the owner's, so a shrink
+    /// refunds the owner, while the revision's rent goes back to the grantee
+    /// when the owner closes it
+    pub fn update_journal_entry_as_grantee(
+        ctx: Context<UpdateJournalEntryAsGrantee>,
+        title: String,
+        content: String,
+        category: String,
+    ) -> Result<()> {
+        instructions::update_journal_entry_as_grantee::handler(ctx, title, content, category)
+    }
 
-/// Context for deleting a journal entry
-#[derive(Accounts)]
-#[instruction(title: String)]
-pub struct DeleteJournalEntry<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    /// The entry to delete - will be closed and rent refunded to owner
-    #[account(
-        mut,
-        seeds = [title.as_bytes(), owner.key().as_ref()],
-        bump,
-        has_one = owner, // CRITICAL: Prevents unauthorized deletions
-        close = owner,   // Automatically refunds rent to owner
-    )]
-    pub journal_entry: Account<'info, JournalEntryState>,
-
-    /// User profile to update the entry count
-    #[account(
-        mut,
-        seeds = [b"profile", owner.key().as_ref()],
-        bump,
-        has_one = owner,
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Archives or unarchives an entry shared with the signer at archive
+    /// access
+    pub fn toggle_archive_entry_as_grantee(
+        ctx: Context<ToggleArchiveEntryAsGrantee>,
+    ) -> Result<()> {
+        instructions::toggle_archive_entry_as_grantee::handler(ctx)
+    }
 
-/// Context for initializing a user profile
-#[derive(Accounts)]
-pub struct InitializeUserProfile<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    /// User profile account - one per user
-    #[account(
-        init,
-        seeds = [b"profile", owner.key().as_ref()],
-        bump,
-        payer = owner,
-        space = 8 + UserProfile::INIT_SPACE,
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Creates a notebook administered by the signer
+    /// Notebooks hold entries for a team; the notebook's address is the
+    /// entries' `owner`, so owner-only instructions never apply to them
+    pub fn create_notebook(
+        ctx: Context<CreateNotebook>,
+        name: String,
+    ) -> Result<()> {
+        instructions::create_notebook::handler(ctx, name)
+    }
 
-/// Context for getting user statistics
-#[derive(Accounts)]
-pub struct GetUserStats<'info> {
-    pub owner: Signer<'info>,
-
-    /// Read-only access to user profile
-    #[account(
-        seeds = [b"profile", owner.key().as_ref()],
-        bump,
-        has_one = owner,
-    )]
-    pub user_profile: Account<'info, UserProfile>,
-}
+    /// Adds a member to a notebook, or changes a member's role
+    /// Takes the admin or a member with the admin role
+    pub fn set_notebook_member(
+        ctx: Context<ManageNotebook>,
+        member: Pubkey,
+        role: NotebookRole,
+    ) -> Result<()> {
+        instructions::set_notebook_member::handler(ctx, member, role)
+    }
 
-// ============================================================================
-// ACCOUNT STATES - Define the data structures stored on-chain
-// ============================================================================
-
-/// Main journal entry data structure
-#[account]
-#[derive(InitSpace)]
-pub struct JournalEntryState {
-    pub owner: Pubkey,        // 32 bytes - who owns this entry
-    
-    #[max_len(100)]
-    pub title: String,        // 4 + 100 bytes - entry title
-    
-    #[max_len(1000)]
-    pub content: String,      // 4 + 1000 bytes - entry content
-    
-    #[max_len(50)]
-    pub category: String,     // 4 + 50 bytes - category for organization
-    
-    pub is_archived: bool,    // 1 byte - soft delete flag
-    pub created_at: i64,      // 8 bytes - creation timestamp
-    pub updated_at: i64,      // 8 bytes - last update timestamp
-}
+    /// Removes a member from a notebook
+    /// Takes the admin or a member with the admin role; members may also
+    /// leave on their own
+    pub fn remove_notebook_member(
+        ctx: Context<ManageNotebook>,
+        member: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_notebook_member::handler(ctx, member)
+    }
 
-/// User profile to track statistics and metadata
-#[account]
-#[derive(InitSpace)]
-pub struct UserProfile {
-    pub owner: Pubkey,        // 32 bytes - profile owner
-    pub total_entries: u32,   // 4 bytes - count of entries created
-    pub created_at: i64,      // 8 bytes - when profile was created
-}
+    /// Creates an entry in a notebook, as an editor or admin
+    /// Entries are numbered by the notebook's `next_entry_id`, like
+    /// numbered entries; the member pays the rent
+    pub fn create_notebook_entry(
+        ctx: Context<CreateNotebookEntry>,
+        title: String,
+        content: String,
+        category: String,
+    ) -> Result<()> {
+        instructions::create_notebook_entry::handler(ctx, title, content, category)
+    }
 
-// ============================================================================
-// CUSTOM ERRORS - Define application-specific error messages
-// ============================================================================
-
-#[error_code]
-pub enum JournalError {
-    #[msg("Title cannot be empty")]
-    TitleEmpty,
-    #[msg("Title is too long (max 100 characters)")]
-    TitleTooLong,
-    #[msg("Content is too long (max 1000 characters)")]
-    ContentTooLong,
-    #[msg("Category is too long (max 50 characters)")]
-    CategoryTooLong,
-}
+    /// Updates a notebook entry, as an editor or admin
+    /// The member pays for growth; the entry's rent stays with the member
+    /// who created it, who gets back what a shrink frees.
+    /// Notebook entries keep no revisions: those are tied to a user profile
+    pub fn update_notebook_entry(
+        ctx: Context<UpdateNotebookEntry>,
+        title: String,
+        content: String,
+        category: String,
+    ) -> Result<()> {
+        instructions::update_notebook_entry::handler(ctx, title, content, category)
+    }
 
-// ============================================================================
-// EVENTS - For frontend integration and off-chain indexing
-// ============================================================================
+    /// Deletes a notebook entry, as an admin
+    /// The rent goes back to the member who created the entry
+    pub fn delete_notebook_entry(
+        ctx: Context<DeleteNotebookEntry>,
+    ) -> Result<()> {
+        instructions::delete_notebook_entry::handler(ctx)
+    }
 
-#[event]
-pub struct JournalEntryCreated {
-    pub owner: Pubkey,
-    pub title: String,
-    pub category: String,
-    pub created_at: i64,
-}
+    /// Tags an entry, listing it on the last `TagIndex` page of the tag
+    /// The tag's `TagState` and pages are created on first use; the owner
+    /// pays their rent and the entry's growth
+    pub fn tag_entry(
+        ctx: Context<TagEntry>,
+        tag: String,
+    ) -> Result<()> {
+        instructions::tag_entry::handler(ctx, tag)
+    }
 
-#[event]
-pub struct JournalEntryUpdated {
-    pub owner: Pubkey,
-    pub title: String,
-    pub updated_at: i64,
-}
+    /// Removes a tag from an entry and the entry from the `TagIndex` page
+    /// listing it, which leaves a gap there
+    /// A full page left without entries is closed, refunding its rent to
+    /// the owner
+    pub fn untag_entry(
+        ctx: Context<UntagEntry>,
+        tag: String,
+        page: u32,
+    ) -> Result<()> {
+        instructions::untag_entry::handler(ctx, tag, page)
+    }
 
-#[event]
-pub struct JournalEntryDeleted {
-    pub owner: Pubkey,
-    pub title: String,
-}
+    /// Lists one page of the entries carrying a tag, in the order they were
+    /// tagged
+    /// This is a view function. Untagged entries leave gaps that are
+    /// skipped, and a tag no entry ever carried, or a page closed once its
+    /// entries were gone, lists nothing
+    pub fn get_tag_entries(
+        ctx: Context<GetTagEntries>,
+        tag: String,
+        page: u32,
+    ) -> Result<TagEntriesResponse> {
+        instructions::get_tag_entries::handler(ctx, tag, page)
+    }
 
-#[event]
-pub struct JournalEntryArchived {
-    pub owner: Pubkey,
-    pub title: String,
-}
+    /// Starts the signer's category registry
+    /// From now on their entries are counted by category as they're
+    /// created, updated and deleted, and every such instruction must pass
+    /// the registry. Existing entries are counted once they're next updated.
+    /// In strict mode, entries can only use categories added with
+    /// `add_category`; otherwise new categories are added as they're used
+    pub fn initialize_category_registry(
+        ctx: Context<InitializeCategoryRegistry>,
+        strict: bool,
+    ) -> Result<()> {
+        instructions::initialize_category_registry::handler(ctx, strict)
+    }
 
-#[event]
-pub struct JournalEntryUnarchived {
-    pub owner: Pubkey,
-    pub title: String,
-}
+    /// Adds a category to the signer's registry
+    pub fn add_category(
+        ctx: Context<ManageCategories>,
+        name: String,
+    ) -> Result<()> {
+        instructions::add_category::handler(ctx, name)
+    }
 
-#[event]
-pub struct UserProfileCreated {
-    pub owner: Pubkey,
-    pub created_at: i64,
-}
+    /// Removes a category from the signer's registry
+    /// Only a category no entry is counted under can go
+    pub fn remove_category(
+        ctx: Context<ManageCategories>,
+        name: String,
+    ) -> Result<()> {
+        instructions::remove_category::handler(ctx, name)
+    }
+
+    /// Turns strict mode on or off for the signer's registry
+    pub fn set_category_strict(
+        ctx: Context<ManageCategories>,
+        strict: bool,
+    ) -> Result<()> {
+        instructions::set_category_strict::handler(ctx, strict)
+    }
 
-// ============================================================================
-// RETURN TYPES - For functions that return data
-// ============================================================================
+    /// Counts an entry made before the signer's registry existed
+    /// Such entries are otherwise only counted from their next update; this
+    /// back-fills one without touching its content. Strict registries need
+    /// the entry's category added first
+    pub fn count_entry(
+        ctx: Context<CountEntry>,
+    ) -> Result<()> {
+        instructions::count_entry::handler(ctx)
+    }
+
+    /// Upgrades a user profile created before `next_entry_id`,
+    /// `indexed_entries`, `next_revision_id` or `category_registry` existed
+    /// Grows the account, with the owner paying the extra rent, and starts
+    /// the new counters at 0. Entries made before the upgrade aren't indexed
+    pub fn upgrade_user_profile(
+        ctx: Context<UpgradeUserProfile>,
+    ) -> Result<()> {
+        instructions::upgrade_user_profile::handler(ctx)
+    }
+
+    /// Upgrades an entry written before fields were appended to
+    /// `JournalEntryState`, so it deserializes again
+    /// Fields the entry predates start at their zero value: not numbered,
+    /// indexed, sealed, revised, shared, counted or tagged, with the rent
+    /// belonging to the owner. The account is resized to fit, the owner
+    /// paying for growth and getting back what a shrink frees - entries made
+    /// before sizes followed their content were allocated at the maximum
+    pub fn upgrade_entry(
+        ctx: Context<UpgradeEntry>,
+    ) -> Result<()> {
+        instructions::upgrade_entry::handler(ctx)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct UserStatsResponse {
-    pub total_entries: u32,
-    pub member_since: i64,
-}
\ No newline at end of file
+    /// Initializes a user profile to track their journal statistics
+    /// This is called once per user to set up their profile
+    pub fn initialize_user_profile(
+        ctx: Context<InitializeUserProfile>,
+    ) -> Result<()> {
+        instructions::initialize_user_profile::handler(ctx)
+    }
+
+    /// Gets the count of entries for a user (for pagination purposes)
+    /// This is a view function that doesn't modify state
+    pub fn get_user_stats(
+        ctx: Context<GetUserStats>,
+    ) -> Result<UserStatsResponse> {
+        instructions::get_user_stats::handler(ctx)
+    }
+
+    /// Lists one page of a user's entries with their archived flags
+    /// This is a view function; pass the live entries of the page, in order,
+    /// as remaining accounts. Deleted entries leave gaps that are skipped
+    pub fn get_entry_index_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetEntryIndexPage<'info>>,
+    ) -> Result<EntryIndexPageResponse> {
+        instructions::get_entry_index_page::handler(ctx)
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::state::entry_grant::GrantPermission;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserStatsResponse {
+    pub total_entries: u32,
+    pub member_since: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct IndexedEntry {
+    pub entry: Pubkey,
+    pub is_archived: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TagEntriesResponse {
+    pub tag: String,
+    pub page: u32,
+    pub entries: Vec<Pubkey>,
+    pub next_page: Option<u32>, // None on the last page
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct EntryIndexPageResponse {
+    pub page: u32,
+    pub entries: Vec<IndexedEntry>,
+    pub next_page: Option<u32>, // None on the last page
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GrantedAccess {
+    pub grantee: Pubkey,
+    pub permission: GrantPermission,
+    pub granted_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct EntryGrantsResponse {
+    pub entry: Pubkey,
+    pub grants: Vec<GrantedAccess>,
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_CATEGORIES;
+use crate::error::JournalError;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct CategoryCount {
+    #[max_len(50)]
+    pub name: String,         // 4 + 50 bytes
+    pub entries: u32,         // 4 bytes - entries counted under this category
+}
+
+/// A user's categories and how many entries each has
+/// Entries without a category aren't counted
+#[account]
+#[derive(InitSpace)]
+pub struct CategoryRegistry {
+    pub owner: Pubkey,        // 32 bytes - whose categories these are
+    pub strict: bool,         // 1 byte - entries may only use listed categories
+
+    #[max_len(MAX_CATEGORIES)]
+    pub categories: Vec<CategoryCount>, // 4 + 20 * 58 bytes - in the order they were added
+}
+
+impl CategoryRegistry {
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.categories.iter().position(|c| c.name == name)
+    }
+
+    pub(crate) fn add(&mut self, name: String) -> Result<usize> {
+        require!(
+            self.categories.len() < MAX_CATEGORIES,
+            JournalError::TooManyCategories
+        );
+        self.categories.push(CategoryCount { name, entries: 0 });
+        Ok(self.categories.len() - 1)
+    }
+
+    /// Counts one more entry under `name`
+    /// An unlisted category is added, unless the registry is strict
+    pub(crate) fn count(&mut self, name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Ok(());
+        }
+        let position = match self.find(name) {
+            Some(position) => position,
+            None => {
+                require!(!self.strict, JournalError::UnknownCategory);
+                self.add(name.to_string())?
+            }
+        };
+        self.categories[position].entries += 1;
+        Ok(())
+    }
+
+    /// Counts one entry fewer under `name`
+    pub(crate) fn release(&mut self, name: &str) {
+        // Counted categories can't be removed, so it's listed
+        if let Some(position) = self.find(name) {
+            let category = &mut self.categories[position];
+            category.entries = category.entries.saturating_sub(1);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_GRANTS;
+
+/// How much a grant lets its grantee do
+/// Ordered: each level includes the ones before it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, InitSpace)]
+pub enum GrantPermission {
+    Comment,
+    Edit,
+    Archive,
+}
+
+/// Lets `grantee` act on one of the owner's entries
+#[account]
+#[derive(InitSpace)]
+pub struct EntryGrant {
+    pub owner: Pubkey,        // 32 bytes - the entry's owner
+    pub entry: Pubkey,        // 32 bytes - the entry's address, also in the seeds
+    pub grantee: Pubkey,      // 32 bytes - who is granted access, also in the seeds
+    pub permission: GrantPermission, // 1 byte
+    pub granted_at: i64,      // 8 bytes - when the permission was last set
+}
+
+/// The grantees of one entry, in the order they were first granted access
+#[account]
+#[derive(InitSpace)]
+pub struct EntryGrantList {
+    pub owner: Pubkey,        // 32 bytes - the entry's owner
+    pub entry: Pubkey,        // 32 bytes - the entry's address, also in the seeds
+
+    #[max_len(MAX_ENTRY_GRANTS)]
+    pub grantees: Vec<Pubkey>, // 4 + 16 * 32 bytes
+}
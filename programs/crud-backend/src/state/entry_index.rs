@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ENTRY_INDEX_PAGE_SIZE;
+use crate::error::JournalError;
+use crate::state::user_profile::UserProfile;
+
+/// One page of a user's entry addresses, in creation order
+/// Pages only grow: a deleted entry leaves `Pubkey::default()` in its slot
+/// and a renamed one is replaced in place, so positions never shift
+#[account]
+#[derive(InitSpace)]
+pub struct EntryIndex {
+    pub owner: Pubkey,        // 32 bytes - whose entries these are
+    pub page: u32,            // 4 bytes - page number, also in the seeds
+
+    #[max_len(ENTRY_INDEX_PAGE_SIZE)]
+    pub entries: Vec<Pubkey>, // 4 + 25 * 32 bytes - entry addresses
+}
+
+impl EntryIndex {
+    /// The page that the slot after `indexed_entries` falls on
+    pub fn page_of(indexed_entries: u64) -> u32 {
+        (indexed_entries / ENTRY_INDEX_PAGE_SIZE as u64) as u32
+    }
+
+    /// Appends `entry` and returns this page's number
+    /// The page is the user's last, so it is fresh when it was just created
+    pub(crate) fn push(&mut self, user_profile: &mut UserProfile, entry: Pubkey) -> u32 {
+        if self.entries.is_empty() {
+            self.owner = user_profile.owner;
+            self.page = Self::page_of(user_profile.indexed_entries);
+        }
+        self.entries.push(entry);
+        user_profile.indexed_entries += 1;
+        self.page
+    }
+
+    pub(crate) fn replace(&mut self, old: &Pubkey, new: Pubkey) -> Result<()> {
+        let slot = self
+            .entries
+            .iter_mut()
+            .find(|entry| *entry == old)
+            .ok_or(JournalError::WrongEntryIndexPage)?;
+        *slot = new;
+        Ok(())
+    }
+}
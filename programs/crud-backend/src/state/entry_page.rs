@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_ENTRY_PAGE_CONTENT_LEN;
+
+/// Overflow content of an entry beyond `MAX_ENTRY_CONTENT_LEN`
+/// Sized to its content, up to `MAX_ENTRY_PAGE_CONTENT_LEN` bytes
+#[account]
+#[derive(InitSpace)]
+pub struct EntryPage {
+    pub entry: Pubkey,        // 32 bytes - the entry this continues
+    pub page: u32,            // 4 bytes - position after the inline content, also in the seeds
+
+    #[max_len(MAX_ENTRY_PAGE_CONTENT_LEN)]
+    pub content: String,      // 4 + len bytes - this page's slice of the content
+}
+
+impl EntryPage {
+    pub fn space_for(content_len: usize) -> usize {
+        8 + Self::INIT_SPACE + content_len - MAX_ENTRY_PAGE_CONTENT_LEN
+    }
+}
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    CONTENT_URI_SCHEMES,
+    ENCRYPTION_SCHEME_XCHACHA20_POLY1305,
+    ENCRYPTION_TAG_LEN,
+    MAX_CATEGORY_LEN,
+    MAX_CIPHERTEXT_LEN,
+    MAX_CONTENT_LEN,
+    MAX_CONTENT_URI_LEN,
+    MAX_ENTRY_TAGS,
+    MAX_TAG_LEN,
+    MAX_TITLE_LEN,
+    title_seed,
+};
+use crate::error::JournalError;
+
+/// Main journal entry data structure
+/// Accounts are sized to fit their strings (see `space_for`); `INIT_SPACE`
+/// is the size at the create and update limits, with room for content,
+/// ciphertext, a content URI and the most tags
+#[account]
+#[derive(InitSpace)]
+pub struct JournalEntryState {
+    pub owner: Pubkey,        // 32 bytes - who owns this entry
+    
+    #[max_len(MAX_TITLE_LEN)]
+    pub title: String,        // 4 + 100 bytes - entry title
+    
+    #[max_len(MAX_CONTENT_LEN)]
+    pub content: String,      // 4 + 1000 bytes - entry content
+    
+    #[max_len(MAX_CATEGORY_LEN)]
+    pub category: String,     // 4 + 50 bytes - category for organization
+    
+    pub is_archived: bool,    // 1 byte - soft delete flag
+    pub created_at: i64,      // 8 bytes - creation timestamp
+    pub updated_at: i64,      // 8 bytes - last update timestamp
+    pub entry_id: Option<u64>, // 1 + 8 bytes - id for numbered entries, None when addressed by title
+    pub index_page: Option<u32>, // 1 + 4 bytes - EntryIndex page listing this entry, None before the index existed
+    pub content_pages: u32,   // 4 bytes - EntryPage accounts continuing the content
+    pub encrypted: Option<EncryptedContent>, // 1 byte, or 1 + 29 + ciphertext when content is encrypted
+    pub off_chain: Option<OffChainContent>, // 1 byte, or 1 + 36 + uri when content is stored off-chain
+    pub history: Option<u64>, // 1 + 8 bytes - id of the first revision, shared by all of them
+    pub last_revision: Option<u64>, // 1 + 8 bytes - the newest revision, None before the first update
+    pub revision_hash: [u8; 32], // 32 bytes - hash of the newest revision, the head of the chain
+    pub grants: u8,           // 1 byte - EntryGrant accounts for this entry
+    pub category_counted: bool, // 1 byte - category is counted in the owner's CategoryRegistry
+
+    #[max_len(MAX_ENTRY_TAGS, MAX_TAG_LEN)]
+    pub tags: Vec<String>,    // 4 bytes, plus 4 + len per tag - each listed in the owner's TagIndex for it
+    pub rent_payer: Option<Pubkey>, // 1 byte, or 1 + 32 for notebook entries - who the rent goes back to, None for the owner
+}
+
+impl JournalEntryState {
+    /// Account size for an entry with strings of these lengths, untagged
+    /// and with neither ciphertext nor a content URI
+    /// `INIT_SPACE` holds every string and optional field at its largest;
+    /// this trades those for the actual lengths and empty fields
+    pub fn space_for(title_len: usize, content_len: usize, category_len: usize) -> usize {
+        8 + Self::INIT_SPACE + title_len + content_len + category_len
+            - MAX_TITLE_LEN
+            - MAX_CONTENT_LEN
+            - MAX_CATEGORY_LEN
+            - EncryptedContent::INIT_SPACE
+            - OffChainContent::INIT_SPACE
+            - MAX_ENTRY_TAGS * (4 + MAX_TAG_LEN)
+            - std::mem::size_of::<Pubkey>() // rent_payer
+    }
+
+    /// Account size for an encrypted entry: empty content plus the ciphertext
+    pub fn encrypted_space_for(title_len: usize, ciphertext_len: usize, category_len: usize) -> usize {
+        Self::space_for(title_len, 0, category_len) + EncryptedContent::space_for(ciphertext_len)
+    }
+
+    /// Account size for an off-chain entry: empty content plus the URI
+    pub fn off_chain_space_for(title_len: usize, uri_len: usize, category_len: usize) -> usize {
+        Self::space_for(title_len, 0, category_len) + OffChainContent::space_for(uri_len)
+    }
+
+    /// Account size for this entry's content under a title of `title_len`
+    pub fn space_with_title(&self, title_len: usize) -> usize {
+        Self::space_for(title_len, self.content.len(), self.category.len())
+            + sealed_space(&self.encrypted, &self.off_chain)
+            + self.tag_space()
+            + self.rent_payer_space()
+    }
+
+    /// Bytes the entry's tags add to an untagged entry
+    /// `space_for` and its variants size untagged entries, so resizing a
+    /// tagged one adds this
+    pub fn tag_space(&self) -> usize {
+        Self::space_for_tags(&self.tags)
+    }
+
+    /// Bytes `tags` add to an untagged entry
+    pub fn space_for_tags(tags: &[String]) -> usize {
+        tags.iter().map(|tag| 4 + tag.len()).sum()
+    }
+
+    /// Bytes a recorded `rent_payer` adds, which `space_for` leaves out too
+    pub fn rent_payer_space(&self) -> usize {
+        self.rent_payer.map_or(0, |_| 32)
+    }
+
+    /// Whoever the entry's rent goes back to
+    pub fn rent_holder(&self) -> Pubkey {
+        self.rent_payer.unwrap_or(self.owner)
+    }
+
+    /// Where an entry titled `title` has to live: at the title's seeds for
+    /// title-addressed entries, at `[b"entry", owner, id]` for numbered ones
+    pub fn address_for(&self, title: &str) -> Pubkey {
+        match self.entry_id {
+            Some(id) => Pubkey::find_program_address(
+                &[b"entry", self.owner.as_ref(), id.to_le_bytes().as_ref()],
+                &crate::ID,
+            ).0,
+            None => Pubkey::find_program_address(
+                &[title_seed(title).as_ref(), self.owner.as_ref()],
+                &crate::ID,
+            ).0,
+        }
+    }
+
+    /// Account size once `extra` content bytes are appended
+    /// Never shrinks: entries allocated at full size, before entries were
+    /// sized to fit, keep their slack until the next update
+    pub fn grown_space(entry: &Account<JournalEntryState>, extra: usize) -> usize {
+        let needed = Self::space_for(entry.title.len(), entry.content.len() + extra, entry.category.len())
+            + entry.tag_space()
+            + entry.rent_payer_space();
+        needed.max(entry.to_account_info().data_len())
+    }
+}
+
+/// Content encrypted client-side, stored in place of `content`
+/// The program can't read it; it only checks the scheme and size
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EncryptedContent {
+    pub scheme: u8,           // 1 byte - ENCRYPTION_SCHEME_XCHACHA20_POLY1305
+    pub nonce: [u8; 24],      // 24 bytes - XChaCha20 nonce, random per encryption
+    #[max_len(MAX_CIPHERTEXT_LEN)]
+    pub ciphertext: Vec<u8>,  // 4 + len bytes - encrypted content followed by the tag
+}
+
+impl EncryptedContent {
+    /// Bytes this adds to an entry over an unencrypted one
+    pub fn space_for(ciphertext_len: usize) -> usize {
+        Self::INIT_SPACE + ciphertext_len - MAX_CIPHERTEXT_LEN
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        require!(
+            self.scheme == ENCRYPTION_SCHEME_XCHACHA20_POLY1305,
+            JournalError::UnknownEncryptionScheme
+        );
+        // Even empty content carries a tag
+        require!(
+            (ENCRYPTION_TAG_LEN..=MAX_CIPHERTEXT_LEN).contains(&self.ciphertext.len()),
+            JournalError::CiphertextSize
+        );
+        Ok(())
+    }
+}
+
+/// Bytes ciphertext or a content URI adds to an entry or revision
+pub(crate) fn sealed_space(encrypted: &Option<EncryptedContent>, off_chain: &Option<OffChainContent>) -> usize {
+    encrypted.as_ref().map_or(0, |e| EncryptedContent::space_for(e.ciphertext.len()))
+        + off_chain.as_ref().map_or(0, |o| OffChainContent::space_for(o.uri.len()))
+}
+
+/// Where an entry's content is stored off-chain, and what it hashes to
+/// The program never sees the content; clients check it against `sha256`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct OffChainContent {
+    #[max_len(MAX_CONTENT_URI_LEN)]
+    pub uri: String,          // 4 + len bytes - ar://<id> or ipfs://<cid>[/<path>]
+    pub sha256: [u8; 32],     // 32 bytes - SHA-256 of the content
+}
+
+impl OffChainContent {
+    /// Bytes this adds to an entry over an on-chain one
+    pub fn space_for(uri_len: usize) -> usize {
+        Self::INIT_SPACE + uri_len - MAX_CONTENT_URI_LEN
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        require!(self.uri.len() <= MAX_CONTENT_URI_LEN, JournalError::ContentUriTooLong);
+        let has_path = CONTENT_URI_SCHEMES
+            .iter()
+            .filter_map(|scheme| self.uri.strip_prefix(scheme))
+            .any(|path| !path.is_empty());
+        require!(has_path, JournalError::InvalidContentUri);
+        Ok(())
+    }
+}
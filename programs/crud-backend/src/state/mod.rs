@@ -0,0 +1,26 @@
+pub mod journal_entry;
+pub use journal_entry::*;
+
+pub mod revision;
+pub use revision::*;
+
+pub mod entry_page;
+pub use entry_page::*;
+
+pub mod entry_grant;
+pub use entry_grant::*;
+
+pub mod notebook;
+pub use notebook::*;
+
+pub mod tag;
+pub use tag::*;
+
+pub mod category_registry;
+pub use category_registry::*;
+
+pub mod user_profile;
+pub use user_profile::*;
+
+pub mod entry_index;
+pub use entry_index::*;
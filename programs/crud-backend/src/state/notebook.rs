@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_NOTEBOOK_MEMBERS, MAX_NOTEBOOK_NAME_LEN};
+use crate::error::JournalError;
+
+/// What a notebook member may do
+/// Ordered: viewers only read, editors also create and update entries,
+/// admins also delete entries and manage members
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, InitSpace)]
+pub enum NotebookRole {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct NotebookMember {
+    pub member: Pubkey,       // 32 bytes
+    pub role: NotebookRole,   // 1 byte
+}
+
+/// A shared notebook: its entries are `JournalEntryState` accounts owned by
+/// the notebook's address, at seeds `[b"entry", notebook, id]`
+#[account]
+#[derive(InitSpace)]
+pub struct Notebook {
+    pub admin: Pubkey,        // 32 bytes - creator, always an admin, also in the seeds
+
+    #[max_len(MAX_NOTEBOOK_NAME_LEN)]
+    pub name: String,         // 4 + 50 bytes - also in the seeds, hashed
+
+    #[max_len(MAX_NOTEBOOK_MEMBERS)]
+    pub members: Vec<NotebookMember>, // 4 + 20 * 33 bytes - everyone but the admin
+    pub next_entry_id: u64,   // 8 bytes - next entry id, never decreases
+    pub created_at: i64,      // 8 bytes
+}
+
+impl Notebook {
+    pub fn role_of(&self, key: &Pubkey) -> Option<NotebookRole> {
+        if *key == self.admin {
+            return Some(NotebookRole::Admin);
+        }
+        self.members
+            .iter()
+            .find(|m| m.member == *key)
+            .map(|m| m.role)
+    }
+
+    pub(crate) fn require_role(&self, key: &Pubkey, needed: NotebookRole) -> Result<()> {
+        match self.role_of(key) {
+            Some(role) if role >= needed => Ok(()),
+            _ => err!(JournalError::NotebookRoleDenied),
+        }
+    }
+}
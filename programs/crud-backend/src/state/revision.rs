@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::prelude::borsh;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::constants::{MAX_CATEGORY_LEN, MAX_ENTRY_CONTENT_LEN, MAX_TITLE_LEN};
+use crate::state::journal_entry::{
+    EncryptedContent,
+    JournalEntryState,
+    OffChainContent,
+    sealed_space,
+};
+
+/// An entry as it was before an update or restore
+/// Each revision hashes the one before it, so the entry's `revision_hash`
+/// commits to its whole history. Ids come from the owner's profile and are
+/// never reused, which keeps revisions findable after the entry is renamed
+/// or deleted
+#[account]
+#[derive(InitSpace)]
+pub struct Revision {
+    pub owner: Pubkey,        // 32 bytes - the entry's owner
+    pub entry: Pubkey,        // 32 bytes - the entry's address when this was recorded
+    pub id: u64,              // 8 bytes - revision id, also in the seeds
+    pub history: u64,         // 8 bytes - the entry's history id
+    pub previous: Option<u64>, // 1 + 8 bytes - the revision before this one
+    pub previous_hash: [u8; 32], // 32 bytes - its hash, zero for the first
+    #[max_len(MAX_TITLE_LEN)]
+    pub title: String,        // 4 + len bytes
+    #[max_len(MAX_ENTRY_CONTENT_LEN)]
+    pub content: String,      // 4 + len bytes
+    #[max_len(MAX_CATEGORY_LEN)]
+    pub category: String,     // 4 + len bytes
+    pub encrypted: Option<EncryptedContent>, // 1 byte, or more for encrypted entries
+    pub off_chain: Option<OffChainContent>, // 1 byte, or more for off-chain entries
+    pub updated_at: i64,      // 8 bytes - when this version was written
+    pub replaced_at: i64,     // 8 bytes - when it was replaced
+    pub rent_payer: Pubkey,   // 32 bytes - refunded when the revision is closed
+}
+
+impl Revision {
+    /// Account size for a snapshot of `entry`
+    /// Sized like `JournalEntryState::space_for`, from `INIT_SPACE`
+    pub fn space_for(entry: &JournalEntryState) -> usize {
+        8 + Self::INIT_SPACE + entry.title.len() + entry.content.len() + entry.category.len()
+            - MAX_TITLE_LEN
+            - MAX_ENTRY_CONTENT_LEN
+            - MAX_CATEGORY_LEN
+            - EncryptedContent::INIT_SPACE
+            - OffChainContent::INIT_SPACE
+            + sealed_space(&entry.encrypted, &entry.off_chain)
+    }
+
+    /// Size of `entry` once restored to this revision
+    pub fn restored_space(&self, entry: &JournalEntryState) -> usize {
+        let title_len = match entry.entry_id {
+            Some(_) => self.title.len(),
+            None => entry.title.len(), // Title-addressed entries keep their title
+        };
+        JournalEntryState::space_for(title_len, self.content.len(), self.category.len())
+            + sealed_space(&self.encrypted, &self.off_chain)
+            + entry.tag_space()
+            + entry.rent_payer_space()
+    }
+
+    /// This revision's link in the hash chain: SHA-256 of its fields,
+    /// `previous_hash` included
+    pub fn hash(&self) -> [u8; 32] {
+        let data = borsh::to_vec(self).expect("serializing to a Vec doesn't fail");
+        hash(&data).to_bytes()
+    }
+}
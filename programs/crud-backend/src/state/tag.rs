@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{MAX_TAG_LEN, TAG_INDEX_PAGE_SIZE};
+use crate::error::JournalError;
+
+/// One of a user's tags, and how far its `TagIndex` pages are filled
+/// Kept once the tag's entries are gone, so page numbers stay stable
+#[account]
+#[derive(InitSpace)]
+pub struct TagState {
+    pub owner: Pubkey,        // 32 bytes - whose tag this is
+
+    #[max_len(MAX_TAG_LEN)]
+    pub tag: String,          // 4 + 32 bytes - also in the seeds, hashed
+
+    pub tagged: u64,          // 8 bytes - slots used across all of the tag's TagIndex pages
+}
+
+/// One page of the entries carrying one of a user's tags, in the order
+/// they were tagged
+/// Like `EntryIndex` pages, slots never shift: an untagged or deleted entry
+/// leaves `Pubkey::default()` in its slot and a renamed one is replaced in
+/// place. A full page whose entries are all gone is closed
+#[account]
+#[derive(InitSpace)]
+pub struct TagIndex {
+    pub owner: Pubkey,        // 32 bytes - whose entries these are
+
+    #[max_len(MAX_TAG_LEN)]
+    pub tag: String,          // 4 + 32 bytes - also in the seeds, hashed
+
+    pub page: u32,            // 4 bytes - page number, also in the seeds
+
+    #[max_len(TAG_INDEX_PAGE_SIZE)]
+    pub entries: Vec<Pubkey>, // 4 + 25 * 32 bytes - entry addresses
+}
+
+impl TagIndex {
+    /// The page that the slot after `tagged` falls on
+    pub fn page_of(tagged: u64) -> u32 {
+        (tagged / TAG_INDEX_PAGE_SIZE as u64) as u32
+    }
+
+    /// Appends `entry`; the page is the tag's last, so it is fresh when it
+    /// was just created
+    pub(crate) fn push(&mut self, tag_state: &mut TagState, entry: Pubkey) {
+        if self.entries.is_empty() {
+            self.owner = tag_state.owner;
+            self.tag = tag_state.tag.clone();
+            self.page = Self::page_of(tag_state.tagged);
+        }
+        self.entries.push(entry);
+        tag_state.tagged += 1;
+    }
+
+    pub(crate) fn replace(&mut self, old: &Pubkey, new: Pubkey) -> Result<()> {
+        let slot = self
+            .entries
+            .iter_mut()
+            .find(|entry| *entry == old)
+            .ok_or(JournalError::WrongTagIndexPage)?;
+        *slot = new;
+        Ok(())
+    }
+
+    /// Full, with all of its entries gone: nothing will be listed here again
+    pub(crate) fn is_spent(&self) -> bool {
+        self.entries.len() == TAG_INDEX_PAGE_SIZE
+            && self.entries.iter().all(|entry| *entry == Pubkey::default())
+    }
+}
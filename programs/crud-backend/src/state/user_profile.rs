@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// User profile to track statistics and metadata
+#[account]
+#[derive(InitSpace)]
+pub struct UserProfile {
+    pub owner: Pubkey,        // 32 bytes - profile owner
+    pub total_entries: u32,   // 4 bytes - count of entries created
+    pub created_at: i64,      // 8 bytes - when profile was created
+    pub next_entry_id: u64,   // 8 bytes - next numbered entry id, never decreases
+    pub indexed_entries: u64, // 8 bytes - slots used across all EntryIndex pages
+    pub next_revision_id: u64, // 8 bytes - next revision id, never decreases
+    pub category_registry: bool, // 1 byte - entries are counted in a CategoryRegistry
+}
@@ -0,0 +1,141 @@
+//! Account sizes derived from `INIT_SPACE` against what the accounts
+//! actually serialize to, for every mix of optional fields.
+
+use anchor_lang::prelude::borsh;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use crud_backend::{
+    EncryptedContent, EntryPage, JournalEntryState, OffChainContent, Revision,
+    ENCRYPTION_SCHEME_XCHACHA20_POLY1305, MAX_CIPHERTEXT_LEN, MAX_CONTENT_LEN, MAX_CONTENT_URI_LEN,
+    MAX_ENTRY_CONTENT_LEN, MAX_ENTRY_PAGE_CONTENT_LEN, MAX_ENTRY_TAGS, MAX_TAG_LEN, MAX_TITLE_LEN,
+};
+
+/// Serialized size of an account, discriminator included.
+fn serialized_len<T: borsh::BorshSerialize>(account: &T) -> usize {
+    8 + borsh::to_vec(account).unwrap().len()
+}
+
+fn entry(
+    title_len: usize,
+    content_len: usize,
+    sealed: (Option<usize>, Option<usize>),
+    tags: usize,
+    rent_payer: bool,
+) -> JournalEntryState {
+    JournalEntryState {
+        owner: Pubkey::new_unique(),
+        title: "t".repeat(title_len),
+        content: "c".repeat(content_len),
+        category: "work".to_string(),
+        is_archived: false,
+        created_at: 1,
+        updated_at: 2,
+        entry_id: Some(3),
+        index_page: Some(0),
+        content_pages: 0,
+        encrypted: sealed.0.map(|len| EncryptedContent {
+            scheme: ENCRYPTION_SCHEME_XCHACHA20_POLY1305,
+            nonce: [7; 24],
+            ciphertext: vec![9; len],
+        }),
+        off_chain: sealed.1.map(|len| OffChainContent {
+            uri: "u".repeat(len),
+            sha256: [5; 32],
+        }),
+        history: Some(0),
+        last_revision: Some(4),
+        revision_hash: [1; 32],
+        grants: 2,
+        category_counted: true,
+        tags: (0..tags).map(|tag| format!("tag-{tag}")).collect(),
+        rent_payer: rent_payer.then(Pubkey::new_unique),
+    }
+}
+
+fn shapes() -> Vec<JournalEntryState> {
+    vec![
+        entry(0, 0, (None, None), 0, false),
+        entry(5, 17, (None, None), 0, false),
+        entry(MAX_TITLE_LEN, MAX_CONTENT_LEN, (None, None), 3, true),
+        entry(
+            12,
+            MAX_ENTRY_CONTENT_LEN,
+            (None, None),
+            MAX_ENTRY_TAGS,
+            false,
+        ),
+        entry(8, 0, (Some(MAX_CIPHERTEXT_LEN), None), 1, false),
+        entry(8, 0, (None, Some(MAX_CONTENT_URI_LEN)), 0, true),
+    ]
+}
+
+#[test]
+fn entries_are_sized_to_what_they_serialize_to() {
+    for entry in shapes() {
+        assert_eq!(
+            entry.space_with_title(entry.title.len()),
+            serialized_len(&entry)
+        );
+    }
+}
+
+#[test]
+fn entry_size_pieces_add_up() {
+    let plain = entry(9, 40, (None, None), 0, false);
+    assert_eq!(
+        JournalEntryState::space_for(9, 40, 4),
+        serialized_len(&plain)
+    );
+
+    let encrypted = entry(9, 0, (Some(60), None), 0, false);
+    assert_eq!(
+        JournalEntryState::encrypted_space_for(9, 60, 4),
+        serialized_len(&encrypted)
+    );
+
+    let off_chain = entry(9, 0, (None, Some(30)), 0, false);
+    assert_eq!(
+        JournalEntryState::off_chain_space_for(9, 30, 4),
+        serialized_len(&off_chain)
+    );
+
+    let tags = vec!["a".repeat(MAX_TAG_LEN), "b".to_string()];
+    assert_eq!(
+        JournalEntryState::space_for_tags(&tags),
+        (4 + MAX_TAG_LEN) + (4 + 1)
+    );
+}
+
+#[test]
+fn revisions_are_sized_to_what_they_serialize_to() {
+    for entry in shapes() {
+        let revision = Revision {
+            owner: entry.owner,
+            entry: Pubkey::new_unique(),
+            id: 4,
+            history: 0,
+            previous: Some(3),
+            previous_hash: [2; 32],
+            title: entry.title.clone(),
+            content: entry.content.clone(),
+            category: entry.category.clone(),
+            encrypted: entry.encrypted.clone(),
+            off_chain: entry.off_chain.clone(),
+            updated_at: 2,
+            replaced_at: 5,
+            rent_payer: Pubkey::new_unique(),
+        };
+        assert_eq!(Revision::space_for(&entry), serialized_len(&revision));
+    }
+}
+
+#[test]
+fn pages_are_sized_to_what_they_serialize_to() {
+    for len in [0, 1, 900, MAX_ENTRY_PAGE_CONTENT_LEN] {
+        let page = EntryPage {
+            entry: Pubkey::new_unique(),
+            page: 1,
+            content: "p".repeat(len),
+        };
+        assert_eq!(EntryPage::space_for(len), serialized_len(&page));
+    }
+}
@@ -0,0 +1,192 @@
+//! The per-user `CategoryRegistry`: entry counts by category, kept by
+//! create, update and delete, and the strict mode that rejects unlisted
+//! categories.
+
+mod common;
+
+use anchor_lang::system_program;
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    CategoryAdded, CategoryRegistryCreated, CategoryRemoved, JournalError, MAX_CATEGORIES,
+};
+use solana_sdk::signer::Signer;
+
+fn counts(pairs: &[(&str, u32)]) -> Vec<(String, u32)> {
+    pairs
+        .iter()
+        .map(|(name, entries)| (name.to_string(), *entries))
+        .collect()
+}
+
+#[test]
+fn counts_follow_entries_through_create_update_and_delete() {
+    let mut journal = Journal::new();
+    let meta = journal.initialize_category_registry(false).unwrap();
+    let created: CategoryRegistryCreated = event(&meta);
+    assert_eq!(created.owner, journal.owner.pubkey());
+    assert!(!created.strict);
+    assert!(journal.profile().category_registry);
+
+    // Unlisted categories are added as they're used
+    journal.create("Standup", "notes", "work").unwrap();
+    journal.create_numbered("Retro", "notes", "work").unwrap();
+    journal.create("Groceries", "milk", "home").unwrap();
+    journal.create("Loose", "no category", "").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 2), ("home", 1)])
+    );
+
+    journal.update("Standup", "notes", "home").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 1), ("home", 2)])
+    );
+
+    // A renamed entry stays counted
+    journal.rename("Standup", "Daily").unwrap();
+    journal.delete("Daily").unwrap();
+    journal.delete("Groceries").unwrap();
+    journal.delete("Loose").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 1), ("home", 0)])
+    );
+
+    // Restoring a revision brings its category back
+    let retro = journal.numbered_entry_address(0);
+    let revision = journal.profile().next_revision_id;
+    journal.update_at(&retro, "Retro", "notes", "home").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 0), ("home", 1)])
+    );
+    journal.restore_revision(&retro, revision).unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 1), ("home", 0)])
+    );
+}
+
+#[test]
+fn strict_registries_only_take_listed_categories() {
+    let mut journal = Journal::new();
+    journal.initialize_category_registry(true).unwrap();
+
+    let error = instruction_error(journal.create("Standup", "notes", "work"));
+    assert_eq!(error, custom(JournalError::UnknownCategory));
+    // Uncategorized entries are always fine
+    journal.create("Loose", "no category", "").unwrap();
+
+    let meta = journal.add_category("work").unwrap();
+    let added: CategoryAdded = event(&meta);
+    assert_eq!(added.name, "work");
+    let error = instruction_error(journal.add_category("work"));
+    assert_eq!(error, custom(JournalError::CategoryExists));
+    journal.create("Standup", "notes", "work").unwrap();
+    let error = instruction_error(journal.update("Standup", "notes", "wrok"));
+    assert_eq!(error, custom(JournalError::UnknownCategory));
+
+    journal.set_category_strict(false).unwrap();
+    journal.update("Standup", "notes", "wrok").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 0), ("wrok", 1)])
+    );
+
+    let error = instruction_error(journal.remove_category("wrok"));
+    assert_eq!(error, custom(JournalError::CategoryInUse));
+    let meta = journal.remove_category("work").unwrap();
+    let removed: CategoryRemoved = event(&meta);
+    assert_eq!(removed.name, "work");
+    let error = instruction_error(journal.remove_category("work"));
+    assert_eq!(error, custom(JournalError::CategoryNotFound));
+
+    for category in 1..MAX_CATEGORIES {
+        journal
+            .add_category(&format!("category {category}"))
+            .unwrap();
+    }
+    let error = instruction_error(journal.add_category("one more"));
+    assert_eq!(error, custom(JournalError::TooManyCategories));
+    let error = instruction_error(journal.create("Full", "notes", "one more"));
+    assert_eq!(error, custom(JournalError::TooManyCategories));
+}
+
+#[test]
+fn the_registry_is_required_once_it_exists() {
+    let mut journal = Journal::new();
+    journal.create("Before", "notes", "work").unwrap();
+    journal.create("Older", "notes", "work").unwrap();
+    journal.initialize_category_registry(false).unwrap();
+    assert!(journal.category_counts().is_empty());
+
+    // Leaving the registry out would let the counts drift
+    let instruction = journal.instruction(
+        crud_backend::accounts::CreateJournalEntry {
+            owner: journal.owner.pubkey(),
+            journal_entry: journal.entry_address("Sneaky"),
+            user_profile: journal.profile_address(),
+            entry_index: journal.index_address(journal.last_index_page()),
+            system_program: system_program::ID,
+            category_registry: None,
+        },
+        crud_backend::instruction::CreateJournalEntry {
+            title: "Sneaky".to_string(),
+            content: "notes".to_string(),
+            category: "work".to_string(),
+            tags: vec![],
+        },
+    );
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::CategoryRegistryMissing));
+
+    // Older entries are counted from their next update
+    journal.update("Before", "notes", "work").unwrap();
+    assert_eq!(journal.category_counts(), counts(&[("work", 1)]));
+    journal.delete("Older").unwrap();
+    assert_eq!(journal.category_counts(), counts(&[("work", 1)]));
+    journal.delete("Before").unwrap();
+    assert_eq!(journal.category_counts(), counts(&[("work", 0)]));
+}
+
+#[test]
+fn older_entries_can_be_back_filled() {
+    let mut journal = Journal::new();
+    journal.create("Standup", "notes", "work").unwrap();
+    journal.create_numbered("Retro", "notes", "team").unwrap();
+    journal.create("Loose", "no category", "").unwrap();
+    journal.initialize_category_registry(true).unwrap();
+    journal.add_category("work").unwrap();
+    let (standup, retro) = (
+        journal.entry_address("Standup"),
+        journal.numbered_entry_address(0),
+    );
+
+    journal.count_entry(&standup).unwrap();
+    assert!(journal.entry("Standup").unwrap().category_counted);
+    assert_eq!(journal.category_counts(), counts(&[("work", 1)]));
+    let error = instruction_error(journal.count_entry(&standup));
+    assert_eq!(error, custom(JournalError::EntryAlreadyCounted));
+
+    // Strict registries still only take listed categories
+    let error = instruction_error(journal.count_entry(&retro));
+    assert_eq!(error, custom(JournalError::UnknownCategory));
+    journal.add_category("team").unwrap();
+    journal.count_entry(&retro).unwrap();
+    journal
+        .count_entry(&journal.entry_address("Loose"))
+        .unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 1), ("team", 1)])
+    );
+
+    // Counted entries come off the count when they go
+    journal.delete("Standup").unwrap();
+    journal.delete_at(&retro, "Retro").unwrap();
+    assert_eq!(
+        journal.category_counts(),
+        counts(&[("work", 0), ("team", 0)])
+    );
+}
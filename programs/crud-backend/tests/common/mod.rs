@@ -0,0 +1,1172 @@
+//! A journal on an in-process SVM: the crud-backend program and one funded
+//! owner with an initialized profile.
+
+#![allow(dead_code)]
+
+use anchor_lang::system_program;
+use anchor_lang::{
+    AccountDeserialize, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use crud_backend::{
+    title_seed, CategoryRegistry, EncryptedContent, EntryGrantsResponse, EntryIndex,
+    EntryIndexPageResponse, EntryPage, GrantPermission, JournalEntryState, Notebook, NotebookRole,
+    OffChainContent, Revision, TagEntriesResponse, TagIndex, TagState, UserProfile,
+};
+use local_svm::{FailedTransaction, LocalSvm, TransactionMetadata, UpgradeableProgram};
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::TransactionError;
+
+pub struct Journal {
+    pub svm: LocalSvm,
+    pub owner: Keypair,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        let mut svm = LocalSvm::new(&[UpgradeableProgram {
+            name: "crud_backend",
+            program_id: crud_backend::ID,
+            processor: crud_backend::entry,
+            upgrade_authority: None,
+        }]);
+        let owner = Keypair::new();
+        svm.airdrop(&owner.pubkey(), 10 * LAMPORTS_PER_SOL);
+
+        let mut journal = Self { svm, owner };
+        let instruction = journal.instruction(
+            crud_backend::accounts::InitializeUserProfile {
+                owner: journal.owner.pubkey(),
+                user_profile: journal.profile_address(),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::InitializeUserProfile {},
+        );
+        journal.send(instruction).unwrap();
+        journal
+    }
+
+    /// A new keypair with 1 SOL, for members, grantees and strangers.
+    pub fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL);
+        keypair
+    }
+
+    pub fn profile_address(&self) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(&[b"profile", owner.as_ref()], &crud_backend::ID).0
+    }
+
+    pub fn entry_address(&self, title: &str) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(&[&title_seed(title), owner.as_ref()], &crud_backend::ID).0
+    }
+
+    pub fn numbered_entry_address(&self, id: u64) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[b"entry", owner.as_ref(), &id.to_le_bytes()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn index_address(&self, page: u32) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[b"index", owner.as_ref(), &page.to_le_bytes()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn entry_page_address(&self, entry: &Pubkey, page: u32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"entry_page", entry.as_ref(), &page.to_le_bytes()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn revision_address(&self, id: u64) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[b"revision", owner.as_ref(), &id.to_le_bytes()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    /// Where the next update records the version it replaces.
+    pub fn next_revision_address(&self) -> Pubkey {
+        self.revision_address(self.profile().next_revision_id)
+    }
+
+    pub fn grant_address(&self, entry: &Pubkey, grantee: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"grant", entry.as_ref(), grantee.as_ref()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn entry_grants_address(&self, entry: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"grants", entry.as_ref()], &crud_backend::ID).0
+    }
+
+    pub fn tag_state_address(&self, tag: &str) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[b"tag", owner.as_ref(), &title_seed(tag)],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn tag_index_address(&self, tag: &str, page: u32) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[
+                b"tag",
+                owner.as_ref(),
+                &title_seed(tag),
+                &page.to_le_bytes(),
+            ],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    /// The page the tag's next entry goes on.
+    pub fn last_tag_page(&self, tag: &str) -> u32 {
+        let tagged = self
+            .decode::<TagState>(&self.tag_state_address(tag))
+            .map_or(0, |tag_state| tag_state.tagged);
+        TagIndex::page_of(tagged)
+    }
+
+    /// The tag's `TagIndex` page listing `entry`.
+    pub fn tag_page_of(&self, entry: &Pubkey, tag: &str) -> u32 {
+        (0..=self.last_tag_page(tag))
+            .find(|page| {
+                self.decode::<TagIndex>(&self.tag_index_address(tag, *page))
+                    .is_some_and(|tag_index| tag_index.entries.contains(entry))
+            })
+            .unwrap_or_default()
+    }
+
+    /// The `TagIndex` pages listing the entry for each of its tags, in
+    /// order, as rename and delete take them.
+    pub fn tag_index_metas(&self, entry: &Pubkey) -> Vec<AccountMeta> {
+        let tags = self
+            .decode::<JournalEntryState>(entry)
+            .map_or(vec![], |entry| entry.tags);
+        tags.iter()
+            .map(|tag| {
+                let page = self.tag_page_of(entry, tag);
+                AccountMeta::new(self.tag_index_address(tag, page), false)
+            })
+            .collect()
+    }
+
+    /// Each tag's `TagState` and last `TagIndex` page, as the create
+    /// instructions take them.
+    pub fn new_tag_metas(&self, tags: &[&str]) -> Vec<AccountMeta> {
+        tags.iter()
+            .flat_map(|tag| {
+                [
+                    AccountMeta::new(self.tag_state_address(tag), false),
+                    AccountMeta::new(self.tag_index_address(tag, self.last_tag_page(tag)), false),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn categories_address(&self) -> Pubkey {
+        let owner = self.owner.pubkey();
+        Pubkey::find_program_address(&[b"categories", owner.as_ref()], &crud_backend::ID).0
+    }
+
+    /// The registry entry instructions need: the owner's, once they have one.
+    pub fn category_registry_address(&self) -> Option<Pubkey> {
+        let registry = self.categories_address();
+        self.svm.get_account(&registry).map(|_| registry)
+    }
+
+    /// The owner's notebook called `name`.
+    pub fn notebook_address(&self, name: &str) -> Pubkey {
+        let admin = self.owner.pubkey();
+        Pubkey::find_program_address(
+            &[b"notebook", admin.as_ref(), &title_seed(name)],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    pub fn notebook_entry_address(&self, notebook: &Pubkey, id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"entry", notebook.as_ref(), &id.to_le_bytes()],
+            &crud_backend::ID,
+        )
+        .0
+    }
+
+    /// The index page the next entry will be listed on.
+    /// Who `entry`'s rent goes back to.
+    pub fn rent_holder(&self, entry: &Pubkey) -> Pubkey {
+        self.decode::<JournalEntryState>(entry)
+            .map_or(Pubkey::default(), |entry| entry.rent_holder())
+    }
+
+    pub fn last_index_page(&self) -> u32 {
+        EntryIndex::page_of(self.profile().indexed_entries)
+    }
+
+    /// The index page listing the entry at `entry`, if it is indexed.
+    pub fn index_of(&self, entry: &Pubkey) -> Option<Pubkey> {
+        let entry: JournalEntryState = self.decode(entry)?;
+        Some(self.index_address(entry.index_page?))
+    }
+
+    pub fn profile(&self) -> UserProfile {
+        self.decode(&self.profile_address()).unwrap()
+    }
+
+    pub fn entry(&self, title: &str) -> Option<JournalEntryState> {
+        self.decode(&self.entry_address(title))
+    }
+
+    pub fn decode<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        let account = self.svm.get_account(address)?;
+        Some(T::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Instruction {
+        Instruction {
+            program_id: crud_backend::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    /// Sends `instruction` signed by the owner.
+    pub fn send(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let owner = self.owner.insecure_clone();
+        self.svm.send_transaction(&[instruction], &[&owner])
+    }
+
+    pub fn create(
+        &mut self,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        self.create_tagged(title, content, category, &[])
+    }
+
+    /// Creates an entry carrying `tags` from the start.
+    pub fn create_tagged(
+        &mut self,
+        title: &str,
+        content: &str,
+        category: &str,
+        tags: &[&str],
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let mut instruction = self.instruction(
+            crud_backend::accounts::CreateJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: self.entry_address(title),
+                user_profile: self.profile_address(),
+                entry_index: self.index_address(self.last_index_page()),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::CreateJournalEntry {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            },
+        );
+        instruction.accounts.extend(self.new_tag_metas(tags));
+        self.send(instruction)
+    }
+
+    /// Creates a numbered entry, at the profile's `next_entry_id`.
+    pub fn create_numbered(
+        &mut self,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let id = self.profile().next_entry_id;
+        let page = self.last_index_page();
+        let instruction = self.create_numbered_ix(id, page, title, content, category);
+        self.send(instruction)
+    }
+
+    pub fn create_numbered_ix(
+        &self,
+        id: u64,
+        index_page: u32,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Instruction {
+        self.instruction(
+            crud_backend::accounts::CreateNumberedJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: self.numbered_entry_address(id),
+                user_profile: self.profile_address(),
+                entry_index: self.index_address(index_page),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::CreateNumberedJournalEntry {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+                tags: vec![],
+            },
+        )
+    }
+
+    pub fn update(
+        &mut self,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let entry = self.entry_address(title);
+        self.update_at(&entry, title, content, category)
+    }
+
+    /// Updates the entry at `entry`, which may be title-addressed or
+    /// numbered.
+    pub fn update_at(
+        &mut self,
+        entry: &Pubkey,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpdateJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                user_profile: self.profile_address(),
+                revision: self.next_revision_address(),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::UpdateJournalEntry {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn create_encrypted(
+        &mut self,
+        title: &str,
+        category: &str,
+        encrypted: EncryptedContent,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::CreateEncryptedJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: self.entry_address(title),
+                user_profile: self.profile_address(),
+                entry_index: self.index_address(self.last_index_page()),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::CreateEncryptedJournalEntry {
+                title: title.to_string(),
+                category: category.to_string(),
+                encrypted,
+                tags: vec![],
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn update_encrypted_at(
+        &mut self,
+        entry: &Pubkey,
+        title: &str,
+        category: &str,
+        encrypted: EncryptedContent,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpdateEncryptedJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                user_profile: self.profile_address(),
+                revision: self.next_revision_address(),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::UpdateEncryptedJournalEntry {
+                title: title.to_string(),
+                category: category.to_string(),
+                encrypted,
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn create_off_chain(
+        &mut self,
+        title: &str,
+        category: &str,
+        off_chain: OffChainContent,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::CreateOffChainJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: self.entry_address(title),
+                user_profile: self.profile_address(),
+                entry_index: self.index_address(self.last_index_page()),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::CreateOffChainJournalEntry {
+                title: title.to_string(),
+                category: category.to_string(),
+                off_chain,
+                tags: vec![],
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn update_off_chain_at(
+        &mut self,
+        entry: &Pubkey,
+        title: &str,
+        category: &str,
+        off_chain: OffChainContent,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpdateOffChainJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                user_profile: self.profile_address(),
+                revision: self.next_revision_address(),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::UpdateOffChainJournalEntry {
+                title: title.to_string(),
+                category: category.to_string(),
+                off_chain,
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn restore_revision(
+        &mut self,
+        entry: &Pubkey,
+        revision_id: u64,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::RestoreRevision {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                restored_revision: self.revision_address(revision_id),
+                user_profile: self.profile_address(),
+                revision: self.next_revision_address(),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::RestoreRevision { revision_id },
+        );
+        self.send(instruction)
+    }
+
+    pub fn close_revision(
+        &mut self,
+        revision_id: u64,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let revision = self.revision_address(revision_id);
+        let rent_payer = self
+            .decode::<Revision>(&revision)
+            .map_or(Pubkey::default(), |revision| revision.rent_payer);
+        let instruction = self.instruction(
+            crud_backend::accounts::CloseRevision {
+                owner: self.owner.pubkey(),
+                revision,
+                rent_payer,
+            },
+            crud_backend::instruction::CloseRevision { revision_id },
+        );
+        self.send(instruction)
+    }
+
+    pub fn toggle_archive(
+        &mut self,
+        title: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let entry = self.entry_address(title);
+        self.toggle_archive_at(&entry, title)
+    }
+
+    pub fn toggle_archive_at(
+        &mut self,
+        entry: &Pubkey,
+        title: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::ToggleArchiveEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::ToggleArchiveEntry {
+                title: title.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn grant_access(
+        &mut self,
+        entry: &Pubkey,
+        grantee: &Pubkey,
+        permission: GrantPermission,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::GrantEntryAccess {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                entry_grants: self.entry_grants_address(entry),
+                grant: self.grant_address(entry, grantee),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::GrantEntryAccess {
+                grantee: *grantee,
+                permission,
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn revoke_access(
+        &mut self,
+        entry: &Pubkey,
+        grantee: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::RevokeEntryAccess {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                entry_grants: self.entry_grants_address(entry),
+                grant: self.grant_address(entry, grantee),
+            },
+            crud_backend::instruction::RevokeEntryAccess { grantee: *grantee },
+        );
+        self.send(instruction)
+    }
+
+    /// Lists the entry's grants through the `get_entry_grants` view.
+    pub fn entry_grants(
+        &mut self,
+        entry: &Pubkey,
+    ) -> Result<EntryGrantsResponse, FailedTransaction> {
+        let list = self.entry_grants_address(entry);
+        let grantees = self
+            .decode::<crud_backend::EntryGrantList>(&list)
+            .map_or(vec![], |list| list.grantees);
+        let mut instruction = self.instruction(
+            crud_backend::accounts::GetEntryGrants { entry_grants: list },
+            crud_backend::instruction::GetEntryGrants {},
+        );
+        instruction.accounts.extend(
+            grantees.iter().map(|grantee| {
+                AccountMeta::new_readonly(self.grant_address(entry, grantee), false)
+            }),
+        );
+        self.view(instruction)
+    }
+
+    /// Comments on `entry` as `author`, passing their grant unless they
+    /// own the entry.
+    pub fn comment_as(
+        &mut self,
+        author: &Keypair,
+        entry: &Pubkey,
+        comment: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let grant = (author.pubkey() != self.owner.pubkey())
+            .then(|| self.grant_address(entry, &author.pubkey()));
+        let instruction = self.instruction(
+            crud_backend::accounts::CommentOnEntry {
+                author: author.pubkey(),
+                journal_entry: *entry,
+                grant,
+            },
+            crud_backend::instruction::CommentOnEntry {
+                comment: comment.to_string(),
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[author])
+    }
+
+    /// Updates the owner's entry at `entry` as `grantee`.
+    pub fn update_as(
+        &mut self,
+        grantee: &Keypair,
+        entry: &Pubkey,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpdateJournalEntryAsGrantee {
+                grantee: grantee.pubkey(),
+                journal_entry: *entry,
+                owner: self.owner.pubkey(),
+                grant: self.grant_address(entry, &grantee.pubkey()),
+                user_profile: self.profile_address(),
+                revision: self.next_revision_address(),
+                system_program: system_program::ID,
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::UpdateJournalEntryAsGrantee {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[grantee])
+    }
+
+    /// Archives or unarchives the owner's entry at `entry` as `grantee`.
+    pub fn toggle_archive_as(
+        &mut self,
+        grantee: &Keypair,
+        entry: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::ToggleArchiveEntryAsGrantee {
+                grantee: grantee.pubkey(),
+                journal_entry: *entry,
+                grant: self.grant_address(entry, &grantee.pubkey()),
+            },
+            crud_backend::instruction::ToggleArchiveEntryAsGrantee {},
+        );
+        self.svm.send_transaction(&[instruction], &[grantee])
+    }
+
+    pub fn tag_entry(
+        &mut self,
+        entry: &Pubkey,
+        tag: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::TagEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                tag_state: self.tag_state_address(tag),
+                tag_index: self.tag_index_address(tag, self.last_tag_page(tag)),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::TagEntry {
+                tag: tag.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn untag_entry(
+        &mut self,
+        entry: &Pubkey,
+        tag: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let page = self.tag_page_of(entry, tag);
+        let instruction = self.instruction(
+            crud_backend::accounts::UntagEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                tag_index: self.tag_index_address(tag, page),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::UntagEntry {
+                tag: tag.to_string(),
+                page,
+            },
+        );
+        self.send(instruction)
+    }
+
+    /// Lists a page of the entries tagged `tag` through the
+    /// `get_tag_entries` view.
+    pub fn tag_entries_page(
+        &mut self,
+        tag: &str,
+        page: u32,
+    ) -> Result<TagEntriesResponse, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::GetTagEntries {
+                owner: self.owner.pubkey(),
+                tag_state: self.tag_state_address(tag),
+                tag_index: self.tag_index_address(tag, page),
+            },
+            crud_backend::instruction::GetTagEntries {
+                tag: tag.to_string(),
+                page,
+            },
+        );
+        self.view(instruction)
+    }
+
+    /// Every entry tagged `tag`, following `next_page` from the first page.
+    pub fn tag_entries(&mut self, tag: &str) -> Result<Vec<Pubkey>, FailedTransaction> {
+        let mut entries = vec![];
+        let mut page = Some(0);
+        while let Some(current) = page {
+            let listed = self.tag_entries_page(tag, current)?;
+            entries.extend(listed.entries);
+            page = listed.next_page;
+        }
+        Ok(entries)
+    }
+
+    pub fn initialize_category_registry(
+        &mut self,
+        strict: bool,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::InitializeCategoryRegistry {
+                owner: self.owner.pubkey(),
+                user_profile: self.profile_address(),
+                category_registry: self.categories_address(),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::InitializeCategoryRegistry { strict },
+        );
+        self.send(instruction)
+    }
+
+    pub fn add_category(&mut self, name: &str) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            self.manage_categories(),
+            crud_backend::instruction::AddCategory {
+                name: name.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn remove_category(
+        &mut self,
+        name: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            self.manage_categories(),
+            crud_backend::instruction::RemoveCategory {
+                name: name.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn set_category_strict(
+        &mut self,
+        strict: bool,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            self.manage_categories(),
+            crud_backend::instruction::SetCategoryStrict { strict },
+        );
+        self.send(instruction)
+    }
+
+    /// Back-fills the count for an entry made before the registry.
+    pub fn count_entry(
+        &mut self,
+        entry: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::CountEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                category_registry: self.categories_address(),
+            },
+            crud_backend::instruction::CountEntry {},
+        );
+        self.send(instruction)
+    }
+
+    /// Brings an entry in an older layout up to the current one.
+    pub fn upgrade_entry(
+        &mut self,
+        entry: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpgradeEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::UpgradeEntry {},
+        );
+        self.send(instruction)
+    }
+
+    fn manage_categories(&self) -> crud_backend::accounts::ManageCategories {
+        crud_backend::accounts::ManageCategories {
+            owner: self.owner.pubkey(),
+            category_registry: self.categories_address(),
+        }
+    }
+
+    /// The registry's categories and their entry counts, in order.
+    pub fn category_counts(&self) -> Vec<(String, u32)> {
+        let registry: CategoryRegistry = self.decode(&self.categories_address()).unwrap();
+        registry
+            .categories
+            .into_iter()
+            .map(|category| (category.name, category.entries))
+            .collect()
+    }
+
+    /// Creates a notebook administered by the owner.
+    pub fn create_notebook(
+        &mut self,
+        name: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::CreateNotebook {
+                admin: self.owner.pubkey(),
+                notebook: self.notebook_address(name),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::CreateNotebook {
+                name: name.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn set_notebook_member_as(
+        &mut self,
+        signer: &Keypair,
+        notebook: &Pubkey,
+        member: &Pubkey,
+        role: NotebookRole,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::ManageNotebook {
+                signer: signer.pubkey(),
+                notebook: *notebook,
+            },
+            crud_backend::instruction::SetNotebookMember {
+                member: *member,
+                role,
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[signer])
+    }
+
+    pub fn remove_notebook_member_as(
+        &mut self,
+        signer: &Keypair,
+        notebook: &Pubkey,
+        member: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::ManageNotebook {
+                signer: signer.pubkey(),
+                notebook: *notebook,
+            },
+            crud_backend::instruction::RemoveNotebookMember { member: *member },
+        );
+        self.svm.send_transaction(&[instruction], &[signer])
+    }
+
+    /// Creates an entry in `notebook` as `member`, at its `next_entry_id`.
+    pub fn create_notebook_entry_as(
+        &mut self,
+        member: &Keypair,
+        notebook: &Pubkey,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let id = self
+            .decode::<Notebook>(notebook)
+            .map_or(0, |notebook| notebook.next_entry_id);
+        let instruction = self.instruction(
+            crud_backend::accounts::CreateNotebookEntry {
+                member: member.pubkey(),
+                notebook: *notebook,
+                journal_entry: self.notebook_entry_address(notebook, id),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::CreateNotebookEntry {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[member])
+    }
+
+    pub fn update_notebook_entry_as(
+        &mut self,
+        member: &Keypair,
+        notebook: &Pubkey,
+        entry: &Pubkey,
+        title: &str,
+        content: &str,
+        category: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::UpdateNotebookEntry {
+                member: member.pubkey(),
+                notebook: *notebook,
+                journal_entry: *entry,
+                rent_payer: self.rent_holder(entry),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::UpdateNotebookEntry {
+                title: title.to_string(),
+                content: content.to_string(),
+                category: category.to_string(),
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[member])
+    }
+
+    pub fn delete_notebook_entry_as(
+        &mut self,
+        member: &Keypair,
+        notebook: &Pubkey,
+        entry: &Pubkey,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::DeleteNotebookEntry {
+                member: member.pubkey(),
+                notebook: *notebook,
+                journal_entry: *entry,
+                rent_payer: self.rent_holder(entry),
+            },
+            crud_backend::instruction::DeleteNotebookEntry {},
+        );
+        self.svm.send_transaction(&[instruction], &[member])
+    }
+
+    pub fn rename(
+        &mut self,
+        old_title: &str,
+        new_title: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let entry = self.entry_address(old_title);
+        let mut instruction = self.instruction(
+            crud_backend::accounts::RenameJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: entry,
+                new_journal_entry: self.entry_address(new_title),
+                system_program: system_program::ID,
+                entry_index: self.index_of(&entry),
+            },
+            crud_backend::instruction::RenameJournalEntry {
+                old_title: old_title.to_string(),
+                new_title: new_title.to_string(),
+            },
+        );
+        // The entry's tags move with it.
+        instruction.accounts.extend(self.tag_index_metas(&entry));
+        self.send(instruction)
+    }
+
+    pub fn append_content(
+        &mut self,
+        entry: &Pubkey,
+        chunk: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::AppendContent {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::AppendContent {
+                chunk: chunk.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn create_entry_page(
+        &mut self,
+        entry: &Pubkey,
+        page: u32,
+        chunk: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::CreateEntryPage {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                entry_page: self.entry_page_address(entry, page),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::CreateEntryPage {
+                page,
+                chunk: chunk.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    pub fn append_entry_page(
+        &mut self,
+        entry: &Pubkey,
+        page: u32,
+        chunk: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let instruction = self.instruction(
+            crud_backend::accounts::AppendEntryPage {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                entry_page: self.entry_page_address(entry, page),
+                system_program: system_program::ID,
+            },
+            crud_backend::instruction::AppendEntryPage {
+                page,
+                chunk: chunk.to_string(),
+            },
+        );
+        self.send(instruction)
+    }
+
+    /// The entry's full content: inline, then each page in order.
+    pub fn full_content(&self, entry: &Pubkey) -> String {
+        let state: JournalEntryState = self.decode(entry).unwrap();
+        let mut content = state.content;
+        for page in 0..state.content_pages {
+            let page: EntryPage = self.decode(&self.entry_page_address(entry, page)).unwrap();
+            content.push_str(&page.content);
+        }
+        content
+    }
+
+    /// Runs the `get_entry_index_page` view for `page`, passing the page's
+    /// live entries.
+    pub fn entry_index_page(
+        &mut self,
+        page: u32,
+    ) -> Result<EntryIndexPageResponse, FailedTransaction> {
+        let index = self.index_address(page);
+        let live = self
+            .decode::<EntryIndex>(&index)
+            .map(|index| index.entries)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| *entry != Pubkey::default());
+        let mut instruction = self.instruction(
+            crud_backend::accounts::GetEntryIndexPage {
+                owner: self.owner.pubkey(),
+                user_profile: self.profile_address(),
+                entry_index: index,
+            },
+            crud_backend::instruction::GetEntryIndexPage {},
+        );
+        instruction
+            .accounts
+            .extend(live.map(|entry| AccountMeta::new_readonly(entry, false)));
+        self.view(instruction)
+    }
+
+    /// Sends a view instruction and decodes what it returned.
+    pub fn view<T: AnchorDeserialize>(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<T, FailedTransaction> {
+        let meta = self.send(instruction)?;
+        let (program_id, data) = meta.return_data.expect("view returns data");
+        assert_eq!(program_id, crud_backend::ID);
+        Ok(T::try_from_slice(&data).unwrap())
+    }
+
+    pub fn delete(&mut self, title: &str) -> Result<TransactionMetadata, FailedTransaction> {
+        let entry = self.entry_address(title);
+        self.delete_at(&entry, title)
+    }
+
+    pub fn delete_at(
+        &mut self,
+        entry: &Pubkey,
+        title: &str,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let mut instruction = self.instruction(
+            crud_backend::accounts::DeleteJournalEntry {
+                owner: self.owner.pubkey(),
+                journal_entry: *entry,
+                user_profile: self.profile_address(),
+                system_program: system_program::ID,
+                entry_index: self.index_of(entry),
+                category_registry: self.category_registry_address(),
+            },
+            crud_backend::instruction::DeleteJournalEntry {
+                title: title.to_string(),
+            },
+        );
+        // The entry's content pages are closed along with it.
+        let pages = self
+            .decode::<JournalEntryState>(entry)
+            .map_or(0, |entry| entry.content_pages);
+        instruction.accounts.extend(
+            (0..pages).map(|page| AccountMeta::new(self.entry_page_address(entry, page), false)),
+        );
+        // So is it taken off its tags' lists.
+        instruction.accounts.extend(self.tag_index_metas(entry));
+        self.send(instruction)
+    }
+}
+
+/// The only event of type `T` the transaction emitted.
+pub fn event<T: Discriminator + AnchorDeserialize>(meta: &TransactionMetadata) -> T {
+    let mut events = meta
+        .events
+        .iter()
+        .filter_map(|fields| fields.first()?.strip_prefix(T::DISCRIMINATOR));
+    let data = events.next().expect("event emitted");
+    assert!(events.next().is_none(), "event emitted once");
+    T::try_from_slice(data).unwrap()
+}
+
+/// The error of the first failing instruction.
+pub fn instruction_error(
+    result: Result<TransactionMetadata, FailedTransaction>,
+) -> InstructionError {
+    match result {
+        Ok(_) => panic!("transaction succeeded"),
+        Err(FailedTransaction {
+            error: TransactionError::InstructionError(_, error),
+            ..
+        }) => error,
+        Err(failure) => panic!("{failure}"),
+    }
+}
+
+/// Anchor and journal errors surface as custom program errors.
+pub fn custom(code: impl Into<u32>) -> InstructionError {
+    InstructionError::Custom(code.into())
+}
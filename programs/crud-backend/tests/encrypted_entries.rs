@@ -0,0 +1,165 @@
+//! Entries encrypted client-side with `journal-crypto`: the program stores
+//! the scheme, nonce and ciphertext and never sees the content.
+
+mod common;
+
+use common::{custom, instruction_error, Journal};
+use crud_backend::{
+    EncryptedContent, JournalEntryState, JournalError, ENCRYPTION_SCHEME_XCHACHA20_POLY1305,
+    ENCRYPTION_TAG_LEN, MAX_CIPHERTEXT_LEN, MAX_CONTENT_LEN,
+};
+use journal_crypto::{EntryKey, Sealed};
+
+fn on_chain(sealed: Sealed) -> EncryptedContent {
+    EncryptedContent {
+        scheme: sealed.scheme,
+        nonce: sealed.nonce,
+        ciphertext: sealed.ciphertext,
+    }
+}
+
+fn sealed(entry: &JournalEntryState) -> Sealed {
+    let encrypted = entry.encrypted.clone().expect("entry is encrypted");
+    Sealed {
+        scheme: encrypted.scheme,
+        nonce: encrypted.nonce,
+        ciphertext: encrypted.ciphertext,
+    }
+}
+
+#[test]
+fn stores_only_ciphertext_and_decrypts_locally() {
+    let mut journal = Journal::new();
+    let key = EntryKey::from_keypair(&journal.owner);
+
+    let encrypted = on_chain(key.encrypt(b"I think the cat knows my PIN"));
+    journal
+        .create_encrypted("Secrets", "private", encrypted.clone())
+        .unwrap();
+
+    let entry = journal.entry("Secrets").unwrap();
+    assert!(entry.content.is_empty());
+    assert_eq!(entry.category, "private");
+    assert_eq!(
+        key.decrypt(&sealed(&entry)).unwrap(),
+        b"I think the cat knows my PIN"
+    );
+
+    // The plaintext is nowhere in the account
+    let account = journal
+        .svm
+        .get_account(&journal.entry_address("Secrets"))
+        .unwrap();
+    assert!(!account.data.windows(7).any(|bytes| bytes == b"cat kno"));
+    assert_eq!(
+        account.data.len(),
+        JournalEntryState::encrypted_space_for(7, encrypted.ciphertext.len(), 7)
+    );
+
+    // Listed like any other entry
+    assert_eq!(journal.profile().total_entries, 1);
+    let page = journal.entry_index_page(0).unwrap();
+    assert_eq!(page.entries[0].entry, journal.entry_address("Secrets"));
+}
+
+#[test]
+fn accepts_ciphertext_of_the_allowed_sizes() {
+    let mut journal = Journal::new();
+    let key = EntryKey::from_keypair(&journal.owner);
+
+    // From empty content, which is just the tag, to the plaintext limit
+    for (title, len) in [("Empty", 0), ("One", 1), ("Full", MAX_CONTENT_LEN)] {
+        let sealed = key.encrypt(&vec![b'x'; len]);
+        assert_eq!(sealed.ciphertext.len(), len + ENCRYPTION_TAG_LEN);
+        journal
+            .create_encrypted(title, "", on_chain(sealed))
+            .unwrap();
+        let entry = journal.entry(title).unwrap();
+        assert_eq!(key.decrypt(&self::sealed(&entry)).unwrap(), vec![b'x'; len]);
+    }
+
+    let raw = |scheme, len| EncryptedContent {
+        scheme,
+        nonce: [7; 24],
+        ciphertext: vec![0; len],
+    };
+    for len in [0, ENCRYPTION_TAG_LEN - 1, MAX_CIPHERTEXT_LEN + 1] {
+        let error = instruction_error(journal.create_encrypted(
+            "Bad size",
+            "",
+            raw(ENCRYPTION_SCHEME_XCHACHA20_POLY1305, len),
+        ));
+        assert_eq!(error, custom(JournalError::CiphertextSize));
+    }
+    let error =
+        instruction_error(journal.create_encrypted("Bad scheme", "", raw(2, ENCRYPTION_TAG_LEN)));
+    assert_eq!(error, custom(JournalError::UnknownEncryptionScheme));
+    assert!(journal.entry("Bad size").is_none());
+}
+
+#[test]
+fn encrypted_entries_only_take_ciphertext() {
+    let mut journal = Journal::new();
+    let key = EntryKey::from_keypair(&journal.owner);
+    let entry = journal.entry_address("Diary");
+    journal
+        .create_encrypted("Diary", "", on_chain(key.encrypt(b"day one")))
+        .unwrap();
+
+    // Plaintext writes would leak next to, or over, the ciphertext
+    let error = instruction_error(journal.update("Diary", "day two", ""));
+    assert_eq!(error, custom(JournalError::EntryIsEncrypted));
+    let error = instruction_error(journal.append_content(&entry, " and more"));
+    assert_eq!(error, custom(JournalError::EntryIsEncrypted));
+    let error = instruction_error(journal.create_entry_page(&entry, 0, "overflow"));
+    assert_eq!(error, custom(JournalError::EntryIsEncrypted));
+
+    // New ciphertext resizes the account
+    let longer = on_chain(key.encrypt(&[b'y'; 500]));
+    journal
+        .update_encrypted_at(&entry, "Diary", "days", longer.clone())
+        .unwrap();
+    let state = journal.entry("Diary").unwrap();
+    assert_eq!(key.decrypt(&sealed(&state)).unwrap(), vec![b'y'; 500]);
+    assert_eq!(state.category, "days");
+    assert_eq!(
+        journal.svm.get_account(&entry).unwrap().data.len(),
+        JournalEntryState::encrypted_space_for(5, longer.ciphertext.len(), 4)
+    );
+
+    // Renaming keeps the ciphertext; the key is the owner's, not the entry's
+    journal.rename("Diary", "Journal").unwrap();
+    let state = journal.entry("Journal").unwrap();
+    assert_eq!(key.decrypt(&sealed(&state)).unwrap(), vec![b'y'; 500]);
+
+    journal.delete("Journal").unwrap();
+    assert!(journal.entry("Journal").is_none());
+}
+
+#[test]
+fn plaintext_entries_can_be_encrypted() {
+    let mut journal = Journal::new();
+    let key = EntryKey::from_keypair(&journal.owner);
+    journal.create("Notes", "meant to be private", "").unwrap();
+    let entry = journal.entry_address("Notes");
+
+    journal
+        .update_encrypted_at(&entry, "Notes", "", on_chain(key.encrypt(b"private now")))
+        .unwrap();
+
+    let state = journal.entry("Notes").unwrap();
+    assert!(state.content.is_empty());
+    assert_eq!(key.decrypt(&sealed(&state)).unwrap(), b"private now");
+
+    // Entries with pages can't be: the pages would stay in plaintext
+    journal.create("Long", "", "").unwrap();
+    let long = journal.entry_address("Long");
+    journal.create_entry_page(&long, 0, "page zero").unwrap();
+    let error = instruction_error(journal.update_encrypted_at(
+        &long,
+        "Long",
+        "",
+        on_chain(key.encrypt(b"too late")),
+    ));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+}
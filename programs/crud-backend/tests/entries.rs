@@ -0,0 +1,221 @@
+//! Journal entry lifecycle against the program running in an in-process
+//! SVM.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::{AnchorSerialize, Discriminator};
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{JournalEntryRenamed, JournalEntryState, JournalError};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+/// `SystemError::AccountAlreadyInUse`, from creating an entry that exists.
+const ACCOUNT_ALREADY_IN_USE: InstructionError = InstructionError::Custom(0);
+
+#[test]
+fn entry_lifecycle() {
+    let mut journal = Journal::new();
+
+    journal.create("Groceries", "eggs, milk", "home").unwrap();
+    let entry = journal.entry("Groceries").unwrap();
+    assert_eq!(entry.owner, journal.owner.pubkey());
+    assert_eq!(entry.content, "eggs, milk");
+    assert_eq!(journal.profile().total_entries, 1);
+
+    journal
+        .update("Groceries", "eggs, milk, bread", "errands")
+        .unwrap();
+    let entry = journal.entry("Groceries").unwrap();
+    assert_eq!(entry.content, "eggs, milk, bread");
+    assert_eq!(entry.category, "errands");
+
+    journal.toggle_archive("Groceries").unwrap();
+    assert!(journal.entry("Groceries").unwrap().is_archived);
+
+    journal.delete("Groceries").unwrap();
+    assert!(journal.entry("Groceries").is_none());
+    assert_eq!(journal.profile().total_entries, 0);
+}
+
+#[test]
+fn titles_longer_than_a_seed_work() {
+    let mut journal = Journal::new();
+    let title = "A title well past the thirty-two bytes a single PDA seed can hold, \
+                 up to the limit";
+    assert!(title.len() > 32 && title.len() <= 100);
+
+    journal.create(title, "content", "long").unwrap();
+    journal.update(title, "edited", "long").unwrap();
+    journal.toggle_archive(title).unwrap();
+    let entry = journal.entry(title).unwrap();
+    assert_eq!(entry.title, title);
+    assert_eq!(entry.content, "edited");
+    assert!(entry.is_archived);
+
+    journal.delete(title).unwrap();
+    assert!(journal.entry(title).is_none());
+}
+
+#[test]
+fn validation_still_caps_titles() {
+    let mut journal = Journal::new();
+    let title = "x".repeat(101);
+
+    let error = instruction_error(journal.create(&title, "content", "long"));
+
+    assert_eq!(error, custom(JournalError::TitleTooLong));
+}
+
+#[test]
+fn rename_moves_the_entry() {
+    let mut journal = Journal::new();
+    journal.create("Draft", "content", "notes").unwrap();
+    journal.toggle_archive("Draft").unwrap();
+    let before = journal.entry("Draft").unwrap();
+    let old_address = journal.entry_address("Draft");
+    let owner_lamports = journal.svm.lamports(&journal.owner.pubkey());
+    journal.svm.set_unix_timestamp(before.updated_at + 60);
+
+    let meta = journal.rename("Draft", "Final").unwrap();
+
+    assert!(journal.svm.get_account(&old_address).is_none());
+    let entry = journal.entry("Final").unwrap();
+    assert_eq!(entry.owner, before.owner);
+    assert_eq!(entry.title, "Final");
+    assert_eq!(entry.content, before.content);
+    assert_eq!(entry.category, before.category);
+    assert!(entry.is_archived);
+    assert_eq!(entry.created_at, before.created_at);
+    assert_eq!(entry.updated_at, before.updated_at + 60);
+    assert_eq!(journal.profile().total_entries, 1);
+    // Same size account, so the old rent pays for the new one.
+    assert_eq!(
+        journal.svm.lamports(&journal.owner.pubkey()),
+        owner_lamports - meta.fee
+    );
+
+    let renamed: JournalEntryRenamed = event(&meta);
+    assert_eq!(renamed.owner, journal.owner.pubkey());
+    assert_eq!(renamed.old_title, "Draft");
+    assert_eq!(renamed.new_title, "Final");
+    assert_eq!(renamed.updated_at, entry.updated_at);
+
+    // The renamed entry is reachable by its new title only.
+    journal.update("Final", "edited", "notes").unwrap();
+    assert!(journal.update("Draft", "edited", "notes").is_err());
+}
+
+#[test]
+fn rename_rejects_taken_and_invalid_titles() {
+    let mut journal = Journal::new();
+    journal.create("Draft", "content", "notes").unwrap();
+    journal.create("Final", "other", "notes").unwrap();
+
+    let error = instruction_error(journal.rename("Draft", &"x".repeat(101)));
+    assert_eq!(error, custom(JournalError::TitleTooLong));
+
+    // The system program refuses to create an account that already exists.
+    assert_eq!(
+        instruction_error(journal.rename("Draft", "Final")),
+        ACCOUNT_ALREADY_IN_USE
+    );
+    assert_eq!(
+        instruction_error(journal.rename("Draft", "Draft")),
+        ACCOUNT_ALREADY_IN_USE
+    );
+    assert_eq!(journal.entry("Final").unwrap().content, "other");
+
+    let error = instruction_error(journal.rename("Missing", "Other"));
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotInitialized));
+
+    assert_eq!(journal.entry("Draft").unwrap().content, "content");
+}
+
+/// Writes an entry in an older layout: the original fields, then
+/// `appended` - whichever later fields the layout had - and zeros up to
+/// `len`.
+fn write_legacy_entry(journal: &mut Journal, title: &str, appended: &[u8], len: usize) -> Pubkey {
+    let address = journal.entry_address(title);
+    let mut data = JournalEntryState::DISCRIMINATOR.to_vec();
+    let owner = journal.owner.pubkey();
+    (
+        owner,
+        title.to_string(),
+        "old notes".to_string(),
+        "misc".to_string(),
+    )
+        .serialize(&mut data)
+        .unwrap();
+    (false, 1_700_000_000i64, 1_700_000_100i64)
+        .serialize(&mut data)
+        .unwrap();
+    data.extend_from_slice(appended);
+    data.resize(len.max(data.len()), 0);
+    let lamports = journal.svm.minimum_balance(data.len());
+    journal.svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: crud_backend::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    address
+}
+
+#[test]
+fn upgrades_legacy_entries() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+
+    // The first layout, allocated at its maximum of 1219 bytes, and a later
+    // one sized to its content, ending after `entry_id` and `index_page`
+    let original = write_legacy_entry(&mut journal, "Original", &[], 1219);
+    let mut appended = vec![];
+    (None::<u64>, Some(2u32)).serialize(&mut appended).unwrap();
+    let later = write_legacy_entry(&mut journal, "Later", &appended, 0);
+    let error = instruction_error(journal.toggle_archive("Later"));
+    assert_eq!(error, custom(AnchorErrorCode::AccountDidNotDeserialize));
+
+    for (address, title) in [(original, "Original"), (later, "Later")] {
+        let rent_before = journal.svm.lamports(&address);
+        let owner_before = journal.svm.lamports(&owner);
+        let meta = journal.upgrade_entry(&address).unwrap();
+
+        let entry = journal.entry(title).unwrap();
+        assert_eq!(entry.owner, owner);
+        assert_eq!(entry.content, "old notes");
+        assert_eq!(entry.category, "misc");
+        assert_eq!(entry.updated_at, 1_700_000_100);
+        assert!(entry.tags.is_empty() && entry.rent_payer.is_none());
+        // Sized to its content, with the owner settling the difference
+        let account = journal.svm.get_account(&address).unwrap();
+        assert_eq!(account.data.len(), entry.space_with_title(title.len()));
+        assert_eq!(
+            account.lamports,
+            journal.svm.minimum_balance(account.data.len())
+        );
+        assert_eq!(
+            journal.svm.lamports(&owner) + account.lamports,
+            owner_before + rent_before - meta.fee
+        );
+    }
+    assert_eq!(journal.entry("Later").unwrap().index_page, Some(2));
+
+    journal.toggle_archive("Later").unwrap();
+    journal.update("Original", "new notes", "misc").unwrap();
+    assert_eq!(journal.entry("Original").unwrap().content, "new notes");
+
+    // Upgrading twice, or someone else's entry, is refused
+    let error = instruction_error(journal.upgrade_entry(&later));
+    assert_eq!(error, custom(JournalError::EntryNotUpgradable));
+    let stale = write_legacy_entry(&mut journal, "Stale", &[], 1219);
+    journal.owner = journal.funded_keypair();
+    let error = instruction_error(journal.upgrade_entry(&stale));
+    assert_eq!(error, custom(JournalError::EntryNotUpgradable));
+}
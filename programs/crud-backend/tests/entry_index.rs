@@ -0,0 +1,175 @@
+//! The per-user `EntryIndex` pages kept by create, delete and rename, and
+//! the view that lists them.
+
+mod common;
+
+use anchor_lang::AccountSerialize;
+use common::{custom, instruction_error, Journal};
+use crud_backend::{EntryIndex, JournalEntryState, JournalError, ENTRY_INDEX_PAGE_SIZE};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn pages_fill_in_creation_order() {
+    let mut journal = Journal::new();
+    let mut created = Vec::new();
+    for n in 0..ENTRY_INDEX_PAGE_SIZE + 2 {
+        if n % 2 == 0 {
+            let title = format!("Entry {n}");
+            journal.create(&title, "content", "misc").unwrap();
+            created.push(journal.entry_address(&title));
+        } else {
+            let id = journal.profile().next_entry_id;
+            journal
+                .create_numbered("Numbered", "content", "misc")
+                .unwrap();
+            created.push(journal.numbered_entry_address(id));
+        }
+    }
+
+    assert_eq!(
+        journal.profile().indexed_entries,
+        created.len() as u64,
+        "every create takes a slot"
+    );
+    let first = journal.entry_index_page(0).unwrap();
+    assert_eq!(first.page, 0);
+    assert_eq!(first.next_page, Some(1));
+    let second = journal.entry_index_page(1).unwrap();
+    assert_eq!(second.page, 1);
+    assert_eq!(second.next_page, None);
+
+    let listed: Vec<Pubkey> = first
+        .entries
+        .iter()
+        .chain(&second.entries)
+        .map(|entry| entry.entry)
+        .collect();
+    assert_eq!(listed, created);
+    assert_eq!(second.entries.len(), 2);
+
+    let last: JournalEntryState = journal.decode(created.last().unwrap()).unwrap();
+    assert_eq!(last.index_page, Some(1));
+}
+
+#[test]
+fn deletes_leave_gaps_and_renames_keep_their_place() {
+    let mut journal = Journal::new();
+    for title in ["Alpha", "Beta", "Gamma"] {
+        journal.create(title, "content", "misc").unwrap();
+    }
+    let alpha = journal.entry_address("Alpha");
+    let beta = journal.entry_address("Beta");
+
+    journal.toggle_archive("Beta").unwrap();
+    journal.delete("Alpha").unwrap();
+    journal.rename("Gamma", "Delta").unwrap();
+    let delta = journal.entry_address("Delta");
+
+    let index: EntryIndex = journal.decode(&journal.index_address(0)).unwrap();
+    assert_eq!(index.entries, vec![Pubkey::default(), beta, delta]);
+    assert!(!index.entries.contains(&alpha));
+
+    let page = journal.entry_index_page(0).unwrap();
+    let listed: Vec<(Pubkey, bool)> = page
+        .entries
+        .iter()
+        .map(|entry| (entry.entry, entry.is_archived))
+        .collect();
+    assert_eq!(listed, vec![(beta, true), (delta, false)]);
+    assert_eq!(page.next_page, None);
+
+    // Gaps aren't reused.
+    journal.create("Epsilon", "content", "misc").unwrap();
+    let index: EntryIndex = journal.decode(&journal.index_address(0)).unwrap();
+    assert_eq!(index.entries.len(), 4);
+}
+
+#[test]
+fn delete_needs_the_page_listing_the_entry() {
+    let mut journal = Journal::new();
+    for n in 0..=ENTRY_INDEX_PAGE_SIZE {
+        journal
+            .create(&format!("Entry {n}"), "content", "misc")
+            .unwrap();
+    }
+    let last = journal.entry_address(&format!("Entry {ENTRY_INDEX_PAGE_SIZE}"));
+
+    // Page 0 is a real index page, but not the one listing this entry.
+    let mut instruction = journal.instruction(
+        crud_backend::accounts::DeleteJournalEntry {
+            owner: journal.owner.pubkey(),
+            journal_entry: last,
+            user_profile: journal.profile_address(),
+            system_program: anchor_lang::system_program::ID,
+            entry_index: Some(journal.index_address(0)),
+            category_registry: None,
+        },
+        crud_backend::instruction::DeleteJournalEntry {
+            title: format!("Entry {ENTRY_INDEX_PAGE_SIZE}"),
+        },
+    );
+    let error = instruction_error(journal.send(instruction.clone()));
+    assert_eq!(error, custom(JournalError::WrongEntryIndexPage));
+
+    // Leaving the index out doesn't skip the bookkeeping either.
+    let index_meta = instruction.accounts.last_mut().unwrap();
+    *index_meta = AccountMeta::new_readonly(crud_backend::ID, false);
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::WrongEntryIndexPage));
+
+    assert!(journal.svm.get_account(&last).is_some());
+}
+
+#[test]
+fn entries_from_before_the_index_can_still_be_deleted() {
+    let mut journal = Journal::new();
+    journal.create("Old", "content", "misc").unwrap();
+    let address = journal.entry_address("Old");
+
+    // Rewrite the entry as it was before the index existed.
+    let mut account = journal.svm.get_account(&address).unwrap();
+    let mut entry: JournalEntryState = journal.decode(&address).unwrap();
+    entry.index_page = None;
+    let mut data = Vec::new();
+    entry.try_serialize(&mut data).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    journal.svm.set_account(address, account);
+
+    journal.delete("Old").unwrap();
+
+    assert!(journal.svm.get_account(&address).is_none());
+    assert_eq!(journal.profile().total_entries, 0);
+}
+
+#[test]
+fn view_checks_the_entries_passed() {
+    let mut journal = Journal::new();
+    journal.create("Alpha", "content", "misc").unwrap();
+    journal.create("Beta", "content", "misc").unwrap();
+
+    let mut instruction = journal.instruction(
+        crud_backend::accounts::GetEntryIndexPage {
+            owner: journal.owner.pubkey(),
+            user_profile: journal.profile_address(),
+            entry_index: journal.index_address(0),
+        },
+        crud_backend::instruction::GetEntryIndexPage {},
+    );
+    let swapped = [
+        journal.entry_address("Beta"),
+        journal.entry_address("Alpha"),
+    ];
+    instruction.accounts.extend(
+        swapped
+            .iter()
+            .map(|entry| AccountMeta::new_readonly(*entry, false)),
+    );
+    let error = instruction_error(journal.send(instruction.clone()));
+    assert_eq!(error, custom(JournalError::EntryIndexAccountsMismatch));
+
+    instruction.accounts.pop();
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::EntryIndexAccountsMismatch));
+}
@@ -0,0 +1,277 @@
+//! Sharing an entry: `EntryGrant` accounts let a grantee comment on, edit or
+//! archive one of the owner's entries.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::system_program;
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    EntryAccessGranted, EntryAccessRevoked, EntryGrant, GrantPermission, JournalEntryCommented,
+    JournalError, Revision, MAX_ENTRY_GRANTS,
+};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn each_level_includes_the_ones_before_it() {
+    let mut journal = Journal::new();
+    journal.create("Runbook", "restart the db", "ops").unwrap();
+    let entry = journal.entry_address("Runbook");
+    let colleague = journal.funded_keypair();
+
+    // Nothing without a grant
+    let error = instruction_error(journal.comment_as(&colleague, &entry, "+1"));
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotInitialized));
+
+    let meta = journal
+        .grant_access(&entry, &colleague.pubkey(), GrantPermission::Comment)
+        .unwrap();
+    let granted: EntryAccessGranted = event(&meta);
+    assert_eq!(granted.grantee, colleague.pubkey());
+    assert_eq!(granted.permission, GrantPermission::Comment);
+
+    let meta = journal.comment_as(&colleague, &entry, "which db?").unwrap();
+    let commented: JournalEntryCommented = event(&meta);
+    assert_eq!(commented.entry, entry);
+    assert_eq!(commented.author, colleague.pubkey());
+    assert_eq!(commented.comment, "which db?");
+    let error = instruction_error(journal.update_as(&colleague, &entry, "Runbook", "x", "ops"));
+    assert_eq!(error, custom(JournalError::PermissionDenied));
+    let error = instruction_error(journal.toggle_archive_as(&colleague, &entry));
+    assert_eq!(error, custom(JournalError::PermissionDenied));
+
+    // Edit: the grantee pays for the revision, which is still the owner's
+    journal
+        .grant_access(&entry, &colleague.pubkey(), GrantPermission::Edit)
+        .unwrap();
+    journal
+        .update_as(&colleague, &entry, "Runbook", "restart postgres", "ops")
+        .unwrap();
+    assert_eq!(
+        journal.entry("Runbook").unwrap().content,
+        "restart postgres"
+    );
+    let revision: Revision = journal.decode(&journal.revision_address(0)).unwrap();
+    assert_eq!(revision.owner, journal.owner.pubkey());
+    assert_eq!(revision.content, "restart the db");
+    journal.comment_as(&colleague, &entry, "fixed").unwrap();
+    let error = instruction_error(journal.toggle_archive_as(&colleague, &entry));
+    assert_eq!(error, custom(JournalError::PermissionDenied));
+
+    journal
+        .grant_access(&entry, &colleague.pubkey(), GrantPermission::Archive)
+        .unwrap();
+    journal.toggle_archive_as(&colleague, &entry).unwrap();
+    assert!(journal.entry("Runbook").unwrap().is_archived);
+
+    // The owner needs no grant
+    journal
+        .comment_as(&journal.owner.insecure_clone(), &entry, "thanks")
+        .unwrap();
+}
+
+#[test]
+fn grantees_pay_for_growth_but_shrinking_refunds_the_owner() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+    journal.create("Runbook", "restart the db", "ops").unwrap();
+    let entry = journal.entry_address("Runbook");
+    let colleague = journal.funded_keypair();
+    journal
+        .grant_access(&entry, &colleague.pubkey(), GrantPermission::Edit)
+        .unwrap();
+
+    // Growing: the grantee covers the extra rent and the revision
+    let (owner_before, colleague_before) = (
+        journal.svm.lamports(&owner),
+        journal.svm.lamports(&colleague.pubkey()),
+    );
+    let entry_before = journal.svm.lamports(&entry);
+    let long = "restart postgres, then check replication ".repeat(10);
+    let meta = journal
+        .update_as(&colleague, &entry, "Runbook", &long, "ops")
+        .unwrap();
+    let grown = journal.svm.get_account(&entry).unwrap();
+    assert_eq!(
+        grown.lamports,
+        journal.svm.minimum_balance(grown.data.len())
+    );
+    let revision = journal.revision_address(0);
+    assert_eq!(
+        journal.svm.lamports(&colleague.pubkey()),
+        colleague_before
+            - meta.fee
+            - (grown.lamports - entry_before)
+            - journal.svm.lamports(&revision)
+    );
+    assert_eq!(journal.svm.lamports(&owner), owner_before);
+    let recorded: Revision = journal.decode(&revision).unwrap();
+    assert_eq!(recorded.rent_payer, colleague.pubkey());
+
+    // Shrinking: the freed rent is the owner's, not the grantee's
+    let (owner_before, colleague_before) = (
+        journal.svm.lamports(&owner),
+        journal.svm.lamports(&colleague.pubkey()),
+    );
+    let meta = journal
+        .update_as(&colleague, &entry, "Runbook", "restart", "ops")
+        .unwrap();
+    let shrunk = journal.svm.get_account(&entry).unwrap();
+    assert_eq!(
+        shrunk.lamports,
+        journal.svm.minimum_balance(shrunk.data.len())
+    );
+    let second = journal.revision_address(1);
+    assert_eq!(
+        journal.svm.lamports(&owner),
+        owner_before + (grown.lamports - shrunk.lamports)
+    );
+    assert_eq!(
+        journal.svm.lamports(&colleague.pubkey()),
+        colleague_before - meta.fee - journal.svm.lamports(&second)
+    );
+
+    // Closing a revision refunds whoever paid for it
+    let rent = journal.svm.lamports(&revision);
+    let colleague_before = journal.svm.lamports(&colleague.pubkey());
+    journal.close_revision(0).unwrap();
+    assert!(journal.svm.get_account(&revision).is_none());
+    assert_eq!(
+        journal.svm.lamports(&colleague.pubkey()),
+        colleague_before + rent
+    );
+
+    // A stand-in can't collect it instead
+    let stranger = journal.funded_keypair();
+    let instruction = journal.instruction(
+        crud_backend::accounts::CloseRevision {
+            owner,
+            revision: second,
+            rent_payer: stranger.pubkey(),
+        },
+        crud_backend::instruction::CloseRevision { revision_id: 1 },
+    );
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+}
+
+#[test]
+fn grants_are_listed_until_revoked() {
+    let mut journal = Journal::new();
+    journal.create("Shared", "content", "").unwrap();
+    let entry = journal.entry_address("Shared");
+    let (first, second) = (journal.funded_keypair(), journal.funded_keypair());
+
+    journal
+        .grant_access(&entry, &first.pubkey(), GrantPermission::Comment)
+        .unwrap();
+    journal
+        .grant_access(&entry, &second.pubkey(), GrantPermission::Edit)
+        .unwrap();
+    // Changing a permission keeps the grantee's place
+    journal
+        .grant_access(&entry, &first.pubkey(), GrantPermission::Archive)
+        .unwrap();
+
+    let listed = journal.entry_grants(&entry).unwrap();
+    assert_eq!(listed.entry, entry);
+    let grants: Vec<_> = listed
+        .grants
+        .iter()
+        .map(|grant| (grant.grantee, grant.permission))
+        .collect();
+    assert_eq!(
+        grants,
+        [
+            (first.pubkey(), GrantPermission::Archive),
+            (second.pubkey(), GrantPermission::Edit)
+        ]
+    );
+    assert_eq!(journal.entry("Shared").unwrap().grants, 2);
+
+    let grant = journal.grant_address(&entry, &first.pubkey());
+    let stored: EntryGrant = journal.decode(&grant).unwrap();
+    assert_eq!(stored.owner, journal.owner.pubkey());
+    let rent = journal.svm.lamports(&grant);
+    let lamports_before = journal.svm.lamports(&journal.owner.pubkey());
+    let meta = journal.revoke_access(&entry, &first.pubkey()).unwrap();
+    let revoked: EntryAccessRevoked = event(&meta);
+    assert_eq!((revoked.entry, revoked.grantee), (entry, first.pubkey()));
+    assert!(journal.svm.get_account(&grant).is_none());
+    assert_eq!(
+        journal.svm.lamports(&journal.owner.pubkey()),
+        lamports_before - meta.fee + rent
+    );
+
+    let listed = journal.entry_grants(&entry).unwrap();
+    assert_eq!(listed.grants.len(), 1);
+    assert_eq!(listed.grants[0].grantee, second.pubkey());
+    assert_eq!(journal.entry("Shared").unwrap().grants, 1);
+    let error = instruction_error(journal.toggle_archive_as(&first, &entry));
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotInitialized));
+}
+
+#[test]
+fn only_the_owner_grants_and_only_so_many() {
+    let mut journal = Journal::new();
+    journal.create("Shared", "content", "").unwrap();
+    let entry = journal.entry_address("Shared");
+    let stranger = journal.funded_keypair();
+
+    let instruction = journal.instruction(
+        crud_backend::accounts::GrantEntryAccess {
+            owner: stranger.pubkey(),
+            journal_entry: entry,
+            entry_grants: journal.entry_grants_address(&entry),
+            grant: journal.grant_address(&entry, &stranger.pubkey()),
+            system_program: system_program::ID,
+        },
+        crud_backend::instruction::GrantEntryAccess {
+            grantee: stranger.pubkey(),
+            permission: GrantPermission::Archive,
+        },
+    );
+    let error = instruction_error(journal.svm.send_transaction(&[instruction], &[&stranger]));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+
+    for _ in 0..MAX_ENTRY_GRANTS {
+        let grantee = Keypair::new().pubkey();
+        journal
+            .grant_access(&entry, &grantee, GrantPermission::Comment)
+            .unwrap();
+    }
+    let error = instruction_error(journal.grant_access(
+        &entry,
+        &stranger.pubkey(),
+        GrantPermission::Comment,
+    ));
+    assert_eq!(error, custom(JournalError::TooManyGrants));
+    assert_eq!(
+        journal.entry_grants(&entry).unwrap().grants.len(),
+        MAX_ENTRY_GRANTS
+    );
+}
+
+#[test]
+fn shared_entries_keep_their_address_until_revoked() {
+    let mut journal = Journal::new();
+    journal.create("Shared", "content", "").unwrap();
+    let entry = journal.entry_address("Shared");
+    let colleague = journal.funded_keypair();
+    journal
+        .grant_access(&entry, &colleague.pubkey(), GrantPermission::Edit)
+        .unwrap();
+
+    // Otherwise the grant would carry over to whatever takes the address next
+    let error = instruction_error(journal.delete("Shared"));
+    assert_eq!(error, custom(JournalError::EntryHasGrants));
+    let error = instruction_error(journal.rename("Shared", "Moved"));
+    assert_eq!(error, custom(JournalError::EntryHasGrants));
+
+    journal.revoke_access(&entry, &colleague.pubkey()).unwrap();
+    journal.delete("Shared").unwrap();
+    journal.create("Shared", "someone else's now", "").unwrap();
+    let error = instruction_error(journal.update_as(&colleague, &entry, "Shared", "mine", ""));
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotInitialized));
+}
@@ -0,0 +1,237 @@
+//! Content past the 1000 bytes create takes: grown inline with
+//! `append_content`, then continued in `EntryPage` accounts.
+
+mod common;
+
+use anchor_lang::system_program;
+use anchor_lang::Space;
+use common::{custom, instruction_error, Journal};
+use crud_backend::{
+    EncryptedContent, EntryPage, GrantPermission, JournalEntryState, JournalError, OffChainContent,
+    MAX_CIPHERTEXT_LEN, MAX_CONTENT_LEN, MAX_CONTENT_URI_LEN, MAX_ENTRY_CONTENT_LEN,
+    MAX_ENTRY_PAGE_CONTENT_LEN, MAX_ENTRY_TAGS, MAX_TAG_LEN,
+};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+/// Fits in a transaction with room to spare.
+const CHUNK_LEN: usize = 900;
+
+fn chunk(n: usize) -> String {
+    char::from(b'a' + (n % 26) as u8)
+        .to_string()
+        .repeat(CHUNK_LEN)
+}
+
+/// An entry created with the most content create takes.
+fn full_entry(journal: &mut Journal) -> Pubkey {
+    let content = "x".repeat(MAX_CONTENT_LEN);
+    journal.create("Memoir", &content, "long").unwrap();
+    journal.entry_address("Memoir")
+}
+
+#[test]
+fn space_for_matches_the_declared_maximum() {
+    assert_eq!(
+        JournalEntryState::space_for(100, 1000, 50)
+            + EncryptedContent::space_for(MAX_CIPHERTEXT_LEN)
+            + OffChainContent::space_for(MAX_CONTENT_URI_LEN)
+            + MAX_ENTRY_TAGS * (4 + MAX_TAG_LEN)
+            + 32, // A rent payer
+        8 + JournalEntryState::INIT_SPACE
+    );
+}
+
+#[test]
+fn append_grows_the_entry_until_it_is_full() {
+    let mut journal = Journal::new();
+    let entry = full_entry(&mut journal);
+    let mut expected = "x".repeat(MAX_CONTENT_LEN);
+
+    let mut n = 0;
+    while expected.len() + CHUNK_LEN <= MAX_ENTRY_CONTENT_LEN {
+        let owner_before = journal.svm.lamports(&journal.owner.pubkey());
+        let entry_before = journal.svm.get_account(&entry).unwrap();
+
+        let meta = journal.append_content(&entry, &chunk(n)).unwrap();
+        expected.push_str(&chunk(n));
+        n += 1;
+
+        // The account grows by the chunk, and the owner pays exactly the
+        // extra rent.
+        let account = journal.svm.get_account(&entry).unwrap();
+        assert_eq!(account.data.len(), entry_before.data.len() + CHUNK_LEN);
+        assert_eq!(
+            account.lamports,
+            journal.svm.minimum_balance(account.data.len())
+        );
+        assert_eq!(
+            journal.svm.lamports(&journal.owner.pubkey()),
+            owner_before - meta.fee - (account.lamports - entry_before.lamports)
+        );
+    }
+    assert_eq!(journal.entry("Memoir").unwrap().content, expected);
+    assert_eq!(
+        journal.svm.get_account(&entry).unwrap().data.len(),
+        JournalEntryState::space_for("Memoir".len(), expected.len(), "long".len())
+    );
+
+    let error = instruction_error(journal.append_content(&entry, &chunk(n)));
+    assert_eq!(error, custom(JournalError::EntryContentFull));
+}
+
+#[test]
+fn pages_continue_the_content_in_order() {
+    let mut journal = Journal::new();
+    let entry = full_entry(&mut journal);
+    journal.append_content(&entry, &chunk(0)).unwrap();
+
+    journal.create_entry_page(&entry, 0, &chunk(1)).unwrap();
+    journal.append_entry_page(&entry, 0, &chunk(2)).unwrap();
+    journal.create_entry_page(&entry, 1, &chunk(3)).unwrap();
+
+    let page: EntryPage = journal
+        .decode(&journal.entry_page_address(&entry, 0))
+        .unwrap();
+    assert_eq!(page.entry, entry);
+    assert_eq!(page.page, 0);
+    assert_eq!(page.content, chunk(1) + &chunk(2));
+    let account = journal
+        .svm
+        .get_account(&journal.entry_page_address(&entry, 0))
+        .unwrap();
+    assert_eq!(account.data.len(), EntryPage::space_for(2 * CHUNK_LEN));
+    assert_eq!(journal.entry("Memoir").unwrap().content_pages, 2);
+
+    let expected = "x".repeat(MAX_CONTENT_LEN) + &chunk(0) + &chunk(1) + &chunk(2) + &chunk(3);
+    assert_eq!(journal.full_content(&entry), expected);
+
+    // Only the last page grows, and pages start in sequence.
+    let error = instruction_error(journal.append_entry_page(&entry, 0, &chunk(4)));
+    assert_eq!(error, custom(JournalError::EntryPageOutOfOrder));
+    let error = instruction_error(journal.create_entry_page(&entry, 3, &chunk(4)));
+    assert_eq!(error, custom(JournalError::EntryPageOutOfOrder));
+    // Inline content is fixed once pages follow it.
+    let error = instruction_error(journal.append_content(&entry, &chunk(4)));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+    assert_eq!(journal.full_content(&entry), expected);
+}
+
+#[test]
+fn updates_leave_paged_entries_alone() {
+    let mut journal = Journal::new();
+    let entry = full_entry(&mut journal);
+    journal.create_entry_page(&entry, 0, &chunk(0)).unwrap();
+    let expected = journal.full_content(&entry);
+
+    // Replacing the inline content would orphan the pages after it
+    let error = instruction_error(journal.update("Memoir", "short", "long"));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+    let editor = journal.funded_keypair();
+    journal
+        .grant_access(&entry, &editor.pubkey(), GrantPermission::Edit)
+        .unwrap();
+    let error = instruction_error(journal.update_as(&editor, &entry, "Memoir", "", "long"));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+    assert_eq!(journal.full_content(&entry), expected);
+    assert_eq!(journal.entry("Memoir").unwrap().content_pages, 1);
+}
+
+#[test]
+fn restores_leave_paged_entries_alone() {
+    let mut journal = Journal::new();
+    journal.create("Memoir", "draft", "long").unwrap();
+    let entry = journal.entry_address("Memoir");
+    journal
+        .update("Memoir", &"x".repeat(MAX_CONTENT_LEN), "long")
+        .unwrap();
+    journal.create_entry_page(&entry, 0, &chunk(0)).unwrap();
+    let expected = journal.full_content(&entry);
+
+    // Even plain inline content from before the pages would orphan them
+    let error = instruction_error(journal.restore_revision(&entry, 0));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+    assert_eq!(journal.full_content(&entry), expected);
+    assert_eq!(journal.entry("Memoir").unwrap().content_pages, 1);
+}
+
+#[test]
+fn pages_stop_at_their_limit() {
+    let mut journal = Journal::new();
+    let entry = full_entry(&mut journal);
+    journal.create_entry_page(&entry, 0, &chunk(0)).unwrap();
+
+    let mut len = CHUNK_LEN;
+    while len + CHUNK_LEN <= MAX_ENTRY_PAGE_CONTENT_LEN {
+        journal.append_entry_page(&entry, 0, &chunk(0)).unwrap();
+        len += CHUNK_LEN;
+    }
+
+    let error = instruction_error(journal.append_entry_page(&entry, 0, &chunk(0)));
+    assert_eq!(error, custom(JournalError::EntryPageFull));
+    journal.create_entry_page(&entry, 1, &chunk(0)).unwrap();
+}
+
+#[test]
+fn delete_closes_the_pages_too() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+    let entry = full_entry(&mut journal);
+    journal.append_content(&entry, &chunk(0)).unwrap();
+    journal.create_entry_page(&entry, 0, &chunk(1)).unwrap();
+    journal.create_entry_page(&entry, 1, &chunk(2)).unwrap();
+    let pages = [0, 1].map(|page| journal.entry_page_address(&entry, page));
+
+    // Every page has to come along.
+    let mut instruction = journal.instruction(
+        crud_backend::accounts::DeleteJournalEntry {
+            owner,
+            journal_entry: entry,
+            user_profile: journal.profile_address(),
+            system_program: system_program::ID,
+            entry_index: journal.index_of(&entry),
+            category_registry: None,
+        },
+        crud_backend::instruction::DeleteJournalEntry {
+            title: "Memoir".to_string(),
+        },
+    );
+    instruction.accounts.push(AccountMeta::new(pages[0], false));
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::EntryPageAccountsMismatch));
+
+    let rent: u64 = [entry, pages[0], pages[1]]
+        .iter()
+        .map(|address| journal.svm.lamports(address))
+        .sum();
+    let lamports_before = journal.svm.lamports(&owner);
+    let meta = journal.delete_at(&entry, "Memoir").unwrap();
+
+    for address in [entry, pages[0], pages[1]] {
+        assert!(journal.svm.get_account(&address).is_none());
+    }
+    assert_eq!(
+        journal.svm.lamports(&owner),
+        lamports_before - meta.fee + rent
+    );
+}
+
+#[test]
+fn rename_keeps_grown_content_but_not_pages() {
+    let mut journal = Journal::new();
+    let entry = full_entry(&mut journal);
+    journal.append_content(&entry, &chunk(0)).unwrap();
+
+    let long_title = "t".repeat(100);
+    journal.rename("Memoir", &long_title).unwrap();
+    let renamed = journal.entry(&long_title).unwrap();
+    assert_eq!(renamed.content.len(), MAX_CONTENT_LEN + CHUNK_LEN);
+
+    let renamed_address = journal.entry_address(&long_title);
+    journal
+        .create_entry_page(&renamed_address, 0, &chunk(1))
+        .unwrap();
+    let error = instruction_error(journal.rename(&long_title, "Memoir"));
+    assert_eq!(error, custom(JournalError::EntryHasPages));
+}
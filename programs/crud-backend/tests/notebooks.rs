@@ -0,0 +1,274 @@
+//! Team notebooks: entries owned by a `Notebook`, which members create,
+//! update and delete according to their role.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    JournalEntryCreated, JournalEntryState, JournalError, Notebook, NotebookCreated,
+    NotebookMemberRemoved, NotebookMemberSet, NotebookRole, MAX_NOTEBOOK_MEMBERS,
+};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn roles_decide_who_creates_updates_and_deletes() {
+    let mut journal = Journal::new();
+    let meta = journal.create_notebook("Runbooks").unwrap();
+    let notebook = journal.notebook_address("Runbooks");
+    let created: NotebookCreated = event(&meta);
+    assert_eq!(created.notebook, notebook);
+    assert_eq!(created.admin, journal.owner.pubkey());
+    assert_eq!(created.name, "Runbooks");
+
+    let admin = journal.owner.insecure_clone();
+    let (viewer, editor) = (journal.funded_keypair(), journal.funded_keypair());
+    journal
+        .set_notebook_member_as(&admin, &notebook, &viewer.pubkey(), NotebookRole::Viewer)
+        .unwrap();
+    let meta = journal
+        .set_notebook_member_as(&admin, &notebook, &editor.pubkey(), NotebookRole::Editor)
+        .unwrap();
+    let set: NotebookMemberSet = event(&meta);
+    assert_eq!(
+        (set.member, set.role),
+        (editor.pubkey(), NotebookRole::Editor)
+    );
+
+    // Viewers only read
+    let error = instruction_error(
+        journal.create_notebook_entry_as(&viewer, &notebook, "Deploy", "x", "ops"),
+    );
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+
+    let meta = journal
+        .create_notebook_entry_as(&editor, &notebook, "Deploy", "cargo build", "ops")
+        .unwrap();
+    let created: JournalEntryCreated = event(&meta);
+    assert_eq!(created.owner, notebook);
+    assert_eq!(created.entry_id, Some(0));
+    let entry = journal.notebook_entry_address(&notebook, 0);
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(state.owner, notebook);
+    assert_eq!(state.content, "cargo build");
+    assert_eq!(
+        journal.decode::<Notebook>(&notebook).unwrap().next_entry_id,
+        1
+    );
+
+    let error = instruction_error(
+        journal.update_notebook_entry_as(&viewer, &notebook, &entry, "Deploy", "rm -rf", "ops"),
+    );
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+    journal
+        .update_notebook_entry_as(&editor, &notebook, &entry, "Deploy", "anchor deploy", "ops")
+        .unwrap();
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(state.content, "anchor deploy");
+
+    // Only admins delete
+    let error = instruction_error(journal.delete_notebook_entry_as(&editor, &notebook, &entry));
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+    journal
+        .set_notebook_member_as(&admin, &notebook, &editor.pubkey(), NotebookRole::Admin)
+        .unwrap();
+    let rent = journal.svm.lamports(&entry);
+    let lamports_before = journal.svm.lamports(&editor.pubkey());
+    let meta = journal
+        .delete_notebook_entry_as(&editor, &notebook, &entry)
+        .unwrap();
+    assert!(journal.svm.get_account(&entry).is_none());
+    assert_eq!(
+        journal.svm.lamports(&editor.pubkey()),
+        lamports_before - meta.fee + rent
+    );
+}
+
+#[test]
+fn rent_goes_back_to_the_member_who_paid_it() {
+    let mut journal = Journal::new();
+    journal.create_notebook("Shared").unwrap();
+    let notebook = journal.notebook_address("Shared");
+    let admin = journal.owner.insecure_clone();
+    let (author, reviser) = (journal.funded_keypair(), journal.funded_keypair());
+    for editor in [&author, &reviser] {
+        journal
+            .set_notebook_member_as(&admin, &notebook, &editor.pubkey(), NotebookRole::Editor)
+            .unwrap();
+    }
+    let long = "page the db team, then the app team ".repeat(10);
+    journal
+        .create_notebook_entry_as(&author, &notebook, "Oncall", &long, "ops")
+        .unwrap();
+    let entry = journal.notebook_entry_address(&notebook, 0);
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(state.rent_payer, Some(author.pubkey()));
+
+    // Another editor's shrink frees the author's rent, so it goes to them
+    let (author_before, reviser_before) = (
+        journal.svm.lamports(&author.pubkey()),
+        journal.svm.lamports(&reviser.pubkey()),
+    );
+    let entry_before = journal.svm.lamports(&entry);
+    let meta = journal
+        .update_notebook_entry_as(&reviser, &notebook, &entry, "Oncall", "page db", "ops")
+        .unwrap();
+    let shrunk = journal.svm.get_account(&entry).unwrap();
+    assert_eq!(
+        shrunk.lamports,
+        journal.svm.minimum_balance(shrunk.data.len())
+    );
+    assert_eq!(
+        journal.svm.lamports(&author.pubkey()),
+        author_before + (entry_before - shrunk.lamports)
+    );
+    assert_eq!(
+        journal.svm.lamports(&reviser.pubkey()),
+        reviser_before - meta.fee
+    );
+
+    // Growth is on whoever grows it
+    let (author_before, reviser_before) = (
+        journal.svm.lamports(&author.pubkey()),
+        journal.svm.lamports(&reviser.pubkey()),
+    );
+    let meta = journal
+        .update_notebook_entry_as(&reviser, &notebook, &entry, "Oncall", &long, "ops")
+        .unwrap();
+    let grown = journal.svm.get_account(&entry).unwrap();
+    assert_eq!(
+        grown.lamports,
+        journal.svm.minimum_balance(grown.data.len())
+    );
+    assert_eq!(
+        journal.svm.lamports(&reviser.pubkey()),
+        reviser_before - meta.fee - (grown.lamports - shrunk.lamports)
+    );
+    assert_eq!(journal.svm.lamports(&author.pubkey()), author_before);
+
+    // The refund can't be redirected to the member asking for it
+    let instruction = journal.instruction(
+        crud_backend::accounts::DeleteNotebookEntry {
+            member: admin.pubkey(),
+            notebook,
+            journal_entry: entry,
+            rent_payer: admin.pubkey(),
+        },
+        crud_backend::instruction::DeleteNotebookEntry {},
+    );
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+
+    let rent = journal.svm.lamports(&entry);
+    let author_before = journal.svm.lamports(&author.pubkey());
+    journal
+        .delete_notebook_entry_as(&admin, &notebook, &entry)
+        .unwrap();
+    assert!(journal.svm.get_account(&entry).is_none());
+    assert_eq!(journal.svm.lamports(&author.pubkey()), author_before + rent);
+}
+
+#[test]
+fn admins_manage_members_and_members_may_leave() {
+    let mut journal = Journal::new();
+    journal.create_notebook("Team").unwrap();
+    let notebook = journal.notebook_address("Team");
+    let admin = journal.owner.insecure_clone();
+    let (editor, other) = (journal.funded_keypair(), journal.funded_keypair());
+    journal
+        .set_notebook_member_as(&admin, &notebook, &editor.pubkey(), NotebookRole::Editor)
+        .unwrap();
+
+    // Editors can't hand out roles, even to themselves
+    let error = instruction_error(journal.set_notebook_member_as(
+        &editor,
+        &notebook,
+        &editor.pubkey(),
+        NotebookRole::Admin,
+    ));
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+    let error = instruction_error(journal.set_notebook_member_as(
+        &admin,
+        &notebook,
+        &admin.pubkey(),
+        NotebookRole::Viewer,
+    ));
+    assert_eq!(error, custom(JournalError::NotebookAdminFixed));
+
+    journal
+        .set_notebook_member_as(&admin, &notebook, &other.pubkey(), NotebookRole::Viewer)
+        .unwrap();
+    let error =
+        instruction_error(journal.remove_notebook_member_as(&editor, &notebook, &other.pubkey()));
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+    let meta = journal
+        .remove_notebook_member_as(&editor, &notebook, &editor.pubkey())
+        .unwrap();
+    let removed: NotebookMemberRemoved = event(&meta);
+    assert_eq!(removed.member, editor.pubkey());
+    let error =
+        instruction_error(journal.create_notebook_entry_as(&editor, &notebook, "Left", "x", ""));
+    assert_eq!(error, custom(JournalError::NotebookRoleDenied));
+    let error =
+        instruction_error(journal.remove_notebook_member_as(&admin, &notebook, &editor.pubkey()));
+    assert_eq!(error, custom(JournalError::NotNotebookMember));
+
+    let members = journal.decode::<Notebook>(&notebook).unwrap().members;
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].member, other.pubkey());
+
+    for _ in 1..MAX_NOTEBOOK_MEMBERS {
+        journal
+            .set_notebook_member_as(
+                &admin,
+                &notebook,
+                &Keypair::new().pubkey(),
+                NotebookRole::Viewer,
+            )
+            .unwrap();
+    }
+    let error = instruction_error(journal.set_notebook_member_as(
+        &admin,
+        &notebook,
+        &editor.pubkey(),
+        NotebookRole::Viewer,
+    ));
+    assert_eq!(error, custom(JournalError::TooManyNotebookMembers));
+    // Changing a role needs no free slot
+    journal
+        .set_notebook_member_as(&admin, &notebook, &other.pubkey(), NotebookRole::Editor)
+        .unwrap();
+}
+
+#[test]
+fn entries_stay_with_their_notebook() {
+    let mut journal = Journal::new();
+    journal.create_notebook("Ours").unwrap();
+    journal.create_notebook("Theirs").unwrap();
+    let (ours, theirs) = (
+        journal.notebook_address("Ours"),
+        journal.notebook_address("Theirs"),
+    );
+    let admin = journal.owner.insecure_clone();
+    let editor = journal.funded_keypair();
+    journal
+        .set_notebook_member_as(&admin, &ours, &editor.pubkey(), NotebookRole::Editor)
+        .unwrap();
+    journal
+        .create_notebook_entry_as(&admin, &theirs, "Secret", "content", "")
+        .unwrap();
+    let entry = journal.notebook_entry_address(&theirs, 0);
+
+    // An editor of one notebook can't reach another's entries through it
+    let error = instruction_error(
+        journal.update_notebook_entry_as(&editor, &ours, &entry, "Secret", "mine", ""),
+    );
+    assert_eq!(error, custom(JournalError::EntryNotInNotebook));
+
+    // Nor can the notebook's admin treat them as personal entries
+    let error = instruction_error(journal.update_at(&entry, "Secret", "mine", ""));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(state.content, "content");
+}
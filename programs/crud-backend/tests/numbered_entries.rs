@@ -0,0 +1,220 @@
+//! Entries addressed by a per-user id from `UserProfile.next_entry_id`, and
+//! the profile upgrade that adds the counter.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::system_program;
+use anchor_lang::{AnchorSerialize, Discriminator};
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    JournalEntryCreated, JournalEntryState, JournalError, UserProfile, LEGACY_USER_PROFILE_SPACE,
+};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn ids_count_up_and_are_never_reused() {
+    let mut journal = Journal::new();
+
+    let meta = journal.create_numbered("Standup", "notes", "work").unwrap();
+    let created: JournalEntryCreated = event(&meta);
+    assert_eq!(created.entry_id, Some(0));
+    journal
+        .create_numbered("Standup", "more notes", "work")
+        .unwrap();
+    journal.create("Titled", "content", "misc").unwrap();
+
+    let first = journal.numbered_entry_address(0);
+    let second = journal.numbered_entry_address(1);
+    let entry: JournalEntryState = journal.decode(&second).unwrap();
+    assert_eq!(entry.entry_id, Some(1));
+    assert_eq!(entry.title, "Standup", "titles needn't be unique");
+    assert_eq!(journal.entry("Titled").unwrap().entry_id, None);
+    assert_eq!(journal.profile().next_entry_id, 2);
+    assert_eq!(journal.profile().total_entries, 3);
+
+    journal.delete_at(&second, "Standup").unwrap();
+    assert!(journal.svm.get_account(&second).is_none());
+    assert_eq!(journal.profile().total_entries, 2);
+
+    // Deleting doesn't free the id.
+    journal.create_numbered("Retro", "notes", "work").unwrap();
+    assert!(journal.svm.get_account(&second).is_none());
+    let third = journal.numbered_entry_address(2);
+    assert_eq!(
+        journal.decode::<JournalEntryState>(&third).unwrap().title,
+        "Retro"
+    );
+    assert_eq!(journal.profile().next_entry_id, 3);
+    assert!(journal.svm.get_account(&first).is_some());
+}
+
+#[test]
+fn numbered_entries_can_change_title() {
+    let mut journal = Journal::new();
+    journal
+        .create_numbered("Draft", "content", "notes")
+        .unwrap();
+    let address = journal.numbered_entry_address(0);
+
+    journal
+        .update_at(&address, "Final", "edited", "notes")
+        .unwrap();
+    journal.toggle_archive_at(&address, "Final").unwrap();
+
+    let entry: JournalEntryState = journal.decode(&address).unwrap();
+    assert_eq!(entry.title, "Final");
+    assert_eq!(entry.content, "edited");
+    assert!(entry.is_archived);
+}
+
+#[test]
+fn titled_entries_are_only_reached_by_their_title() {
+    let mut journal = Journal::new();
+    journal.create("Draft", "content", "notes").unwrap();
+    let address = journal.entry_address("Draft");
+
+    // The title has to derive the entry's address, so an update can't
+    // change it and the other instructions can't name it wrongly
+    let error = instruction_error(journal.update_at(&address, "Final", "edited", "notes"));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+    let error = instruction_error(journal.toggle_archive_at(&address, "Final"));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+    let error = instruction_error(journal.delete_at(&address, "Final"));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+
+    let entry = journal.entry("Draft").unwrap();
+    assert_eq!(entry.content, "content");
+    assert!(!entry.is_archived);
+    journal.delete_at(&address, "Draft").unwrap();
+}
+
+#[test]
+fn numbered_entries_are_checked_against_their_id() {
+    let mut journal = Journal::new();
+    journal.create_numbered("First", "content", "notes").unwrap();
+    journal.create_numbered("Second", "content", "notes").unwrap();
+    let first = journal.numbered_entry_address(0);
+
+    // An entry made at another address can't pass for it
+    let mut entry: JournalEntryState = journal.decode(&first).unwrap();
+    entry.entry_id = Some(1);
+    let mut data = JournalEntryState::DISCRIMINATOR.to_vec();
+    entry.serialize(&mut data).unwrap();
+    let mut account = journal.svm.get_account(&first).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    journal.svm.set_account(first, account);
+
+    let error = instruction_error(journal.toggle_archive_at(&first, "First"));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintAddress));
+}
+
+#[test]
+fn only_the_owner_can_touch_an_entry() {
+    let mut journal = Journal::new();
+    journal
+        .create_numbered("Private", "content", "notes")
+        .unwrap();
+    let stranger = journal.funded_keypair();
+
+    // With a profile of their own, so only the entry's owner check stands
+    // in the way
+    let stranger_profile =
+        Pubkey::find_program_address(&[b"profile", stranger.pubkey().as_ref()], &crud_backend::ID)
+            .0;
+    let instruction = journal.instruction(
+        crud_backend::accounts::InitializeUserProfile {
+            owner: stranger.pubkey(),
+            user_profile: stranger_profile,
+            system_program: system_program::ID,
+        },
+        crud_backend::instruction::InitializeUserProfile {},
+    );
+    journal
+        .svm
+        .send_transaction(&[instruction], &[&stranger])
+        .unwrap();
+
+    let instruction = journal.instruction(
+        crud_backend::accounts::UpdateJournalEntry {
+            owner: stranger.pubkey(),
+            journal_entry: journal.numbered_entry_address(0),
+            user_profile: stranger_profile,
+            revision: Pubkey::find_program_address(
+                &[b"revision", stranger.pubkey().as_ref(), &0u64.to_le_bytes()],
+                &crud_backend::ID,
+            )
+            .0,
+            system_program: system_program::ID,
+            category_registry: None,
+        },
+        crud_backend::instruction::UpdateJournalEntry {
+            title: "Mine".to_string(),
+            content: "now".to_string(),
+            category: "notes".to_string(),
+        },
+    );
+    let error = instruction_error(journal.svm.send_transaction(&[instruction], &[&stranger]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+}
+
+#[test]
+fn upgrades_a_legacy_profile() {
+    let mut journal = Journal::new();
+    let address = journal.profile_address();
+    let owner = journal.owner.pubkey();
+
+    // The layout before `next_entry_id`: owner, total_entries, created_at.
+    let mut data = UserProfile::DISCRIMINATOR.to_vec();
+    (owner, 7u32, 1_700_000_000i64)
+        .serialize(&mut data)
+        .unwrap();
+    assert_eq!(data.len(), LEGACY_USER_PROFILE_SPACE);
+    let legacy_rent = journal.svm.minimum_balance(data.len());
+    journal.svm.set_account(
+        address,
+        Account {
+            lamports: legacy_rent,
+            data,
+            owner: crud_backend::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let instruction = journal.create_numbered_ix(0, 0, "Before", "upgrade", "misc");
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(AnchorErrorCode::AccountDidNotDeserialize));
+
+    let instruction = journal.instruction(
+        crud_backend::accounts::UpgradeUserProfile {
+            owner,
+            user_profile: address,
+            system_program: system_program::ID,
+        },
+        crud_backend::instruction::UpgradeUserProfile {},
+    );
+    journal.send(instruction.clone()).unwrap();
+
+    let profile = journal.profile();
+    assert_eq!(profile.owner, owner);
+    assert_eq!(profile.total_entries, 7);
+    assert_eq!(profile.created_at, 1_700_000_000);
+    assert_eq!(profile.next_entry_id, 0);
+    let account = journal.svm.get_account(&address).unwrap();
+    assert_eq!(
+        account.lamports,
+        journal.svm.minimum_balance(account.data.len())
+    );
+
+    journal.create_numbered("After", "upgrade", "misc").unwrap();
+    assert_eq!(journal.profile().next_entry_id, 1);
+
+    // Upgrading twice is refused.
+    journal.svm.warp_to_slot(journal.svm.clock().slot + 1);
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::ProfileNotUpgradable));
+}
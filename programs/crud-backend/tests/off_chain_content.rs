@@ -0,0 +1,197 @@
+//! Entries whose content lives off-chain: the account holds a URI and the
+//! content's SHA-256, and `journal-storage` checks fetched copies against it.
+//! A local directory stands in for Arweave and IPFS.
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::{custom, instruction_error, Journal};
+use crud_backend::{JournalEntryState, JournalError, OffChainContent, MAX_CONTENT_URI_LEN};
+use journal_crypto::EntryKey;
+use journal_storage::{digest, verify, Error, LocalStore};
+
+/// A fresh local store, removed when dropped.
+struct Storage {
+    store: LocalStore,
+    root: PathBuf,
+}
+
+impl Storage {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "crud-backend-off-chain-{}-{name}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        Storage {
+            store: LocalStore::new(&root),
+            root,
+        }
+    }
+
+    /// "Uploads" `payload` to `uri` and returns what the entry stores.
+    fn upload(&self, uri: &str, payload: &[u8]) -> OffChainContent {
+        self.store.put(uri, payload).unwrap();
+        OffChainContent {
+            uri: uri.to_string(),
+            sha256: digest(payload),
+        }
+    }
+}
+
+impl Drop for Storage {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn content_of_any_size_verifies_against_the_entry() {
+    let storage = Storage::new("verify");
+    let mut journal = Journal::new();
+
+    // Far past what an entry account could hold
+    let payload = "Chapter one. ".repeat(100_000).into_bytes();
+    let off_chain = storage.upload("ar://memoir-draft-1", &payload);
+    journal
+        .create_off_chain("Memoir", "books", off_chain.clone())
+        .unwrap();
+
+    let entry = journal.entry("Memoir").unwrap();
+    assert!(entry.content.is_empty());
+    let stored = entry.off_chain.unwrap();
+    assert_eq!(stored.uri, "ar://memoir-draft-1");
+    assert_eq!(stored.sha256, digest(&payload));
+    assert_eq!(
+        journal
+            .svm
+            .get_account(&journal.entry_address("Memoir"))
+            .unwrap()
+            .data
+            .len(),
+        JournalEntryState::off_chain_space_for(6, off_chain.uri.len(), 5)
+    );
+
+    assert_eq!(
+        verify(&storage.store, &stored.uri, &stored.sha256).unwrap(),
+        payload
+    );
+
+    // A copy that changed after the entry was written is caught
+    storage
+        .store
+        .put(&stored.uri, b"rewritten history")
+        .unwrap();
+    assert!(matches!(
+        verify(&storage.store, &stored.uri, &stored.sha256),
+        Err(Error::DigestMismatch { .. })
+    ));
+}
+
+#[test]
+fn only_content_addressed_uris_are_accepted() {
+    let mut journal = Journal::new();
+    let off_chain = |uri: &str| OffChainContent {
+        uri: uri.to_string(),
+        sha256: digest(b"payload"),
+    };
+
+    journal
+        .create_off_chain("Ipfs", "", off_chain("ipfs://bafybeigdyrzt/entry.md"))
+        .unwrap();
+    let longest = format!("ar://{}", "a".repeat(MAX_CONTENT_URI_LEN - 5));
+    journal
+        .create_off_chain("Longest", "", off_chain(&longest))
+        .unwrap();
+
+    for uri in ["https://example.com/entry.md", "ar://", "ipfs://", ""] {
+        let error = instruction_error(journal.create_off_chain("Bad", "", off_chain(uri)));
+        assert_eq!(error, custom(JournalError::InvalidContentUri), "{uri}");
+    }
+    let error =
+        instruction_error(journal.create_off_chain("Bad", "", off_chain(&format!("{longest}a"))));
+    assert_eq!(error, custom(JournalError::ContentUriTooLong));
+    assert!(journal.entry("Bad").is_none());
+}
+
+#[test]
+fn entries_move_between_on_chain_and_off_chain_content() {
+    let storage = Storage::new("move");
+    let mut journal = Journal::new();
+    journal.create("Notes", "short for now", "").unwrap();
+    let entry = journal.entry_address("Notes");
+
+    // Off-chain once it outgrows the account; the account shrinks to the URI
+    let v1 = storage.upload("ipfs://bafy-notes/v1", &[b'n'; 50_000]);
+    journal
+        .update_off_chain_at(&entry, "Notes", "long", v1.clone())
+        .unwrap();
+    let state = journal.entry("Notes").unwrap();
+    assert!(state.content.is_empty());
+    assert_eq!(state.category, "long");
+    assert_eq!(state.off_chain.unwrap().uri, v1.uri);
+
+    // Inline writes would be shadowed by the URI
+    let error = instruction_error(journal.append_content(&entry, "more"));
+    assert_eq!(error, custom(JournalError::EntryIsOffChain));
+    let error = instruction_error(journal.create_entry_page(&entry, 0, "more"));
+    assert_eq!(error, custom(JournalError::EntryIsOffChain));
+
+    // Renaming keeps the pointer
+    journal.rename("Notes", "Book").unwrap();
+    let entry = journal.entry_address("Book");
+    let v2 = storage.upload("ipfs://bafy-notes/v2", b"edited");
+    journal
+        .update_off_chain_at(&entry, "Book", "long", v2.clone())
+        .unwrap();
+    let stored = journal.entry("Book").unwrap().off_chain.unwrap();
+    assert_eq!(
+        verify(&storage.store, &stored.uri, &stored.sha256).unwrap(),
+        b"edited"
+    );
+    assert_eq!(
+        journal.svm.get_account(&entry).unwrap().data.len(),
+        JournalEntryState::off_chain_space_for(4, v2.uri.len(), 4)
+    );
+
+    // A plain update brings the content back on-chain
+    journal.update("Book", "short again", "").unwrap();
+    let state = journal.entry("Book").unwrap();
+    assert_eq!(state.content, "short again");
+    assert!(state.off_chain.is_none());
+    assert_eq!(
+        journal.svm.get_account(&entry).unwrap().data.len(),
+        JournalEntryState::space_for(4, 11, 0)
+    );
+}
+
+#[test]
+fn encrypted_content_can_move_off_chain() {
+    let storage = Storage::new("encrypted");
+    let mut journal = Journal::new();
+    let key = EntryKey::from_keypair(&journal.owner);
+    let sealed = key.encrypt(b"secret");
+    journal
+        .create_encrypted(
+            "Secret",
+            "",
+            crud_backend::EncryptedContent {
+                scheme: sealed.scheme,
+                nonce: sealed.nonce,
+                ciphertext: sealed.ciphertext,
+            },
+        )
+        .unwrap();
+
+    // Whatever is uploaded is the client's business, ciphertext included
+    let off_chain = storage.upload("ar://sealed-secret", b"nonce and ciphertext");
+    let entry = journal.entry_address("Secret");
+    journal
+        .update_off_chain_at(&entry, "Secret", "", off_chain)
+        .unwrap();
+    let state = journal.entry("Secret").unwrap();
+    assert!(state.encrypted.is_none());
+    assert!(state.off_chain.is_some());
+}
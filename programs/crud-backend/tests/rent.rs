@@ -0,0 +1,131 @@
+//! Entry accounts are sized to their payload, and resized on update, so
+//! rent follows what an entry actually stores. Run with `--nocapture` to
+//! see the rent saved.
+
+mod common;
+
+use anchor_lang::AccountSerialize;
+use common::Journal;
+use crud_backend::{JournalEntryState, MAX_CONTENT_LEN};
+use solana_sdk::signer::Signer;
+
+/// Size every entry was allocated at before entries were sized to fit.
+fn full_size() -> usize {
+    JournalEntryState::space_for(100, MAX_CONTENT_LEN, 50)
+}
+
+/// Rent an entry paid before entries were sized to fit.
+fn full_size_rent(journal: &Journal) -> u64 {
+    journal.svm.minimum_balance(full_size())
+}
+
+#[test]
+fn entries_pay_rent_for_what_they_store() {
+    let mut journal = Journal::new();
+    let cases = [
+        ("Milk", "buy".to_string(), String::new()),
+        (
+            "Standup",
+            "Shipped the importer; next up is pagination.".to_string(),
+            "work".to_string(),
+        ),
+        ("Essay", "word ".repeat(200), "writing".to_string()),
+        ("Maximal", "x".repeat(1000), "c".repeat(50)),
+    ];
+
+    println!(
+        "{:<10} {:>6} {:>12} {:>12}",
+        "entry", "bytes", "rent", "saved"
+    );
+    for (title, content, category) in &cases {
+        journal.create(title, content, category).unwrap();
+        let account = journal
+            .svm
+            .get_account(&journal.entry_address(title))
+            .unwrap();
+
+        let space = JournalEntryState::space_for(title.len(), content.len(), category.len());
+        assert_eq!(account.data.len(), space);
+        assert_eq!(account.lamports, journal.svm.minimum_balance(space));
+        let saved = full_size_rent(&journal) - account.lamports;
+        println!(
+            "{:<10} {:>6} {:>12} {:>12}",
+            title, space, account.lamports, saved
+        );
+        // Even at the content limit the title is shorter than the maximum.
+        assert!(saved > 0);
+    }
+
+    // A one-word entry used to pay for ~1.2 KB; now it pays for ~150 bytes,
+    // plus the per-account overhead every account is charged for.
+    let milk = journal
+        .svm
+        .get_account(&journal.entry_address("Milk"))
+        .unwrap();
+    assert!(milk.data.len() < 160);
+    assert!(full_size_rent(&journal) > 5 * milk.lamports);
+}
+
+#[test]
+fn update_charges_or_refunds_the_difference() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+    let entry = journal.entry_address("Notes");
+    journal.create("Notes", "short", "misc").unwrap();
+
+    for content in ["x".repeat(800), "tiny".to_string(), String::new()] {
+        let entry_before = journal.svm.lamports(&entry);
+        let owner_before = journal.svm.lamports(&owner);
+        let revision = journal.next_revision_address();
+
+        let meta = journal.update("Notes", &content, "misc").unwrap();
+
+        let space = JournalEntryState::space_for("Notes".len(), content.len(), "misc".len());
+        let account = journal.svm.get_account(&entry).unwrap();
+        assert_eq!(account.data.len(), space);
+        assert_eq!(account.lamports, journal.svm.minimum_balance(space));
+        // What the entry gained, the owner paid, and the other way round,
+        // on top of the rent for the revision the update recorded.
+        assert_eq!(
+            i128::from(journal.svm.lamports(&owner)) - i128::from(owner_before),
+            i128::from(entry_before)
+                - i128::from(account.lamports)
+                - i128::from(journal.svm.lamports(&revision))
+                - i128::from(meta.fee)
+        );
+        assert_eq!(journal.entry("Notes").unwrap().content, content);
+    }
+}
+
+#[test]
+fn full_size_entries_shrink_on_their_next_update() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+    journal
+        .create("Old", "from before right-sizing", "misc")
+        .unwrap();
+    let address = journal.entry_address("Old");
+
+    // Reallocate it the way every entry used to be: at the maximum size.
+    let entry: JournalEntryState = journal.decode(&address).unwrap();
+    let mut account = journal.svm.get_account(&address).unwrap();
+    account.data = vec![0; full_size()];
+    entry
+        .try_serialize(&mut account.data.as_mut_slice())
+        .unwrap();
+    account.lamports = full_size_rent(&journal);
+    journal.svm.set_account(address, account);
+
+    let owner_before = journal.svm.lamports(&owner);
+    let revision = journal.next_revision_address();
+    let meta = journal.update("Old", "edited", "misc").unwrap();
+
+    let account = journal.svm.get_account(&address).unwrap();
+    let refund = full_size_rent(&journal) - account.lamports;
+    assert_eq!(account.data.len(), JournalEntryState::space_for(3, 6, 4));
+    assert_eq!(
+        journal.svm.lamports(&owner),
+        owner_before - meta.fee + refund - journal.svm.lamports(&revision)
+    );
+    println!("refunded {refund} lamports shrinking a full-size entry");
+}
@@ -0,0 +1,163 @@
+//! Revisions: every update snapshots the version it replaces into a
+//! `Revision` account, chained by hash, and `restore_revision` rolls back.
+
+mod common;
+
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    JournalEntryRestored, JournalEntryRevised, JournalEntryState, JournalError, Revision,
+};
+use solana_sdk::signer::Signer;
+
+fn revision(journal: &Journal, id: u64) -> Revision {
+    journal.decode(&journal.revision_address(id)).unwrap()
+}
+
+#[test]
+fn updates_record_a_hash_chain() {
+    let mut journal = Journal::new();
+    journal.create("Notes", "v1", "misc").unwrap();
+    let entry = journal.entry_address("Notes");
+    assert!(journal.entry("Notes").unwrap().last_revision.is_none());
+
+    let meta = journal.update("Notes", "v2", "work").unwrap();
+    journal.update("Notes", "v3", "work").unwrap();
+
+    let first = revision(&journal, 0);
+    assert_eq!(first.owner, journal.owner.pubkey());
+    assert_eq!(first.entry, entry);
+    assert_eq!((first.history, first.previous), (0, None));
+    assert_eq!(first.previous_hash, [0; 32]);
+    assert_eq!(
+        (first.content.as_str(), first.category.as_str()),
+        ("v1", "misc")
+    );
+    let revised: JournalEntryRevised = event(&meta);
+    assert_eq!(revised.revision, 0);
+    assert_eq!(revised.hash, first.hash());
+
+    let second = revision(&journal, 1);
+    assert_eq!((second.history, second.previous), (0, Some(0)));
+    assert_eq!(second.previous_hash, first.hash());
+    assert_eq!(second.content, "v2");
+    assert_eq!(
+        second.replaced_at,
+        journal.entry("Notes").unwrap().updated_at
+    );
+    assert_eq!(
+        journal
+            .svm
+            .get_account(&journal.revision_address(1))
+            .unwrap()
+            .data
+            .len(),
+        // The same lengths as "v3" in "work"
+        Revision::space_for(&journal.decode::<JournalEntryState>(&entry).unwrap())
+    );
+
+    let state = journal.entry("Notes").unwrap();
+    assert_eq!(state.history, Some(0));
+    assert_eq!(state.last_revision, Some(1));
+    assert_eq!(state.revision_hash, second.hash());
+    assert_eq!(journal.profile().next_revision_id, 2);
+}
+
+#[test]
+fn restore_rolls_back_and_can_itself_be_undone() {
+    let mut journal = Journal::new();
+    journal.create_numbered("Draft", "first", "misc").unwrap();
+    let entry = journal.numbered_entry_address(0);
+    journal
+        .update_at(&entry, "Final", "a much longer second version", "work")
+        .unwrap();
+
+    let meta = journal.restore_revision(&entry, 0).unwrap();
+    let restored: JournalEntryRestored = event(&meta);
+    assert_eq!(restored.revision, 0);
+
+    // Numbered entries get their old title back, and shrink to fit
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(
+        (
+            state.title.as_str(),
+            state.content.as_str(),
+            state.category.as_str()
+        ),
+        ("Draft", "first", "misc")
+    );
+    assert_eq!(
+        journal.svm.get_account(&entry).unwrap().data.len(),
+        JournalEntryState::space_for(5, 5, 4)
+    );
+
+    // The version the restore replaced is a revision like any other
+    let replaced = revision(&journal, 1);
+    assert_eq!(replaced.content, "a much longer second version");
+    assert_eq!(replaced.previous, Some(0));
+    assert_eq!(state.revision_hash, replaced.hash());
+    journal.restore_revision(&entry, 1).unwrap();
+    let state: JournalEntryState = journal.decode(&entry).unwrap();
+    assert_eq!(state.title, "Final");
+    assert_eq!(state.content, "a much longer second version");
+    assert_eq!(state.last_revision, Some(2));
+}
+
+#[test]
+fn only_an_entrys_own_revisions_restore_it() {
+    let mut journal = Journal::new();
+    journal.create("Mine", "one", "").unwrap();
+    journal.create("Other", "two", "").unwrap();
+    journal.create("Fresh", "three", "").unwrap();
+    journal.update("Mine", "one, edited", "").unwrap();
+    journal.update("Other", "two, edited", "").unwrap();
+
+    let mine = journal.entry_address("Mine");
+    let error = instruction_error(journal.restore_revision(&mine, 1));
+    assert_eq!(error, custom(JournalError::RevisionNotOfEntry));
+    // Nor does anything restore an entry without history
+    let fresh = journal.entry_address("Fresh");
+    let error = instruction_error(journal.restore_revision(&fresh, 0));
+    assert_eq!(error, custom(JournalError::RevisionNotOfEntry));
+
+    assert_eq!(journal.entry("Mine").unwrap().content, "one, edited");
+    assert_eq!(journal.profile().next_revision_id, 2);
+}
+
+#[test]
+fn history_follows_a_rename_and_outlives_the_entry() {
+    let mut journal = Journal::new();
+    let owner = journal.owner.pubkey();
+    journal.create("Notes", "v1", "").unwrap();
+    journal.update("Notes", "v2", "").unwrap();
+
+    journal.rename("Notes", "Journal").unwrap();
+    let entry = journal.entry_address("Journal");
+    journal.restore_revision(&entry, 0).unwrap();
+    let state = journal.entry("Journal").unwrap();
+    assert_eq!(state.content, "v1");
+    assert_eq!(state.history, Some(0));
+    assert_eq!(
+        revision(&journal, 1).previous_hash,
+        revision(&journal, 0).hash()
+    );
+
+    // Deleting the entry leaves the audit trail until it's pruned
+    journal.delete("Journal").unwrap();
+    assert_eq!(revision(&journal, 1).content, "v2");
+
+    let rent = journal.svm.lamports(&journal.revision_address(0));
+    let lamports_before = journal.svm.lamports(&owner);
+    let meta = journal.close_revision(0).unwrap();
+    assert!(journal
+        .svm
+        .get_account(&journal.revision_address(0))
+        .is_none());
+    assert_eq!(
+        journal.svm.lamports(&owner),
+        lamports_before - meta.fee + rent
+    );
+    assert!(journal
+        .svm
+        .get_account(&journal.revision_address(1))
+        .is_some());
+}
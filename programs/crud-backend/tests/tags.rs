@@ -0,0 +1,233 @@
+//! Entry tags and the per-tag `TagIndex` pages that `get_tag_entries`
+//! reads, kept current through updates, renames and deletes.
+
+mod common;
+
+use anchor_lang::system_program;
+use common::{custom, event, instruction_error, Journal};
+use crud_backend::{
+    EntryTagged, EntryUntagged, JournalEntryState, JournalError, TagIndex, MAX_ENTRY_TAGS,
+    MAX_TAG_LEN, TAG_INDEX_PAGE_SIZE,
+};
+use solana_sdk::signer::Signer;
+
+#[test]
+fn tags_list_their_entries() {
+    let mut journal = Journal::new();
+    journal.create("Deploy", "cargo build", "ops").unwrap();
+    journal.create_numbered("Backup", "pg_dump", "ops").unwrap();
+    let deploy = journal.entry_address("Deploy");
+    let backup = journal.numbered_entry_address(0);
+
+    let meta = journal.tag_entry(&deploy, "runbook").unwrap();
+    let tagged: EntryTagged = event(&meta);
+    assert_eq!((tagged.entry, tagged.tag.as_str()), (deploy, "runbook"));
+    journal.tag_entry(&deploy, "ci").unwrap();
+    journal.tag_entry(&backup, "runbook").unwrap();
+    let error = instruction_error(journal.tag_entry(&backup, "runbook"));
+    assert_eq!(error, custom(JournalError::EntryAlreadyTagged));
+
+    let listed = journal.tag_entries_page("runbook", 0).unwrap();
+    assert_eq!((listed.tag.as_str(), listed.page), ("runbook", 0));
+    assert_eq!(listed.entries, [deploy, backup]);
+    assert_eq!(listed.next_page, None);
+    assert_eq!(journal.tag_entries("ci").unwrap(), [deploy]);
+    assert_eq!(journal.entry("Deploy").unwrap().tags, ["runbook", "ci"]);
+
+    // Updates keep the tags, and room for them
+    journal
+        .update("Deploy", &"anchor deploy ".repeat(20), "ops")
+        .unwrap();
+    let entry = journal.entry("Deploy").unwrap();
+    assert_eq!(entry.tags, ["runbook", "ci"]);
+    assert_eq!(
+        journal.svm.get_account(&deploy).unwrap().data.len(),
+        entry.space_with_title(entry.title.len())
+    );
+
+    let meta = journal.untag_entry(&deploy, "runbook").unwrap();
+    let untagged: EntryUntagged = event(&meta);
+    assert_eq!((untagged.entry, untagged.tag.as_str()), (deploy, "runbook"));
+    assert_eq!(journal.tag_entries("runbook").unwrap(), [backup]);
+    let error = instruction_error(journal.untag_entry(&deploy, "runbook"));
+    assert_eq!(error, custom(JournalError::EntryNotTagged));
+
+    // A tag whose entries are gone lists nothing
+    journal.untag_entry(&deploy, "ci").unwrap();
+    assert!(journal.tag_entries("ci").unwrap().is_empty());
+    let entry = journal.entry("Deploy").unwrap();
+    assert!(entry.tags.is_empty());
+    assert_eq!(
+        journal.svm.get_account(&deploy).unwrap().data.len(),
+        entry.space_with_title(entry.title.len())
+    );
+}
+
+#[test]
+fn renames_and_deletes_keep_the_lists_current() {
+    let mut journal = Journal::new();
+    journal.create("Draft", "content", "").unwrap();
+    journal.create("Other", "content", "").unwrap();
+    let draft = journal.entry_address("Draft");
+    let other = journal.entry_address("Other");
+    journal.tag_entry(&draft, "todo").unwrap();
+    journal.tag_entry(&draft, "solo").unwrap();
+    journal.tag_entry(&other, "todo").unwrap();
+
+    // The tag indexes have to come along
+    let mut instruction = journal.instruction(
+        crud_backend::accounts::RenameJournalEntry {
+            owner: journal.owner.pubkey(),
+            journal_entry: draft,
+            new_journal_entry: journal.entry_address("Final"),
+            system_program: system_program::ID,
+            entry_index: journal.index_of(&draft),
+        },
+        crud_backend::instruction::RenameJournalEntry {
+            old_title: "Draft".to_string(),
+            new_title: "Final".to_string(),
+        },
+    );
+    let mut metas = journal.tag_index_metas(&draft);
+    metas.reverse();
+    instruction.accounts.extend(metas);
+    let error = instruction_error(journal.send(instruction));
+    assert_eq!(error, custom(JournalError::TagIndexAccountsMismatch));
+
+    journal.rename("Draft", "Final").unwrap();
+    let renamed = journal.entry_address("Final");
+    let entry: JournalEntryState = journal.decode(&renamed).unwrap();
+    assert_eq!(entry.tags, ["todo", "solo"]);
+    assert_eq!(journal.tag_entries("todo").unwrap(), [renamed, other]);
+    assert_eq!(journal.tag_entries("solo").unwrap(), [renamed]);
+
+    journal.delete("Final").unwrap();
+    assert_eq!(journal.tag_entries("todo").unwrap(), [other]);
+    assert!(journal.tag_entries("solo").unwrap().is_empty());
+}
+
+#[test]
+fn entries_can_be_tagged_as_they_are_created() {
+    let mut journal = Journal::new();
+    journal
+        .create_tagged("Deploy", "cargo build", "ops", &["runbook", "ci"])
+        .unwrap();
+    let deploy = journal.entry_address("Deploy");
+    let meta = journal
+        .create_tagged("Backup", "pg_dump", "ops", &["runbook"])
+        .unwrap();
+    let backup = journal.entry_address("Backup");
+    let tagged: EntryTagged = event(&meta);
+    assert_eq!((tagged.entry, tagged.tag.as_str()), (backup, "runbook"));
+
+    assert_eq!(journal.tag_entries("runbook").unwrap(), [deploy, backup]);
+    assert_eq!(journal.tag_entries("ci").unwrap(), [deploy]);
+    let entry = journal.entry("Deploy").unwrap();
+    assert_eq!(entry.tags, ["runbook", "ci"]);
+    assert_eq!(
+        journal.svm.get_account(&deploy).unwrap().data.len(),
+        entry.space_with_title(entry.title.len())
+    );
+
+    // Tags are checked as tag_entry checks them
+    let error = instruction_error(journal.create_tagged("Twice", "", "", &["ci", "ci"]));
+    assert_eq!(error, custom(JournalError::EntryAlreadyTagged));
+    let error = instruction_error(journal.create_tagged("Blank", "", "", &[""]));
+    assert_eq!(error, custom(JournalError::TagEmpty));
+}
+
+#[test]
+fn unknown_tags_list_nothing() {
+    let mut journal = Journal::new();
+    let listed = journal.tag_entries_page("never used", 0).unwrap();
+    assert!(listed.entries.is_empty());
+    assert_eq!(listed.next_page, None);
+    assert!(journal
+        .tag_entries_page("never used", 3)
+        .unwrap()
+        .entries
+        .is_empty());
+}
+
+#[test]
+fn tags_list_their_entries_across_pages() {
+    let mut journal = Journal::new();
+    let mut entries = vec![];
+    for entry in 0..TAG_INDEX_PAGE_SIZE + 2 {
+        let title = format!("Entry {entry}");
+        journal.create(&title, "content", "").unwrap();
+        let address = journal.entry_address(&title);
+        journal.tag_entry(&address, "busy").unwrap();
+        entries.push(address);
+    }
+
+    let first = journal.tag_entries_page("busy", 0).unwrap();
+    assert_eq!(first.entries, entries[..TAG_INDEX_PAGE_SIZE]);
+    assert_eq!(first.next_page, Some(1));
+    let second = journal.tag_entries_page("busy", 1).unwrap();
+    assert_eq!(second.entries, entries[TAG_INDEX_PAGE_SIZE..]);
+    assert_eq!(second.next_page, None);
+    let page: TagIndex = journal
+        .decode(&journal.tag_index_address("busy", 1))
+        .unwrap();
+    assert_eq!((page.tag.as_str(), page.page), ("busy", 1));
+
+    // Untagged entries leave gaps, and a full page left empty is closed
+    journal.untag_entry(&entries[1], "busy").unwrap();
+    let first = journal.tag_entries_page("busy", 0).unwrap();
+    assert_eq!(first.entries.len(), TAG_INDEX_PAGE_SIZE - 1);
+    assert!(!first.entries.contains(&entries[1]));
+    let owner_before = journal.svm.lamports(&journal.owner.pubkey());
+    let page_rent = journal.svm.lamports(&journal.tag_index_address("busy", 0));
+    let mut fees = 0;
+    let mut tag_rent = 0;
+    for (n, entry) in entries[..TAG_INDEX_PAGE_SIZE].iter().enumerate() {
+        if n != 1 {
+            let before = journal.svm.lamports(entry);
+            fees += journal.untag_entry(entry, "busy").unwrap().fee;
+            tag_rent += before - journal.svm.lamports(entry);
+        }
+    }
+    assert!(journal
+        .svm
+        .get_account(&journal.tag_index_address("busy", 0))
+        .is_none());
+    assert_eq!(
+        journal.svm.lamports(&journal.owner.pubkey()),
+        owner_before - fees + tag_rent + page_rent
+    );
+    assert_eq!(
+        journal.tag_entries("busy").unwrap(),
+        entries[TAG_INDEX_PAGE_SIZE..]
+    );
+
+    // New entries keep going on the last page
+    journal.tag_entry(&entries[0], "busy").unwrap();
+    let second = journal.tag_entries_page("busy", 1).unwrap();
+    assert_eq!(second.entries.last(), Some(&entries[0]));
+}
+
+#[test]
+fn tags_are_bounded() {
+    let mut journal = Journal::new();
+    journal.create("Busy", "content", "").unwrap();
+    let busy = journal.entry_address("Busy");
+
+    let error = instruction_error(journal.tag_entry(&busy, ""));
+    assert_eq!(error, custom(JournalError::TagEmpty));
+    let error = instruction_error(journal.tag_entry(&busy, &"t".repeat(MAX_TAG_LEN + 1)));
+    assert_eq!(error, custom(JournalError::TagTooLong));
+
+    for tag in 0..MAX_ENTRY_TAGS {
+        journal.tag_entry(&busy, &format!("tag {tag}")).unwrap();
+    }
+    let error = instruction_error(journal.tag_entry(&busy, "one more"));
+    assert_eq!(error, custom(JournalError::TooManyTags));
+
+    let tags: Vec<String> = (0..=MAX_ENTRY_TAGS)
+        .map(|tag| format!("new {tag}"))
+        .collect();
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    let error = instruction_error(journal.create_tagged("Overtagged", "content", "", &tags));
+    assert_eq!(error, custom(JournalError::TooManyTags));
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Route needs at least one leg")]
+    EmptyRoute,
+    #[msg("Route has more legs than one transaction can settle")]
+    TooManyLegs,
+    #[msg("Remaining accounts are not a whole number of legs")]
+    IncompleteLeg,
+    #[msg("Each leg must pay with the token the previous leg bought")]
+    BrokenRoute,
+    #[msg("Route costs more than the maximum amount in")]
+    AmountInExceedsMaximum,
+    #[msg("Route returns less than the minimum amount out")]
+    AmountOutBelowMinimum,
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct Routed {
+    pub taker: Pubkey,
+    pub legs: u8,
+    /// Token paid into the first leg.
+    pub token_mint_in: Pubkey,
+    pub amount_in: u64,
+    /// Token bought by the last leg.
+    pub token_mint_out: Pubkey,
+    pub amount_out: u64,
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use swap::cpi::accounts::TakeOffer;
+use swap::program::Swap;
+use swap::Offer;
+
+use crate::error::ErrorCode;
+use crate::events::Routed;
+
+/// Accounts per leg in `remaining_accounts`: `swap`'s `TakeOffer` accounts
+/// without the payer and taker, which every leg shares, and without the
+/// vesting escrow, since a vesting leg's output can't be spent by the next
+/// one.
+///
+/// 0. maker, 1. rent_payer, 2. token_mint_a, 3. token_mint_b,
+/// 4. taker_token_account_a, 5. taker_token_account_b,
+/// 6. maker_token_account_b, 7. offer, 8. vault, 9. maker_stats,
+/// 10. system_program, 11. token_program, 12. associated_token_program
+pub const LEG_ACCOUNTS: usize = 13;
+
+/// The most legs a legacy transaction, capped at 1232 bytes, can carry.
+///
+/// Besides the payer, the taker and the swap and router programs, the first
+/// leg brings 13 accounts. Every later leg reuses the three programs, the
+/// mint the previous leg bought and the taker's account for it, so it adds
+/// 8 new ones, or 7 when the maker paid the offer's rent. At 32 bytes per
+/// key plus a byte for each of the instruction's 3 + 13 * legs account
+/// indexes, three legs whose makers paid their rent come to 1099 bytes with
+/// the taker as the only signer, and 1195 with a relayer signing too or with
+/// other rent payers. A relayer and other rent payers together, or any
+/// fourth leg, go past the limit and need address lookup tables.
+pub const MAX_LEGS: usize = 3;
+
+const OFFER_INDEX: usize = 7;
+const VAULT_INDEX: usize = 8;
+
+#[derive(Accounts)]
+pub struct Route<'info> {
+    /// Funds any token accounts the legs create. May be the taker or a
+    /// relayer sponsoring the transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Signs every leg through the CPI, so each `take_offer` sees the same
+    /// signature it would if called directly.
+    pub taker: Signer<'info>,
+
+    pub swap_program: Program<'info, Swap>,
+}
+
+/// One offer of the route, with the terms read before it is taken.
+pub struct Leg<'info> {
+    accounts: &'info [AccountInfo<'info>],
+    /// What the taker pays: the offer's token B.
+    pub token_mint_in: Pubkey,
+    pub amount_in: u64,
+    /// What the taker gets: the offer's token A.
+    pub token_mint_out: Pubkey,
+    pub amount_out: u64,
+}
+
+/// Splits `remaining_accounts` into legs and checks that each leg pays with
+/// the token the previous one bought.
+pub fn load_legs<'info>(
+    context: &Context<'_, '_, 'info, 'info, Route<'info>>,
+) -> Result<Vec<Leg<'info>>> {
+    let accounts = context.remaining_accounts;
+    require!(!accounts.is_empty(), ErrorCode::EmptyRoute);
+    // `usize::is_multiple_of` is newer than the platform tools' rustc
+    #[allow(clippy::manual_is_multiple_of)]
+    let whole_legs = accounts.len() % LEG_ACCOUNTS == 0;
+    require!(whole_legs, ErrorCode::IncompleteLeg);
+    require!(
+        accounts.len() / LEG_ACCOUNTS <= MAX_LEGS,
+        ErrorCode::TooManyLegs
+    );
+
+    let mut legs: Vec<Leg<'info>> = Vec::with_capacity(accounts.len() / LEG_ACCOUNTS);
+    for leg_accounts in accounts.chunks_exact(LEG_ACCOUNTS) {
+        // `take_offer` validates the offer and vault again; these reads
+        // only quote the leg.
+        let offer = Account::<Offer>::try_from(&leg_accounts[OFFER_INDEX])?;
+        let vault = InterfaceAccount::<TokenAccount>::try_from(&leg_accounts[VAULT_INDEX])?;
+        let leg = Leg {
+            accounts: leg_accounts,
+            token_mint_in: offer.token_mint_b,
+            amount_in: offer.token_b_wanted_amount,
+            token_mint_out: offer.token_mint_a,
+            amount_out: vault.amount,
+        };
+        if let Some(previous) = legs.last() {
+            require_keys_eq!(
+                previous.token_mint_out,
+                leg.token_mint_in,
+                ErrorCode::BrokenRoute
+            );
+        }
+        legs.push(leg);
+    }
+    Ok(legs)
+}
+
+/// Bounds what the first leg costs and what the last leg returns. Legs in
+/// between are paid from what the previous leg bought, topped up from the
+/// taker's own balance if it falls short.
+pub fn check_amounts(legs: &[Leg], max_amount_in: u64, min_amount_out: u64) -> Result<()> {
+    let (first, last) = (&legs[0], &legs[legs.len() - 1]);
+    require!(
+        first.amount_in <= max_amount_in,
+        ErrorCode::AmountInExceedsMaximum
+    );
+    require!(
+        last.amount_out >= min_amount_out,
+        ErrorCode::AmountOutBelowMinimum
+    );
+    Ok(())
+}
+
+/// Takes each offer in order. Any failing leg aborts the transaction, so
+/// the route settles in full or not at all.
+pub fn take_offers<'info>(
+    context: &Context<'_, '_, 'info, 'info, Route<'info>>,
+    legs: &[Leg<'info>],
+) -> Result<()> {
+    for leg in legs {
+        let account = |index: usize| leg.accounts[index].clone();
+        let take_offer_accounts = TakeOffer {
+            payer: context.accounts.payer.to_account_info(),
+            taker: context.accounts.taker.to_account_info(),
+            maker: account(0),
+            rent_payer: account(1),
+            token_mint_a: account(2),
+            token_mint_b: account(3),
+            taker_token_account_a: account(4),
+            taker_token_account_b: account(5),
+            maker_token_account_b: account(6),
+            offer: account(OFFER_INDEX),
+            vault: account(VAULT_INDEX),
+            maker_stats: account(9),
+            system_program: account(10),
+            token_program: account(11),
+            associated_token_program: account(12),
+            vesting_escrow: None,
+            vesting_vault: None,
+        };
+        let cpi_context = CpiContext::new(
+            context.accounts.swap_program.to_account_info(),
+            take_offer_accounts,
+        );
+        swap::cpi::take_offer(cpi_context)?;
+    }
+
+    let (first, last) = (&legs[0], &legs[legs.len() - 1]);
+    emit!(Routed {
+        taker: context.accounts.taker.key(),
+        legs: legs.len() as u8,
+        token_mint_in: first.token_mint_in,
+        amount_in: first.amount_in,
+        token_mint_out: last.token_mint_out,
+        amount_out: last.amount_out,
+    });
+    Ok(())
+}
@@ -0,0 +1,31 @@
+// The IDL instructions `#[program]` generates at the crate root still call
+// the deprecated `AccountInfo::realloc`, which an attribute on the module
+// doesn't reach.
+#![allow(deprecated)]
+
+pub mod error;
+pub mod events;
+pub mod instructions;
+
+use anchor_lang::prelude::*;
+
+pub use instructions::*;
+
+declare_id!("9ys8y3nnrKrWmQVBLaYKgq1jCQT1iWQ1JaRr2ZkXT2Lj");
+
+#[program]
+pub mod router {
+    use super::*;
+
+    /// Takes a chain of `swap` offers in one instruction. Each leg's
+    /// accounts follow in `remaining_accounts`; see [`LEG_ACCOUNTS`].
+    pub fn route<'info>(
+        context: Context<'_, '_, 'info, 'info, Route<'info>>,
+        max_amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let legs = instructions::route::load_legs(&context)?;
+        instructions::route::check_amounts(&legs, max_amount_in, min_amount_out)?;
+        instructions::route::take_offers(&context, &legs)
+    }
+}
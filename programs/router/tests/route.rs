@@ -0,0 +1,458 @@
+//! Routes through `swap` offers by CPI, in an in-process SVM with both
+//! programs and the real token processors loaded.
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use local_svm::{FailedTransaction, LocalSvm, TransactionMetadata, UpgradeableProgram};
+use router::error::ErrorCode;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::message::Message;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::TransactionError;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda::{find_associated_token_address, find_offer_address};
+use swap_client::state::{Offer, SwapAccount};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+const STARTING_BALANCE: u64 = 1_000_000;
+/// `swap`'s error for a transfer the sender can't cover, from the token
+/// program.
+const INSUFFICIENT_FUNDS: u32 = 1;
+
+/// Two makers quoting a chain X -> Y -> Z, and a taker holding X.
+struct Chain {
+    svm: LocalSvm,
+    token_program: Pubkey,
+    mint_authority: Keypair,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    mint_z: Pubkey,
+    taker: Keypair,
+    maker_y: Keypair,
+    maker_z: Keypair,
+}
+
+impl Chain {
+    fn new(token_program: Pubkey) -> Self {
+        let admin = Keypair::new();
+        let mut svm = LocalSvm::new(&[
+            UpgradeableProgram {
+                name: "swap",
+                program_id: swap::ID,
+                processor: swap::entry,
+                upgrade_authority: Some(admin.pubkey()),
+            },
+            UpgradeableProgram {
+                name: "router",
+                program_id: router::ID,
+                processor: router::entry,
+                upgrade_authority: None,
+            },
+        ]);
+        let payer = svm.payer().insecure_clone();
+        svm.send_transaction(
+            &[instruction::initialize(
+                &payer.pubkey(),
+                &admin.pubkey(),
+                0,
+                0,
+            )],
+            &[&payer, &admin],
+        )
+        .unwrap();
+
+        let mint_authority = Keypair::new();
+        let mint_x = svm.create_mint(&token_program, &mint_authority.pubkey(), 6);
+        let mint_y = svm.create_mint(&token_program, &mint_authority.pubkey(), 6);
+        let mint_z = svm.create_mint(&token_program, &mint_authority.pubkey(), 6);
+        let mut chain = Chain {
+            svm,
+            token_program,
+            mint_authority,
+            mint_x,
+            mint_y,
+            mint_z,
+            taker: Keypair::new(),
+            maker_y: Keypair::new(),
+            maker_z: Keypair::new(),
+        };
+        chain.fund(&chain.taker.pubkey(), &mint_x, STARTING_BALANCE);
+        chain.fund(&chain.taker.pubkey(), &mint_y, 0);
+        chain.fund(&chain.maker_y.pubkey(), &mint_y, STARTING_BALANCE);
+        chain.fund(&chain.maker_z.pubkey(), &mint_z, STARTING_BALANCE);
+        chain
+    }
+
+    fn fund(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) {
+        self.svm.airdrop(owner, 10 * LAMPORTS_PER_SOL);
+        let account = self
+            .svm
+            .create_associated_token_account(owner, mint, &self.token_program);
+        if amount > 0 {
+            let authority = self.mint_authority.insecure_clone();
+            self.svm
+                .mint_to(&self.token_program, mint, &authority, &account, amount);
+        }
+    }
+
+    fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let account = find_associated_token_address(owner, mint, &self.token_program);
+        self.svm.token_balance(&account).unwrap_or(0)
+    }
+
+    /// `maker` offers `offered` of `sell` for `wanted` of `buy`.
+    fn make_offer(
+        &mut self,
+        maker: &Keypair,
+        sell: Pubkey,
+        buy: Pubkey,
+        offered: u64,
+        wanted: u64,
+    ) -> Offer {
+        let instruction = instruction::make_offer(
+            &maker.pubkey(),
+            &maker.pubkey(),
+            &sell,
+            &buy,
+            &self.token_program,
+            &MakeOfferArgs {
+                id: 1,
+                token_a_offered_amount: offered,
+                token_b_wanted_amount: wanted,
+                ..MakeOfferArgs::default()
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[maker]).unwrap();
+        self.offer(&maker.pubkey()).unwrap()
+    }
+
+    fn offer(&self, maker: &Pubkey) -> Option<Offer> {
+        let account = self.svm.get_account(&find_offer_address(maker, 1).0)?;
+        Some(Offer::decode(&account.data).unwrap())
+    }
+
+    /// Sells 100 X for 50 Y, then 50 Y for 25 Z.
+    fn quote(&mut self) -> [Offer; 2] {
+        let (maker_y, maker_z) = (self.maker_y.insecure_clone(), self.maker_z.insecure_clone());
+        let (mint_x, mint_y, mint_z) = (self.mint_x, self.mint_y, self.mint_z);
+        [
+            self.make_offer(&maker_y, mint_y, mint_x, 50, 100),
+            self.make_offer(&maker_z, mint_z, mint_y, 25, 50),
+        ]
+    }
+
+    fn route_ix(
+        &self,
+        payer: &Pubkey,
+        offers: &[Offer],
+        max_amount_in: u64,
+        min_amount_out: u64,
+    ) -> Instruction {
+        let taker = self.taker.pubkey();
+        let mut accounts = router::accounts::Route {
+            payer: *payer,
+            taker,
+            swap_program: swap::ID,
+        }
+        .to_account_metas(None);
+        for offer in offers {
+            // The leg is `take_offer`'s account list without the shared
+            // payer and taker and the vesting escrow at the end.
+            let take = instruction::take_offer(payer, &taker, offer, &self.token_program);
+            accounts.extend_from_slice(&take.accounts[2..2 + router::LEG_ACCOUNTS]);
+        }
+
+        Instruction {
+            program_id: router::ID,
+            accounts,
+            data: router::instruction::Route {
+                max_amount_in,
+                min_amount_out,
+            }
+            .data(),
+        }
+    }
+
+    fn route(
+        &mut self,
+        offers: &[Offer],
+        max_amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let taker = self.taker.insecure_clone();
+        let instruction = self.route_ix(&taker.pubkey(), offers, max_amount_in, min_amount_out);
+        self.svm.send_transaction(&[instruction], &[&taker])
+    }
+
+    /// Token balances of everyone in the chain, for checking rollbacks.
+    fn ledger(&self) -> Vec<u64> {
+        let owners = [&self.taker, &self.maker_y, &self.maker_z];
+        let mints = [self.mint_x, self.mint_y, self.mint_z];
+        owners
+            .iter()
+            .flat_map(|owner| mints.map(|mint| self.balance(&owner.pubkey(), &mint)))
+            .collect()
+    }
+}
+
+fn instruction_error(result: Result<TransactionMetadata, FailedTransaction>) -> InstructionError {
+    match result {
+        Ok(_) => panic!("transaction succeeded"),
+        Err(FailedTransaction {
+            error: TransactionError::InstructionError(_, error),
+            ..
+        }) => error,
+        Err(failure) => panic!("{failure}"),
+    }
+}
+
+fn custom(code: impl Into<u32>) -> InstructionError {
+    InstructionError::Custom(code.into())
+}
+
+#[test]
+fn settles_a_two_leg_chain() {
+    for token_program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let mut chain = Chain::new(token_program);
+        let offers = chain.quote();
+        let taker = chain.taker.pubkey();
+        let (mint_x, mint_y, mint_z) = (chain.mint_x, chain.mint_y, chain.mint_z);
+
+        let meta = chain.route(&offers, 100, 25).unwrap();
+
+        assert_eq!(chain.balance(&taker, &mint_x), STARTING_BALANCE - 100);
+        assert_eq!(chain.balance(&taker, &mint_y), 0);
+        assert_eq!(chain.balance(&taker, &mint_z), 25);
+        assert_eq!(chain.balance(&chain.maker_y.pubkey(), &mint_x), 100);
+        assert_eq!(chain.balance(&chain.maker_z.pubkey(), &mint_y), 50);
+        assert!(chain.offer(&chain.maker_y.pubkey()).is_none());
+        assert!(chain.offer(&chain.maker_z.pubkey()).is_none());
+
+        // Each leg is a `take_offer` called by the router.
+        let swap_calls: Vec<_> = meta
+            .invocations
+            .iter()
+            .filter(|invocation| invocation.program_id == swap::ID)
+            .collect();
+        assert_eq!(swap_calls.len(), 2);
+        assert!(swap_calls.iter().all(|invocation| invocation.depth == 2));
+        assert_eq!(meta.events.len(), 3, "two OfferFilled events and Routed");
+    }
+}
+
+#[test]
+fn taker_signature_reaches_every_leg() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let offers = chain.quote();
+    let relayer = Keypair::new();
+    chain.svm.airdrop(&relayer.pubkey(), LAMPORTS_PER_SOL);
+    let taker = chain.taker.insecure_clone();
+    let taker_lamports = chain.svm.lamports(&taker.pubkey());
+    let relayer_lamports = chain.svm.lamports(&relayer.pubkey());
+
+    // The relayer pays the fee and the token accounts the legs create; the
+    // taker only signs, and swap accepts that signature through the CPI.
+    let instruction = chain.route_ix(&relayer.pubkey(), &offers, 100, 25);
+    let meta = chain
+        .svm
+        .send_transaction(&[instruction], &[&relayer, &taker])
+        .unwrap();
+
+    assert_eq!(chain.balance(&taker.pubkey(), &chain.mint_z), 25);
+    assert_eq!(chain.svm.lamports(&taker.pubkey()), taker_lamports);
+    let created = [
+        (taker.pubkey(), chain.mint_z),
+        (chain.maker_y.pubkey(), chain.mint_x),
+        (chain.maker_z.pubkey(), chain.mint_y),
+    ];
+    let rent: u64 = created
+        .iter()
+        .map(|(owner, mint)| {
+            let account = find_associated_token_address(owner, mint, &chain.token_program);
+            chain.svm.lamports(&account)
+        })
+        .sum();
+    assert_eq!(
+        chain.svm.lamports(&relayer.pubkey()),
+        relayer_lamports - meta.fee - rent
+    );
+}
+
+#[test]
+fn route_needs_the_taker_signature() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let offers = chain.quote();
+    let relayer = Keypair::new();
+    chain.svm.airdrop(&relayer.pubkey(), LAMPORTS_PER_SOL);
+    let before = chain.ledger();
+
+    let mut instruction = chain.route_ix(&relayer.pubkey(), &offers, 100, 25);
+    instruction.accounts[1].is_signer = false;
+    let error = instruction_error(chain.svm.send_transaction(&[instruction], &[&relayer]));
+
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotSigner));
+    assert_eq!(chain.ledger(), before);
+}
+
+#[test]
+fn taker_signature_only_covers_the_taker_accounts() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let offers = chain.quote();
+    let victim = Keypair::new();
+    let mint_x = chain.mint_x;
+    chain.fund(&victim.pubkey(), &mint_x, STARTING_BALANCE);
+    let before = chain.ledger();
+
+    // Pay the first leg from the victim's X account instead.
+    let mut instruction = chain.route_ix(&chain.taker.pubkey(), &offers, 100, 25);
+    let taker_x = 3 + 5;
+    instruction.accounts[taker_x].pubkey =
+        find_associated_token_address(&victim.pubkey(), &mint_x, &chain.token_program);
+    let taker = chain.taker.insecure_clone();
+    let error = instruction_error(chain.svm.send_transaction(&[instruction], &[&taker]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintTokenOwner));
+    assert_eq!(chain.ledger(), before);
+    assert_eq!(chain.balance(&victim.pubkey(), &mint_x), STARTING_BALANCE);
+}
+
+#[test]
+fn failing_leg_rolls_back_the_whole_route() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let (maker_y, maker_z) = (
+        chain.maker_y.insecure_clone(),
+        chain.maker_z.insecure_clone(),
+    );
+    let (mint_x, mint_y, mint_z) = (chain.mint_x, chain.mint_y, chain.mint_z);
+    // The second leg wants more Y than the first one buys, and the taker
+    // holds none of their own.
+    let offers = [
+        chain.make_offer(&maker_y, mint_y, mint_x, 50, 100),
+        chain.make_offer(&maker_z, mint_z, mint_y, 25, 60),
+    ];
+    let before = chain.ledger();
+
+    let error = instruction_error(chain.route(&offers, 100, 25));
+
+    assert_eq!(error, custom(INSUFFICIENT_FUNDS));
+    assert_eq!(chain.ledger(), before);
+    assert_eq!(chain.offer(&maker_y.pubkey()), Some(offers[0].clone()));
+    assert_eq!(chain.offer(&maker_z.pubkey()), Some(offers[1].clone()));
+}
+
+#[test]
+fn enforces_slippage_limits() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let offers = chain.quote();
+    let before = chain.ledger();
+
+    let error = instruction_error(chain.route(&offers, 99, 25));
+    assert_eq!(error, custom(ErrorCode::AmountInExceedsMaximum));
+
+    let error = instruction_error(chain.route(&offers, 100, 26));
+    assert_eq!(error, custom(ErrorCode::AmountOutBelowMinimum));
+
+    assert_eq!(chain.ledger(), before);
+}
+
+#[test]
+fn rejects_malformed_routes() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let [to_y, to_z] = chain.quote();
+
+    let error = instruction_error(chain.route(&[to_z.clone(), to_y.clone()], 100, 0));
+    assert_eq!(error, custom(ErrorCode::BrokenRoute));
+
+    let error = instruction_error(chain.route(&[], 100, 0));
+    assert_eq!(error, custom(ErrorCode::EmptyRoute));
+
+    let mut instruction = chain.route_ix(&chain.taker.pubkey(), &[to_y, to_z], 100, 0);
+    instruction.accounts.pop();
+    let taker = chain.taker.insecure_clone();
+    let error = instruction_error(chain.svm.send_transaction(&[instruction], &[&taker]));
+    assert_eq!(error, custom(ErrorCode::IncompleteLeg));
+}
+
+#[test]
+fn rejects_more_than_max_legs() {
+    let mut chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    let [to_y, to_z] = chain.quote();
+    let before = chain.ledger();
+
+    // The legs repeat, so the transaction stays small; the count is checked
+    // before the chain is.
+    let legs = [to_y.clone(), to_z.clone(), to_y, to_z];
+    assert_eq!(legs.len(), router::MAX_LEGS + 1);
+    let error = instruction_error(chain.route(&legs, 100, 0));
+    assert_eq!(error, custom(ErrorCode::TooManyLegs));
+    assert_eq!(chain.ledger(), before);
+}
+
+/// Size of a legacy transaction carrying `instruction`, signed by `payer`
+/// and the route's taker.
+fn transaction_size(instruction: Instruction, payer: &Pubkey) -> usize {
+    let message = Message::new(&[instruction], Some(payer));
+    1 + 64 * message.header.num_required_signatures as usize + message.serialize().len()
+}
+
+#[test]
+fn max_legs_fit_a_legacy_transaction() {
+    let chain = Chain::new(TOKEN_2022_PROGRAM_ID);
+    // A chain of offers from distinct makers over distinct mints, each
+    // buying the mint the previous one sold.
+    let mints: Vec<Pubkey> = (0..=router::MAX_LEGS + 1)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let legs = |rent_payers_are_makers: bool| -> Vec<Offer> {
+        mints
+            .windows(2)
+            .map(|pair| {
+                let maker = Pubkey::new_unique();
+                Offer {
+                    id: 1,
+                    maker,
+                    token_mint_a: pair[1],
+                    token_mint_b: pair[0],
+                    token_b_wanted_amount: 1,
+                    bump: 255,
+                    version: swap_client::state::OFFER_VERSION,
+                    flags: 0,
+                    min_fill_amount: 0,
+                    created_slot: 0,
+                    vesting_cliff_seconds: 0,
+                    vesting_duration_seconds: 0,
+                    nonce: 1,
+                    rent_payer: if rent_payers_are_makers {
+                        maker
+                    } else {
+                        Pubkey::new_unique()
+                    },
+                }
+            })
+            .collect()
+    };
+    let taker = chain.taker.pubkey();
+    let relayer = Pubkey::new_unique();
+
+    let offers = legs(true);
+    let (fitting, too_many) = offers.split_at(router::MAX_LEGS);
+    assert_eq!(too_many.len(), 1);
+    for payer in [taker, relayer] {
+        let size = transaction_size(chain.route_ix(&payer, fitting, 1, 0), &payer);
+        assert!(size <= PACKET_DATA_SIZE, "{size} bytes");
+        let size = transaction_size(chain.route_ix(&payer, &offers, 1, 0), &payer);
+        assert!(size > PACKET_DATA_SIZE, "{size} bytes");
+    }
+
+    // With separate rent payers, the taker still fits paying their own way.
+    let offers = legs(false);
+    let size = transaction_size(
+        chain.route_ix(&taker, &offers[..router::MAX_LEGS], 1, 0),
+        &taker,
+    );
+    assert!(size <= PACKET_DATA_SIZE, "{size} bytes");
+}
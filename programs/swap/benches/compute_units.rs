@@ -0,0 +1,320 @@
+//! Compute-unit report for every `swap` instruction under SPL Token and
+//! Token-2022. Run `anchor build` (or `cargo build-sbf`) and then
+//! `cargo bench -p swap --bench compute_units`; it rewrites
+//! `benches/compute_units.md`, so changes show up in review.
+//!
+//! The harness meters `target/deploy/swap.so`. Without that build it would
+//! run `swap` natively, which it cannot meter, so the bench panics rather
+//! than overwrite the report.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::fmt::Write as _;
+
+use common::{Market, MAX_OPEN_OFFERS_PER_MAKER, OFFER_DEPOSIT_LAMPORTS};
+use local_svm::{LocalSvm, TransactionMetadata, UpgradeableProgram};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda::{
+    find_offer_address, find_quote_address, find_rfq_address, find_vesting_escrow_address,
+};
+use swap_client::state::{Offer, Quote, Rfq, SwapAccount, VestingEscrow};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+const OFFER_V0_FIXTURE: &[u8] = include_bytes!("../tests/fixtures/offer_v0.bin");
+
+struct Row {
+    instruction: &'static str,
+    meta: TransactionMetadata,
+}
+
+fn send(svm: &mut LocalSvm, instruction: Instruction, signers: &[&Keypair]) -> TransactionMetadata {
+    svm.send_transaction(&[instruction], signers)
+        .unwrap_or_else(|failure| panic!("{failure}"))
+}
+
+fn decode<T: SwapAccount>(svm: &LocalSvm, address: &Pubkey) -> T {
+    T::decode(&svm.get_account(address).unwrap().data).unwrap()
+}
+
+fn config_rows(rows: &mut Vec<Row>) {
+    let admin = Keypair::new();
+    let mut svm = LocalSvm::new(&[UpgradeableProgram {
+        name: "swap",
+        program_id: swap::ID,
+        processor: swap::entry,
+        upgrade_authority: Some(admin.pubkey()),
+    }]);
+    assert!(
+        !svm.is_native(&swap::ID),
+        "target/deploy/swap.so is missing; run `anchor build` so the compute units can be metered"
+    );
+    let payer = svm.payer().insecure_clone();
+
+    let initialize = instruction::initialize(
+        &payer.pubkey(),
+        &admin.pubkey(),
+        MAX_OPEN_OFFERS_PER_MAKER,
+        OFFER_DEPOSIT_LAMPORTS,
+    );
+    rows.push(Row {
+        instruction: "initialize",
+        meta: send(&mut svm, initialize, &[&payer, &admin]),
+    });
+
+    let update = instruction::update_config(&admin.pubkey(), 20, OFFER_DEPOSIT_LAMPORTS);
+    rows.push(Row {
+        instruction: "update_config",
+        meta: send(&mut svm, update, &[&payer, &admin]),
+    });
+}
+
+fn offer_rows(rows: &mut Vec<Row>, token_program: Pubkey) {
+    let mut market = Market::new(token_program);
+    let alice = market.alice.insecure_clone();
+    let bob = market.bob.insecure_clone();
+    let offer = |market: &Market, id| -> Offer {
+        decode(&market.svm, &find_offer_address(&alice.pubkey(), id).0)
+    };
+
+    rows.push(Row {
+        instruction: "make_offer (first offer of the maker)",
+        meta: market.make_offer(1, 1_000_000, 2_000_000).unwrap(),
+    });
+    rows.push(Row {
+        instruction: "make_offer",
+        meta: market.make_offer(2, 1_000_000, 2_000_000).unwrap(),
+    });
+
+    let take = market.take_offer_ix(&bob, &offer(&market, 1));
+    rows.push(Row {
+        instruction: "take_offer (creates both token accounts)",
+        meta: send(&mut market.svm, take, &[&bob]),
+    });
+    let take = market.take_offer_ix(&bob, &offer(&market, 2));
+    rows.push(Row {
+        instruction: "take_offer",
+        meta: send(&mut market.svm, take, &[&bob]),
+    });
+
+    market.make_offer(3, 1_000_000, 2_000_000).unwrap();
+    let fill = instruction::fill_offer(
+        &bob.pubkey(),
+        &bob.pubkey(),
+        &offer(&market, 3),
+        &token_program,
+        400_000,
+    );
+    rows.push(Row {
+        instruction: "fill_offer (partial)",
+        meta: send(&mut market.svm, fill, &[&bob]),
+    });
+    let fill = instruction::fill_offer(
+        &bob.pubkey(),
+        &bob.pubkey(),
+        &offer(&market, 3),
+        &token_program,
+        600_000,
+    );
+    rows.push(Row {
+        instruction: "fill_offer (closes the offer)",
+        meta: send(&mut market.svm, fill, &[&bob]),
+    });
+
+    let make = market.make_offer_ix(
+        &alice,
+        &MakeOfferArgs {
+            id: 4,
+            token_a_offered_amount: 1_000_000,
+            token_b_wanted_amount: 2_000_000,
+            vesting_cliff_seconds: 100,
+            vesting_duration_seconds: 1_000,
+            ..MakeOfferArgs::default()
+        },
+    );
+    send(&mut market.svm, make, &[&alice]);
+    let vesting_offer = offer(&market, 4);
+    let take = market.take_offer_ix(&bob, &vesting_offer);
+    rows.push(Row {
+        instruction: "take_offer (vesting)",
+        meta: send(&mut market.svm, take, &[&bob]),
+    });
+    let escrow_address = find_vesting_escrow_address(
+        &find_offer_address(&alice.pubkey(), 4).0,
+        vesting_offer.nonce,
+    )
+    .0;
+    let start = market.svm.clock().unix_timestamp;
+    for (elapsed, label) in [
+        (500, "claim_vested (partial)"),
+        (1_000, "claim_vested (closes the escrow)"),
+    ] {
+        market.svm.set_unix_timestamp(start + elapsed);
+        let escrow: VestingEscrow = decode(&market.svm, &escrow_address);
+        let claim = instruction::claim_vested(&escrow, &token_program);
+        rows.push(Row {
+            instruction: label,
+            meta: send(&mut market.svm, claim, &[&bob]),
+        });
+    }
+
+    let legacy_offer = Pubkey::new_unique();
+    let lamports = market.svm.minimum_balance(OFFER_V0_FIXTURE.len());
+    market.svm.set_account(
+        legacy_offer,
+        Account {
+            lamports,
+            data: OFFER_V0_FIXTURE.to_vec(),
+            owner: swap::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let migrate = instruction::migrate_offer(&alice.pubkey(), &legacy_offer);
+    rows.push(Row {
+        instruction: "migrate_offer",
+        meta: send(&mut market.svm, migrate, &[&alice]),
+    });
+}
+
+fn quote_rows(rows: &mut Vec<Row>, token_program: Pubkey) {
+    let mut market = Market::new(token_program);
+    let alice = market.alice.insecure_clone();
+    let bob = market.bob.insecure_clone();
+    let (mint_a, mint_b) = (market.token_mint_a, market.token_mint_b);
+
+    for id in [1, 2, 3] {
+        let request = instruction::request_quote(
+            &alice.pubkey(),
+            &alice.pubkey(),
+            &mint_a,
+            &mint_b,
+            &token_program,
+            id,
+            1_000_000,
+        );
+        let meta = send(&mut market.svm, request, &[&alice]);
+        if id == 1 {
+            rows.push(Row {
+                instruction: "request_quote",
+                meta,
+            });
+        }
+    }
+    let rfq = |market: &Market, id| -> Rfq {
+        decode(&market.svm, &find_rfq_address(&alice.pubkey(), id).0)
+    };
+    let quote = |market: &Market, rfq: &Rfq| -> Quote {
+        let rfq_address = find_rfq_address(&rfq.requester, rfq.id).0;
+        decode(
+            &market.svm,
+            &find_quote_address(&rfq_address, rfq.nonce, &bob.pubkey()).0,
+        )
+    };
+
+    for id in [1, 2, 3] {
+        let submit = instruction::submit_quote(
+            &bob.pubkey(),
+            &bob.pubkey(),
+            &rfq(&market, id),
+            &token_program,
+            500_000,
+        );
+        let meta = send(&mut market.svm, submit, &[&bob]);
+        if id == 1 {
+            rows.push(Row {
+                instruction: "submit_quote",
+                meta,
+            });
+        }
+    }
+
+    let first = rfq(&market, 1);
+    let accept = instruction::accept_quote(
+        &alice.pubkey(),
+        &first,
+        &quote(&market, &first),
+        500_000,
+        &token_program,
+    );
+    rows.push(Row {
+        instruction: "accept_quote",
+        meta: send(&mut market.svm, accept, &[&alice, &bob]),
+    });
+
+    let second = rfq(&market, 2);
+    let withdraw = instruction::withdraw_quote(&quote(&market, &second), &token_program);
+    rows.push(Row {
+        instruction: "withdraw_quote",
+        meta: send(&mut market.svm, withdraw, &[&bob]),
+    });
+
+    let third = rfq(&market, 3);
+    let reject = instruction::reject_quote(&third, &quote(&market, &third), &token_program);
+    rows.push(Row {
+        instruction: "reject_quote",
+        meta: send(&mut market.svm, reject, &[&alice]),
+    });
+
+    let cancel = instruction::cancel_rfq(&third);
+    rows.push(Row {
+        instruction: "cancel_rfq",
+        meta: send(&mut market.svm, cancel, &[&alice]),
+    });
+}
+
+fn table(report: &mut String, rows: &[Row]) {
+    report.push_str(
+        "| Instruction | Compute units | Invocations | CPIs | Max depth |\n\
+         |---|---:|---:|---:|---:|\n",
+    );
+    for row in rows {
+        let invocations = &row.meta.invocations;
+        writeln!(
+            report,
+            "| `{}` | {} | {} | {} | {} |",
+            row.instruction,
+            row.meta.compute_units_consumed,
+            invocations.len(),
+            invocations.iter().filter(|i| i.depth > 1).count(),
+            invocations.iter().map(|i| i.depth).max().unwrap(),
+        )
+        .unwrap();
+    }
+}
+
+fn main() {
+    let mut rows = Vec::new();
+    config_rows(&mut rows);
+
+    let mut report = String::from(
+        "# swap execution costs\n\n\
+         Generated by `cargo bench -p swap --bench compute_units`. Do not edit by hand.\n\n\
+         Compute units are the whole transaction as metered from `target/deploy/swap.so`, \
+         including the SPL programs it calls. Invocations count every program run, and \
+         each CPI costs at least 1,000 CU on chain.\n",
+    );
+
+    report.push_str("\n## Config\n\n");
+    table(&mut report, &rows);
+
+    for (name, token_program) in [
+        ("SPL Token", TOKEN_PROGRAM_ID),
+        ("Token-2022", TOKEN_2022_PROGRAM_ID),
+    ] {
+        let mut rows = Vec::new();
+        offer_rows(&mut rows, token_program);
+        quote_rows(&mut rows, token_program);
+        write!(report, "\n## {name}\n\n").unwrap();
+        table(&mut report, &rows);
+    }
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/compute_units.md");
+    std::fs::write(path, &report).unwrap();
+    print!("{report}");
+}
@@ -4,4 +4,44 @@ use anchor_lang::prelude::*;
 pub enum ErrorCode {
     #[msg("Custom error message")]
     CustomError,
+    #[msg("Account is not an offer")]
+    NotAnOffer,
+    #[msg("Offer already uses the current layout")]
+    OfferAlreadyMigrated,
+    #[msg("Offer has an unrecognized layout")]
+    UnknownOfferLayout,
+    #[msg("Offer flags contain unknown bits")]
+    InvalidOfferFlags,
+    #[msg("Minimum fill is larger than the amount offered")]
+    MinFillExceedsOffer,
+    #[msg("Fill amount must be greater than zero")]
+    ZeroFill,
+    #[msg("Fill amount is larger than what is left in the offer")]
+    FillExceedsOffer,
+    #[msg("All-or-none offer must be filled in full")]
+    AllOrNoneOffer,
+    #[msg("Fill amount is below the offer's minimum fill")]
+    FillBelowMinimum,
+    #[msg("Post-only offer cannot be filled in the slot it was made")]
+    PostOnlyOfferNotResting,
+    #[msg("Maker already has the maximum number of open offers")]
+    TooManyOpenOffers,
+    #[msg("Only the program's upgrade authority can initialize the config")]
+    NotUpgradeAuthority,
+    #[msg("Vesting cliff must be between zero and the vesting duration")]
+    InvalidVestingSchedule,
+    #[msg("Vesting offer can only be taken in full with take_offer")]
+    VestingOfferNotFillable,
+    #[msg("Vesting offer needs the vesting escrow and vault accounts")]
+    MissingVestingEscrow,
+    #[msg("Vesting escrow accounts were passed for an offer that does not vest")]
+    UnexpectedVestingEscrow,
+    #[msg("No vested tokens to claim yet")]
+    NothingToClaim,
+    #[msg("Offered and wanted amounts must be greater than zero")]
+    ZeroOfferAmount,
+    #[msg("Partial fill would pay everything still wanted and leave token A in the vault")]
+    FillLeavesNothingWanted,
+    #[msg("Quote asks for more token A than the requester agreed to pay")]
+    QuoteAboveMaximum,
 }
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub id: u64,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_offered_amount: u64,
+    pub token_b_wanted_amount: u64,
+    pub flags: u8,
+    pub min_fill_amount: u64,
+    pub vesting_cliff_seconds: i64,
+    /// Zero unless token A goes to a vesting escrow on take.
+    pub vesting_duration_seconds: i64,
+}
+
+#[event]
+pub struct OfferFilled {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    /// Token A left in the vault. Zero means the offer was closed.
+    pub token_a_remaining: u64,
+    pub flags: u8,
+    pub min_fill_amount: u64,
+}
+
+#[event]
+pub struct OfferCancelled {
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    /// Token A returned from the vault to the maker.
+    pub token_a_refunded: u64,
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::error::ErrorCode;
+use crate::state::quote::Quote;
+use crate::state::rfq::Rfq;
+
+use super::{empty_and_close_vault, transfer_tokens};
+
+#[derive(Accounts)]
+pub struct AcceptQuote<'info> {
+    /// Funds any token accounts created below. May be the requester or a
+    /// sponsoring relayer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must sign to authorize paying token A to the maker. Receives the RFQ
+    /// rent once the request is filled.
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = requester,
+        associated_token::token_program = token_program,
+    )]
+    pub requester_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = requester,
+        associated_token::token_program = token_program,
+    )]
+    pub requester_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Closing the RFQ is what stops the remaining quotes from being
+    /// accepted; their makers reclaim them with `withdraw_quote`.
+    #[account(
+        mut,
+        close = requester,
+        has_one = requester,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        seeds = [b"rfq", requester.key().as_ref(), rfq.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub rfq: Box<Account<'info, Rfq>>,
+
+    /// Seeded with the RFQ's nonce, so a quote left over from an earlier RFQ
+    /// with the same id doesn't match.
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = rfq,
+        seeds = [b"quote", rfq.key().as_ref(), rfq.nonce.to_le_bytes().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub quote: Box<Account<'info, Quote>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = quote,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Pays the maker the quoted price, as long as it is no more than
+/// `max_token_a_amount`. The maker can withdraw a quote and submit a dearer
+/// one at the same address between the requester reading it and accepting
+/// it, so the requester states what they saw.
+pub fn send_quoted_price_to_maker(
+    context: &Context<AcceptQuote>,
+    max_token_a_amount: u64,
+) -> Result<()> {
+    require!(
+        context.accounts.quote.token_a_wanted_amount <= max_token_a_amount,
+        ErrorCode::QuoteAboveMaximum
+    );
+
+    transfer_tokens(
+        &context.accounts.requester_token_account_a,
+        &context.accounts.maker_token_account_a,
+        &context.accounts.quote.token_a_wanted_amount,
+        &context.accounts.token_mint_a,
+        &context.accounts.requester,
+        &context.accounts.token_program,
+    )
+}
+
+pub fn release_quoted_tokens(
+    context: Context<AcceptQuote>,
+) -> Result<()> {
+    let quote_bump = context.accounts.quote.bump;
+    let rfq_key = context.accounts.rfq.key();
+    let rfq_nonce = context.accounts.rfq.nonce.to_le_bytes();
+    let maker_key = context.accounts.maker.key();
+    let seeds: [&[u8]; 5] = [
+        b"quote",
+        rfq_key.as_ref(),
+        &rfq_nonce,
+        maker_key.as_ref(),
+        &[quote_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    empty_and_close_vault(
+        &context.accounts.vault,
+        &context.accounts.requester_token_account_b,
+        &context.accounts.token_mint_b,
+        context.accounts.quote.to_account_info(),
+        context.accounts.maker.to_account_info(),
+        &context.accounts.token_program,
+        signer_seeds,
+    )
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::events::OfferCancelled;
+use crate::state::maker_stats::MakerStats;
+use crate::state::offer::Offer;
+
+use super::{empty_and_close_vault, return_offer_deposit};
+
+/// Withdraws an offer nobody took. Token A and the deposit go back to the
+/// maker, the offer and vault rent to the offer's rent payer.
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = maker,
+        has_one = rent_payer,
+        has_one = token_mint_a,
+        seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Created here for offers made before open offers were counted.
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = MakerStats::SPACE,
+        seeds = [b"maker", maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_stats: Box<Account<'info, MakerStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn refund_and_close_vault(
+    context: Context<CancelOffer>,
+) -> Result<()> {
+    let offer_bump = context.accounts.offer.bump;
+    let maker_key = context.accounts.maker.key();
+    let seeds: [&[u8]; 4] = [
+        b"offer",
+        maker_key.as_ref(),
+        &context.accounts.offer.id.to_le_bytes(),
+        &[offer_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    context.accounts.maker_stats.close_offer(maker_key, context.bumps.maker_stats);
+
+    emit!(OfferCancelled {
+        offer: context.accounts.offer.key(),
+        maker: maker_key,
+        token_a_refunded: context.accounts.vault.amount,
+    });
+
+    empty_and_close_vault(
+        &context.accounts.vault,
+        &context.accounts.maker_token_account_a,
+        &context.accounts.token_mint_a,
+        context.accounts.offer.to_account_info(),
+        context.accounts.rent_payer.to_account_info(),
+        &context.accounts.token_program,
+        signer_seeds,
+    )?;
+
+    return_offer_deposit(
+        &context.accounts.offer.to_account_info(),
+        &context.accounts.maker.to_account_info(),
+    )?;
+    Ok(())
+}
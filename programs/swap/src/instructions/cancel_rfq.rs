@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::state::rfq::Rfq;
+
+/// Closes an RFQ nobody filled. Outstanding quotes stay in their vaults
+/// until their makers call `withdraw_quote`; reject them with
+/// `reject_quote` first to send them back right away.
+#[derive(Accounts)]
+pub struct CancelRfq<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    #[account(
+        mut,
+        close = requester,
+        has_one = requester,
+        seeds = [b"rfq", requester.key().as_ref(), rfq.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub rfq: Account<'info, Rfq>,
+}
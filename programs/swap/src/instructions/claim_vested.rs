@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::error::ErrorCode;
+use crate::state::vesting_escrow::VestingEscrow;
+
+use super::{empty_and_close_vault, transfer_from_vault};
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    /// The taker of the vesting offer. Receives the escrow's rent once
+    /// everything has been claimed.
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = token_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = beneficiary,
+        has_one = token_mint,
+        seeds = [
+            b"vesting",
+            vesting_escrow.offer.as_ref(),
+            vesting_escrow.offer_nonce.to_le_bytes().as_ref(),
+        ],
+        bump = vesting_escrow.bump,
+    )]
+    pub vesting_escrow: Box<Account<'info, VestingEscrow>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vesting_escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Sends everything vested so far to the beneficiary. The claim that
+/// releases the last tokens closes the escrow and its vault.
+pub fn release_vested_tokens(
+    context: Context<ClaimVested>,
+) -> Result<()> {
+    let escrow = &context.accounts.vesting_escrow;
+    let now = Clock::get()?.unix_timestamp;
+    let claimable = escrow.claimable_amount(now);
+    require!(claimable > 0, ErrorCode::NothingToClaim);
+
+    let offer_nonce = escrow.offer_nonce.to_le_bytes();
+    let seeds: [&[u8]; 4] = [
+        b"vesting",
+        escrow.offer.as_ref(),
+        &offer_nonce,
+        &[escrow.bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    if escrow.claimed_amount + claimable == escrow.total_amount {
+        empty_and_close_vault(
+            &context.accounts.vesting_vault,
+            &context.accounts.beneficiary_token_account,
+            &context.accounts.token_mint,
+            context.accounts.vesting_escrow.to_account_info(),
+            context.accounts.beneficiary.to_account_info(),
+            &context.accounts.token_program,
+            signer_seeds,
+        )?;
+        return context.accounts.vesting_escrow.close(context.accounts.beneficiary.to_account_info());
+    }
+
+    transfer_from_vault(
+        &context.accounts.vesting_vault,
+        &context.accounts.beneficiary_token_account,
+        claimable,
+        &context.accounts.token_mint,
+        context.accounts.vesting_escrow.to_account_info(),
+        &context.accounts.token_program,
+        signer_seeds,
+    )?;
+    context.accounts.vesting_escrow.claimed_amount += claimable;
+    Ok(())
+}
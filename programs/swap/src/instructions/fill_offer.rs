@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::error::ErrorCode;
+use crate::events::OfferFilled;
+use crate::state::maker_stats::MakerStats;
+use crate::state::offer::Offer;
+
+use super::{empty_and_close_vault, return_offer_deposit, transfer_from_vault, transfer_tokens};
+
+/// Same accounts as `TakeOffer`, except the offer is only closed when the
+/// fill empties the vault.
+#[derive(Accounts)]
+pub struct FillOffer<'info> {
+    /// Funds any token accounts created below. May be the taker or a relayer
+    /// sponsoring the transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must still sign to authorize sending token B to the maker.
+    pub taker: Signer<'info>,
+
+    /// Gets the deposit back once the offer is filled.
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    /// Gets the offer and vault rent back once the offer is filled.
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = rent_payer,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Created here for offers made before open offers were counted.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MakerStats::SPACE,
+        seeds = [b"maker", maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_stats: Box<Account<'info, MakerStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn fill_from_vault(
+    context: Context<FillOffer>,
+    token_a_amount: u64,
+) -> Result<()> {
+    require!(!context.accounts.offer.vests(), ErrorCode::VestingOfferNotFillable);
+
+    let token_a_remaining = context.accounts.vault.amount;
+    let token_b_amount = context.accounts.offer.check_fill(
+        token_a_amount,
+        token_a_remaining,
+        Clock::get()?.slot,
+    )?;
+
+    transfer_tokens(
+        &context.accounts.taker_token_account_b,
+        &context.accounts.maker_token_account_b,
+        &token_b_amount,
+        &context.accounts.token_mint_b,
+        &context.accounts.taker,
+        &context.accounts.token_program,
+    )?;
+
+    let offer_bump = context.accounts.offer.bump;
+    let maker_key = context.accounts.maker.key();
+    let offer_id = context.accounts.offer.id.to_le_bytes();
+    let seeds: [&[u8]; 4] = [
+        b"offer",
+        maker_key.as_ref(),
+        &offer_id,
+        &[offer_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    if token_a_amount == token_a_remaining {
+        empty_and_close_vault(
+            &context.accounts.vault,
+            &context.accounts.taker_token_account_a,
+            &context.accounts.token_mint_a,
+            context.accounts.offer.to_account_info(),
+            context.accounts.rent_payer.to_account_info(),
+            &context.accounts.token_program,
+            signer_seeds,
+        )?;
+        return_offer_deposit(
+            &context.accounts.offer.to_account_info(),
+            &context.accounts.maker.to_account_info(),
+        )?;
+        context.accounts.offer.close(context.accounts.rent_payer.to_account_info())?;
+        context.accounts.maker_stats.close_offer(maker_key, context.bumps.maker_stats);
+    } else {
+        transfer_from_vault(
+            &context.accounts.vault,
+            &context.accounts.taker_token_account_a,
+            token_a_amount,
+            &context.accounts.token_mint_a,
+            context.accounts.offer.to_account_info(),
+            &context.accounts.token_program,
+            signer_seeds,
+        )?;
+        context.accounts.offer.token_b_wanted_amount -= token_b_amount;
+    }
+
+    let offer = &context.accounts.offer;
+    emit!(OfferFilled {
+        offer: offer.key(),
+        maker: offer.maker,
+        taker: context.accounts.taker.key(),
+        token_a_amount,
+        token_b_amount,
+        token_a_remaining: token_a_remaining - token_a_amount,
+        flags: offer.flags,
+        min_fill_amount: offer.min_fill_amount,
+    });
+    Ok(())
+}
@@ -1,12 +1,51 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::program::Swap;
+use crate::state::config::{Config, CONFIG_RESERVED_SPACE, CONFIG_VERSION};
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+
+    /// Becomes the config admin. Must be the program's upgrade authority so
+    /// nobody can claim the config between deployment and initialization.
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Config::SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Swap>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(_ctx: Context<Initialize>) -> Result<()> {
+pub fn save_config(
+    context: Context<Initialize>,
+    max_open_offers_per_maker: u32,
+    offer_deposit_lamports: u64,
+) -> Result<()> {
+    context.accounts.config.set_inner(Config {
+        admin: context.accounts.admin.key(),
+        max_open_offers_per_maker,
+        offer_deposit_lamports,
+        bump: context.bumps.config,
+        version: CONFIG_VERSION,
+        reserved: [0; CONFIG_RESERVED_SPACE],
+    });
     Ok(())
 }
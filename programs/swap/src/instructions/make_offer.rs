@@ -1,12 +1,24 @@
-use crate::constants::*;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{token_interface::{TokenInterface, Mint, TokenAccount}, associated_token::AssociatedToken};
-use crate::state::offer::Offer;
+use crate::error::ErrorCode;
+use crate::events::OfferMade;
+use crate::state::config::Config;
+use crate::state::maker_stats::MakerStats;
+use crate::state::offer::{Offer, OFFER_RESERVED_SPACE, OFFER_VERSION};
 use crate::transfer_tokens;
 
 #[derive(Accounts)]
 #[instruction(id: u64)]
 pub struct MakeOffer<'info> {
+    /// Funds the offer and vault accounts, and is recorded as the offer's
+    /// `rent_payer` to get that rent back when it closes. May be the maker
+    /// or a relayer sponsoring the transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must still sign to authorize moving token A into the vault, and pays
+    /// the deposit, which is refunded to them when the offer closes.
     #[account(mut)]
     pub maker: Signer<'info>,
 
@@ -26,8 +38,8 @@ pub struct MakeOffer<'info> {
 
     #[account(
         init,
-        payer = maker,
-        space = ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE,
+        payer = payer,
+        space = Offer::SPACE,
         seeds = [b"offer", maker.key().as_ref(), id.to_le_bytes().as_ref()],
         bump,
     )]
@@ -35,13 +47,25 @@ pub struct MakeOffer<'info> {
 
     #[account(
         init,
-        payer = maker,
+        payer = payer,
         associated_token::mint = token_mint_a,
         associated_token::authority = offer,
         associated_token::token_program = token_program,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MakerStats::SPACE,
+        seeds = [b"maker", maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_stats: Box<Account<'info, MakerStats>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -64,11 +88,51 @@ pub fn send_offered_tokens_to_vault(
        
 }
 
+/// Locks the configured anti-spam deposit in the offer account. It sits on
+/// top of the rent and comes from the maker rather than the payer, since the
+/// maker is who gets it back when the offer closes.
+pub fn lock_offer_deposit(
+    context: &Context<MakeOffer>,
+) -> Result<()> {
+    let deposit = context.accounts.config.offer_deposit_lamports;
+    if deposit == 0 {
+        return Ok(());
+    }
+
+    let transfer_accounts = Transfer {
+        from: context.accounts.maker.to_account_info(),
+        to: context.accounts.offer.to_account_info(),
+    };
+    let cpi_context = CpiContext::new(
+        context.accounts.system_program.to_account_info(),
+        transfer_accounts,
+    );
+    transfer(cpi_context, deposit)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn save_offer(
     context: Context<MakeOffer>, 
     id: u64, 
-    token_b_wanted_amount: u64
+    token_a_offered_amount: u64,
+    token_b_wanted_amount: u64,
+    flags: u8,
+    min_fill_amount: u64,
+    vesting_cliff_seconds: i64,
+    vesting_duration_seconds: i64,
 ) -> Result<()> {
+    Offer::check_terms(token_a_offered_amount, token_b_wanted_amount, flags, min_fill_amount)?;
+    require!(
+        0 <= vesting_cliff_seconds && vesting_cliff_seconds <= vesting_duration_seconds,
+        ErrorCode::InvalidVestingSchedule
+    );
+
+    let nonce = context.accounts.maker_stats.open_offer(
+        context.accounts.maker.key(),
+        context.bumps.maker_stats,
+        context.accounts.config.max_open_offers_per_maker,
+    )?;
+
     context.accounts.offer.set_inner(Offer {
         id,
         maker: context.accounts.maker.key(),
@@ -76,7 +140,29 @@ pub fn save_offer(
         token_mint_b: context.accounts.token_mint_b.key(),
         token_b_wanted_amount,
         bump: context.bumps.offer,
+        version: OFFER_VERSION,
+        flags,
+        min_fill_amount,
+        created_slot: Clock::get()?.slot,
+        vesting_cliff_seconds,
+        vesting_duration_seconds,
+        nonce,
+        rent_payer: context.accounts.payer.key(),
+        reserved: [0; OFFER_RESERVED_SPACE],
+    });
+
+    emit!(OfferMade {
+        offer: context.accounts.offer.key(),
+        maker: context.accounts.maker.key(),
+        id,
+        token_mint_a: context.accounts.token_mint_a.key(),
+        token_mint_b: context.accounts.token_mint_b.key(),
+        token_a_offered_amount,
+        token_b_wanted_amount,
+        flags,
+        min_fill_amount,
+        vesting_cliff_seconds,
+        vesting_duration_seconds,
     });
     Ok(())
 }
-
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::ErrorCode;
+use crate::state::offer::{Offer, OfferV0, OFFER_VERSION};
+
+#[derive(Accounts)]
+pub struct MigrateOffer<'info> {
+    /// Covers the extra rent for the larger layout. Anyone may migrate an
+    /// offer, since the upgrade never changes its terms.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: may still hold a legacy layout that `Account<Offer>` can't
+    /// deserialize. Ownership is checked here and the discriminator in
+    /// `upgrade_offer_data`.
+    #[account(mut, owner = crate::ID)]
+    pub offer: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn grow_offer_account(context: &Context<MigrateOffer>) -> Result<()> {
+    let offer = context.accounts.offer.to_account_info();
+    match offer.data_len() {
+        OfferV0::SPACE | Offer::V3_SPACE => {}
+        Offer::SPACE => return err!(ErrorCode::OfferAlreadyMigrated),
+        _ => return err!(ErrorCode::UnknownOfferLayout),
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(Offer::SPACE)
+        .saturating_sub(offer.lamports());
+
+    if rent_due > 0 {
+        let transfer_accounts = Transfer {
+            from: context.accounts.payer.to_account_info(),
+            to: offer.clone(),
+        };
+        let cpi_context = CpiContext::new(
+            context.accounts.system_program.to_account_info(),
+            transfer_accounts,
+        );
+        transfer(cpi_context, rent_due)?;
+    }
+
+    offer.resize(Offer::SPACE)?;
+    Ok(())
+}
+
+pub fn rewrite_offer(context: Context<MigrateOffer>) -> Result<()> {
+    let offer = context.accounts.offer.to_account_info();
+    let mut data = offer.try_borrow_mut_data()?;
+    upgrade_offer_data(&mut data)
+}
+
+/// Rewrites a legacy offer that has already been resized to
+/// [`Offer::SPACE`] into the current layout, keeping its terms intact.
+///
+/// The version byte right after the [`OfferV0`] fields tells the layouts
+/// apart: it is zero for v0 offers, whose resized tail is all zeros. Version
+/// 1 to 3 offers only ever held zeros in their reserved space, so with the
+/// zeroed tail they read as a current offer with no `rent_payer` yet.
+pub fn upgrade_offer_data(data: &mut [u8]) -> Result<()> {
+    require!(
+        data.len() >= Offer::SPACE && data.starts_with(Offer::DISCRIMINATOR),
+        ErrorCode::NotAnOffer
+    );
+
+    let offer = match data[OfferV0::SPACE] {
+        0 => {
+            let mut legacy_data = &data[Offer::DISCRIMINATOR.len()..OfferV0::SPACE];
+            Offer::from(OfferV0::deserialize(&mut legacy_data)?)
+        }
+        version if version < OFFER_VERSION => {
+            let mut offer = Offer::try_deserialize(&mut &data[..])?;
+            offer.version = OFFER_VERSION;
+            offer.rent_payer = offer.maker;
+            offer
+        }
+        _ => return err!(ErrorCode::OfferAlreadyMigrated),
+    };
+
+    let mut writer: &mut [u8] = data;
+    offer.try_serialize(&mut writer)
+}
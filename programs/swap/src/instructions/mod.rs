@@ -1,11 +1,44 @@
+pub mod accept_quote;
+pub use accept_quote::*;
+
+pub mod cancel_offer;
+pub use cancel_offer::*;
+
+pub mod cancel_rfq;
+pub use cancel_rfq::*;
+
+pub mod claim_vested;
+pub use claim_vested::*;
+
+pub mod fill_offer;
+pub use fill_offer::*;
+
 pub mod initialize;
 pub use initialize::*;
 
 pub mod make_offer;
 pub use make_offer::*;
 
+pub mod migrate_offer;
+pub use migrate_offer::*;
+
+pub mod reject_quote;
+pub use reject_quote::*;
+
+pub mod request_quote;
+pub use request_quote::*;
+
 pub mod shared;
 pub use shared::*;
 
+pub mod submit_quote;
+pub use submit_quote::*;
+
 pub mod take_offer;
-pub use take_offer::*;
\ No newline at end of file
+pub use take_offer::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod withdraw_quote;
+pub use withdraw_quote::*;
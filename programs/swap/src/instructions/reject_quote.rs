@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::quote::Quote;
+use crate::state::rfq::Rfq;
+
+use super::empty_and_close_vault;
+
+/// Turns down a quote on an open RFQ. The escrowed token B and the quote
+/// and vault rent go back to the maker, as with `withdraw_quote`, without
+/// waiting for the maker to reclaim them.
+#[derive(Accounts)]
+pub struct RejectQuote<'info> {
+    pub requester: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = requester,
+        has_one = token_mint_b,
+        seeds = [b"rfq", requester.key().as_ref(), rfq.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub rfq: Account<'info, Rfq>,
+
+    /// Only quotes on this RFQ; one left over from an earlier RFQ with the
+    /// same id is the maker's to withdraw.
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = rfq,
+        seeds = [b"quote", rfq.key().as_ref(), rfq.nonce.to_le_bytes().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub quote: Account<'info, Quote>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = quote,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn return_quote_to_maker(
+    context: Context<RejectQuote>,
+) -> Result<()> {
+    let quote_bump = context.accounts.quote.bump;
+    let rfq_key = context.accounts.rfq.key();
+    let rfq_nonce = context.accounts.rfq.nonce.to_le_bytes();
+    let maker_key = context.accounts.maker.key();
+    let seeds: [&[u8]; 5] = [
+        b"quote",
+        rfq_key.as_ref(),
+        &rfq_nonce,
+        maker_key.as_ref(),
+        &[quote_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    empty_and_close_vault(
+        &context.accounts.vault,
+        &context.accounts.maker_token_account_b,
+        &context.accounts.token_mint_b,
+        context.accounts.quote.to_account_info(),
+        context.accounts.maker.to_account_info(),
+        &context.accounts.token_program,
+        signer_seeds,
+    )
+}
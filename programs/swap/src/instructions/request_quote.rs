@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use crate::state::requester_stats::RequesterStats;
+use crate::state::rfq::{Rfq, RFQ_RESERVED_SPACE, RFQ_VERSION};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct RequestQuote<'info> {
+    /// Funds the RFQ account. May be the requester or a sponsoring relayer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub requester: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Rfq::SPACE,
+        seeds = [b"rfq", requester.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RequesterStats::SPACE,
+        seeds = [b"requester", requester.key().as_ref()],
+        bump,
+    )]
+    pub requester_stats: Box<Account<'info, RequesterStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn save_rfq(
+    context: Context<RequestQuote>,
+    id: u64,
+    token_b_wanted_amount: u64,
+) -> Result<()> {
+    let nonce = context
+        .accounts
+        .requester_stats
+        .open_rfq(context.accounts.requester.key(), context.bumps.requester_stats);
+
+    context.accounts.rfq.set_inner(Rfq {
+        id,
+        requester: context.accounts.requester.key(),
+        token_mint_a: context.accounts.token_mint_a.key(),
+        token_mint_b: context.accounts.token_mint_b.key(),
+        token_b_wanted_amount,
+        bump: context.bumps.rfq,
+        version: RFQ_VERSION,
+        nonce,
+        reserved: [0; RFQ_RESERVED_SPACE],
+    });
+    Ok(())
+}
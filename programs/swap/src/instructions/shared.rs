@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface, TransferChecked, transfer_checked};
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface, TransferChecked, transfer_checked, CloseAccount, close_account};
 
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
@@ -19,4 +19,85 @@ pub fn transfer_tokens<'info>(
     let cpi_context = CpiContext::new(token_program.to_account_info(), transfer_accounts_options);
 
     transfer_checked(cpi_context, *amount, mint.decimals)
-}   
\ No newline at end of file
+}   
+
+/// Moves `amount` out of a PDA-owned vault. `signer_seeds` must derive
+/// `authority`, the vault's owner.
+pub fn transfer_from_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let transfer_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        transfer_accounts,
+        signer_seeds,
+    );
+
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+/// Moves everything held by a PDA-owned vault to `to`, then closes the vault
+/// and sends its rent to `rent_destination`. `signer_seeds` must derive
+/// `authority`, the vault's owner.
+pub fn empty_and_close_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    rent_destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer_from_vault(
+        vault,
+        to,
+        vault.amount,
+        mint,
+        authority.clone(),
+        token_program,
+        signer_seeds,
+    )?;
+
+    let close_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: rent_destination,
+        authority,
+    };
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    );
+
+    close_account(cpi_context)
+}
+
+/// Moves whatever the offer holds above its rent, the maker's deposit, back
+/// to `maker`, so that closing the offer only refunds the rent to its
+/// `rent_payer`. Runs after the instruction's last CPI, since the runtime
+/// rejects a CPI once lamports have moved between accounts it wasn't given.
+pub fn return_offer_deposit<'info>(
+    offer: &AccountInfo<'info>,
+    maker: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(offer.data_len());
+    let deposit = offer.lamports().saturating_sub(rent);
+    if deposit > 0 {
+        offer.sub_lamports(deposit)?;
+        maker.add_lamports(deposit)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{TokenInterface, Mint, TokenAccount}, associated_token::AssociatedToken};
+
+use crate::state::quote::{Quote, QUOTE_RESERVED_SPACE, QUOTE_VERSION};
+use crate::state::rfq::Rfq;
+use crate::transfer_tokens;
+
+#[derive(Accounts)]
+pub struct SubmitQuote<'info> {
+    /// Funds the quote and vault accounts. May be the maker or a sponsoring
+    /// relayer.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must still sign to authorize moving token B into the vault.
+    pub maker: Signer<'info>,
+
+    #[account(has_one = token_mint_b)]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_b: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Quote::SPACE,
+        seeds = [b"quote", rfq.key().as_ref(), rfq.nonce.to_le_bytes().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub quote: Account<'info, Quote>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = quote,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn send_quoted_tokens_to_vault(
+    context: &Context<SubmitQuote>,
+) -> Result<()> {
+    transfer_tokens(
+        &context.accounts.maker_token_account_b,
+        &context.accounts.vault,
+        &context.accounts.rfq.token_b_wanted_amount,
+        &context.accounts.token_mint_b,
+        &context.accounts.maker,
+        &context.accounts.token_program,
+    )
+}
+
+pub fn save_quote(
+    context: Context<SubmitQuote>,
+    token_a_wanted_amount: u64,
+) -> Result<()> {
+    context.accounts.quote.set_inner(Quote {
+        rfq: context.accounts.rfq.key(),
+        maker: context.accounts.maker.key(),
+        token_mint_b: context.accounts.token_mint_b.key(),
+        token_a_wanted_amount,
+        bump: context.bumps.quote,
+        version: QUOTE_VERSION,
+        rfq_nonce: context.accounts.rfq.nonce,
+        reserved: [0; QUOTE_RESERVED_SPACE],
+    });
+    Ok(())
+}
@@ -1,22 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, transfer_checked,
-    TokenInterface, TransferChecked, close_account, CloseAccount}};
+    token_interface::{Mint, TokenAccount, TokenInterface}};
 
 
+use crate::error::ErrorCode;
+use crate::events::OfferFilled;
+use crate::state::maker_stats::MakerStats;
 use crate::state::offer::Offer;
+use crate::state::vesting_escrow::{VestingEscrow, VESTING_ESCROW_RESERVED_SPACE, VESTING_ESCROW_VERSION};
 
 
-use super::transfer_tokens;
+use super::{empty_and_close_vault, return_offer_deposit, transfer_tokens};
 
 #[derive(Accounts)]
 pub struct TakeOffer<'info> {
+    /// Funds any token accounts created below. May be the taker or a relayer
+    /// sponsoring the transaction.
     #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Must still sign to authorize sending token B to the maker.
     pub taker: Signer<'info>,
 
+    /// Gets the deposit back.
     #[account(mut)]
     pub maker: SystemAccount<'info>,
-    
+
+    /// Gets the offer and vault rent back.
+    #[account(mut)]
+    pub rent_payer: SystemAccount<'info>,
+
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
 
@@ -25,7 +38,7 @@ pub struct TakeOffer<'info> {
 
     #[account(
         init_if_needed,
-        payer = taker,
+        payer = payer,
         associated_token::mint = token_mint_a,
         associated_token::authority = taker,
         associated_token::token_program = token_program,
@@ -42,7 +55,7 @@ pub struct TakeOffer<'info> {
 
     #[account(
         init_if_needed,
-        payer = taker,
+        payer = payer,
         associated_token::mint = token_mint_b,
         associated_token::authority = maker,
         associated_token::token_program = token_program,
@@ -51,8 +64,9 @@ pub struct TakeOffer<'info> {
 
     #[account(
         mut,
-        close = maker,
-        has_one = maker, 
+        close = rent_payer,
+        has_one = maker,
+        has_one = rent_payer,
         has_one = token_mint_a, 
         has_one = token_mint_b,
         seeds = [b"offer", maker.key().as_ref(), offer.id.to_le_bytes().as_ref()],
@@ -69,22 +83,100 @@ pub struct TakeOffer<'info> {
     )]
     vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// Created here for offers made before open offers were counted.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MakerStats::SPACE,
+        seeds = [b"maker", maker.key().as_ref()],
+        bump,
+    )]
+    pub maker_stats: Box<Account<'info, MakerStats>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Only for vesting offers, which send token A here instead of to
+    /// `taker_token_account_a`. Left out for every other offer.
+    #[account(
+        init,
+        payer = payer,
+        space = VestingEscrow::SPACE,
+        seeds = [b"vesting", offer.key().as_ref(), offer.nonce.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vesting_escrow: Option<Box<Account<'info, VestingEscrow>>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = vesting_escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 }
 
 pub fn send_wanted_tokens_to_maker(
     context: &Context<TakeOffer>,
 ) -> Result<()> {
+    let offer = &context.accounts.offer;
+    let token_a_amount = context.accounts.vault.amount;
+    let token_b_amount = offer.check_fill(token_a_amount, token_a_amount, Clock::get()?.slot)?;
+
     transfer_tokens(
         &context.accounts.taker_token_account_b,
         &context.accounts.maker_token_account_b,
-        &context.accounts.offer.token_b_wanted_amount,
+        &token_b_amount,
         &context.accounts.token_mint_b,
         &context.accounts.taker,
         &context.accounts.token_program,
-    )
+    )?;
+
+    emit!(OfferFilled {
+        offer: offer.key(),
+        maker: offer.maker,
+        taker: context.accounts.taker.key(),
+        token_a_amount,
+        token_b_amount,
+        token_a_remaining: 0,
+        flags: offer.flags,
+        min_fill_amount: offer.min_fill_amount,
+    });
+    Ok(())
+}
+
+/// Opens the vesting escrow for a vesting offer. The schedule starts now and
+/// covers everything left in the vault.
+pub fn save_vesting_escrow(
+    context: &mut Context<TakeOffer>,
+) -> Result<()> {
+    let vests = context.accounts.offer.vests();
+    let escrow = context.accounts.vesting_escrow.is_some();
+    let escrow_vault = context.accounts.vesting_vault.is_some();
+    require!(!vests || (escrow && escrow_vault), ErrorCode::MissingVestingEscrow);
+    require!(vests || !(escrow || escrow_vault), ErrorCode::UnexpectedVestingEscrow);
+    let Some(vesting_escrow) = context.accounts.vesting_escrow.as_mut() else {
+        return Ok(());
+    };
+
+    let offer = &context.accounts.offer;
+    vesting_escrow.set_inner(VestingEscrow {
+        offer: offer.key(),
+        offer_nonce: offer.nonce,
+        beneficiary: context.accounts.taker.key(),
+        token_mint: context.accounts.token_mint_a.key(),
+        total_amount: context.accounts.vault.amount,
+        claimed_amount: 0,
+        start_timestamp: Clock::get()?.unix_timestamp,
+        cliff_seconds: offer.vesting_cliff_seconds,
+        duration_seconds: offer.vesting_duration_seconds,
+        bump: context.bumps.vesting_escrow.unwrap(),
+        version: VESTING_ESCROW_VERSION,
+        reserved: [0; VESTING_ESCROW_RESERVED_SPACE],
+    });
+    Ok(())
 }
 
 pub fn withdraw_and_close_vault(
@@ -100,37 +192,26 @@ pub fn withdraw_and_close_vault(
     ];
     let signer_seeds: &[&[&[u8]]] = &[&seeds];
 
-    let transfer_accounts = TransferChecked {
-        from: context.accounts.vault.to_account_info(),
-        mint: context.accounts.token_mint_a.to_account_info(),
-        to: context.accounts.taker_token_account_a.to_account_info(),
-        authority: context.accounts.offer.to_account_info(),
-    };
-
-    let cpi_context = CpiContext::new_with_signer(
-        context.accounts.token_program.to_account_info(),
-        transfer_accounts,
-        signer_seeds,
-    );
-
-    transfer_checked(
-        cpi_context,
-        context.accounts.vault.amount,
-        context.accounts.token_mint_a.decimals,
-    )?;
+    context.accounts.maker_stats.close_offer(maker_key, context.bumps.maker_stats);
 
-    let close_accounts: CloseAccount = CloseAccount {
-        account: context.accounts.vault.to_account_info(),
-        destination: context.accounts.maker.to_account_info(),
-        authority: context.accounts.offer.to_account_info(),
+    let destination = match &context.accounts.vesting_vault {
+        Some(vesting_vault) => vesting_vault,
+        None => &context.accounts.taker_token_account_a,
     };
 
-    let cpi_context = CpiContext::new_with_signer(
-        context.accounts.token_program.to_account_info(),
-        close_accounts,
+    empty_and_close_vault(
+        &context.accounts.vault,
+        destination,
+        &context.accounts.token_mint_a,
+        context.accounts.offer.to_account_info(),
+        context.accounts.rent_payer.to_account_info(),
+        &context.accounts.token_program,
         signer_seeds,
-    );
+    )?;
 
-    close_account(cpi_context)?;
+    return_offer_deposit(
+        &context.accounts.offer.to_account_info(),
+        &context.accounts.maker.to_account_info(),
+    )?;
     Ok(())
-}      
\ No newline at end of file
+}
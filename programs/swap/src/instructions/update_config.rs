@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::config::Config;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// New limits only apply to offers made afterwards. Open offers keep the
+/// deposit they were made with, and makers above a lowered cap simply can't
+/// open more until they drop below it.
+pub fn update_limits(
+    context: Context<UpdateConfig>,
+    max_open_offers_per_maker: u32,
+    offer_deposit_lamports: u64,
+) -> Result<()> {
+    let config = &mut context.accounts.config;
+    config.max_open_offers_per_maker = max_open_offers_per_maker;
+    config.offer_deposit_lamports = offer_deposit_lamports;
+    Ok(())
+}
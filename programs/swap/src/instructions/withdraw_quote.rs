@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::quote::Quote;
+
+use super::empty_and_close_vault;
+
+/// Refunds a quote that was not accepted. The maker may also use this to
+/// pull a quote while its RFQ is still open, or after it was cancelled.
+#[derive(Accounts)]
+pub struct WithdrawQuote<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        has_one = token_mint_b,
+        seeds = [b"quote", quote.rfq.as_ref(), quote.rfq_nonce.to_le_bytes().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub quote: Account<'info, Quote>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = quote,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn refund_quote(
+    context: Context<WithdrawQuote>,
+) -> Result<()> {
+    let quote_bump = context.accounts.quote.bump;
+    let rfq_key = context.accounts.quote.rfq;
+    let rfq_nonce = context.accounts.quote.rfq_nonce.to_le_bytes();
+    let maker_key = context.accounts.maker.key();
+    let seeds: [&[u8]; 5] = [
+        b"quote",
+        rfq_key.as_ref(),
+        &rfq_nonce,
+        maker_key.as_ref(),
+        &[quote_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[&seeds];
+
+    empty_and_close_vault(
+        &context.accounts.vault,
+        &context.accounts.maker_token_account_b,
+        &context.accounts.token_mint_b,
+        context.accounts.quote.to_account_info(),
+        context.accounts.maker.to_account_info(),
+        &context.accounts.token_program,
+        signer_seeds,
+    )
+}
@@ -1,5 +1,14 @@
+// `make_offer`'s arguments are repeated in the generated `cpi` and client
+// modules, which an attribute on the handler doesn't reach.
+#![allow(clippy::too_many_arguments)]
+// The IDL instructions `#[program]` generates at the crate root still call
+// the deprecated `AccountInfo::realloc`, which an attribute on the module
+// doesn't reach.
+#![allow(deprecated)]
+
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -15,22 +24,121 @@ declare_id!("EST6eEpbZyQDa1oNzz6rRHuY3kmjbMEd2jf7c4FqbvFv");
 pub mod swap {
     use super::*;
 
+    pub fn initialize(
+        context: Context<Initialize>,
+        max_open_offers_per_maker: u32,
+        offer_deposit_lamports: u64,
+    ) -> Result<()> {
+        instructions::initialize::save_config(context, max_open_offers_per_maker, offer_deposit_lamports)
+    }
+
+    pub fn update_config(
+        context: Context<UpdateConfig>,
+        max_open_offers_per_maker: u32,
+        offer_deposit_lamports: u64,
+    ) -> Result<()> {
+        instructions::update_config::update_limits(context, max_open_offers_per_maker, offer_deposit_lamports)
+    }
+
     pub fn make_offer(
         context: Context<MakeOffer>,
         id: u64,
         token_a_offered_amount: u64,
         token_b_wanted_amount: u64,
+        flags: u8,
+        min_fill_amount: u64,
+        vesting_cliff_seconds: i64,
+        vesting_duration_seconds: i64,
     ) -> Result<()> {
         instructions::make_offer::send_offered_tokens_to_vault(&context, token_a_offered_amount)?;
-        instructions::make_offer::save_offer(context, id, token_b_wanted_amount)
+        instructions::make_offer::lock_offer_deposit(&context)?;
+        instructions::make_offer::save_offer(
+            context,
+            id,
+            token_a_offered_amount,
+            token_b_wanted_amount,
+            flags,
+            min_fill_amount,
+            vesting_cliff_seconds,
+            vesting_duration_seconds,
+        )
     }
 
     
     pub fn take_offer(
-        context: Context<TakeOffer>,
+        mut context: Context<TakeOffer>,
     ) -> Result<()> {
         instructions::take_offer::send_wanted_tokens_to_maker(&context)?;
+        instructions::take_offer::save_vesting_escrow(&mut context)?;
         instructions::take_offer::withdraw_and_close_vault(context)
     }
+
+    pub fn cancel_offer(
+        context: Context<CancelOffer>,
+    ) -> Result<()> {
+        instructions::cancel_offer::refund_and_close_vault(context)
+    }
+
+    pub fn fill_offer(
+        context: Context<FillOffer>,
+        token_a_amount: u64,
+    ) -> Result<()> {
+        instructions::fill_offer::fill_from_vault(context, token_a_amount)
+    }
+
+    pub fn claim_vested(
+        context: Context<ClaimVested>,
+    ) -> Result<()> {
+        instructions::claim_vested::release_vested_tokens(context)
+    }
+
+    pub fn migrate_offer(
+        context: Context<MigrateOffer>,
+    ) -> Result<()> {
+        instructions::migrate_offer::grow_offer_account(&context)?;
+        instructions::migrate_offer::rewrite_offer(context)
+    }
+
+    pub fn request_quote(
+        context: Context<RequestQuote>,
+        id: u64,
+        token_b_wanted_amount: u64,
+    ) -> Result<()> {
+        instructions::request_quote::save_rfq(context, id, token_b_wanted_amount)
+    }
+
+    pub fn submit_quote(
+        context: Context<SubmitQuote>,
+        token_a_wanted_amount: u64,
+    ) -> Result<()> {
+        instructions::submit_quote::send_quoted_tokens_to_vault(&context)?;
+        instructions::submit_quote::save_quote(context, token_a_wanted_amount)
+    }
+
+    pub fn accept_quote(
+        context: Context<AcceptQuote>,
+        max_token_a_amount: u64,
+    ) -> Result<()> {
+        instructions::accept_quote::send_quoted_price_to_maker(&context, max_token_a_amount)?;
+        instructions::accept_quote::release_quoted_tokens(context)
+    }
+
+    pub fn reject_quote(
+        context: Context<RejectQuote>,
+    ) -> Result<()> {
+        instructions::reject_quote::return_quote_to_maker(context)
+    }
+
+    pub fn withdraw_quote(
+        context: Context<WithdrawQuote>,
+    ) -> Result<()> {
+        instructions::withdraw_quote::refund_quote(context)
+    }
+
+    pub fn cancel_rfq(
+        _context: Context<CancelRfq>,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
 
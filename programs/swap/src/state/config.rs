@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+
+/// Layout version written by `initialize`.
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`Config`] for settings added in later versions.
+pub const CONFIG_RESERVED_SPACE: usize = 64;
+
+/// Program-wide settings, stored in the single `[b"config"]` PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// May change the settings below with `update_config`.
+    pub admin: Pubkey,
+    /// Most offers a maker may have open at once. Zero disables the cap.
+    pub max_open_offers_per_maker: u32,
+    /// Lamports locked in every new offer on top of its rent. They go back
+    /// to the maker with the rent when the offer closes.
+    pub offer_deposit_lamports: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; CONFIG_RESERVED_SPACE],
+}
+
+impl Config {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + Config::INIT_SPACE;
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::ErrorCode;
+
+/// Layout version written when a maker's stats are first created.
+pub const MAKER_STATS_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`MakerStats`] for fields added in later versions.
+pub const MAKER_STATS_RESERVED_SPACE: usize = 24;
+
+/// Per-maker bookkeeping, stored in the `[b"maker", maker]` PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct MakerStats {
+    pub maker: Pubkey,
+    pub open_offers: u32,
+    pub bump: u8,
+    pub version: u8,
+    /// Offers ever made, which also numbers them: each offer's `nonce` is
+    /// the count including itself, so nonces start at one.
+    pub offers_made: u64,
+    pub reserved: [u8; MAKER_STATS_RESERVED_SPACE],
+}
+
+impl MakerStats {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + MakerStats::INIT_SPACE;
+
+    /// Counts a new open offer, enforcing `max_open_offers` unless it is zero,
+    /// and returns the offer's nonce.
+    pub fn open_offer(&mut self, maker: Pubkey, bump: u8, max_open_offers: u32) -> Result<u64> {
+        self.fill_header(maker, bump);
+        require!(
+            max_open_offers == 0 || self.open_offers < max_open_offers,
+            ErrorCode::TooManyOpenOffers
+        );
+        self.open_offers += 1;
+        self.offers_made += 1;
+        Ok(self.offers_made)
+    }
+
+    /// Counts a closed offer. Saturates so that offers made before stats
+    /// were tracked can still be closed.
+    pub fn close_offer(&mut self, maker: Pubkey, bump: u8) {
+        self.fill_header(maker, bump);
+        self.open_offers = self.open_offers.saturating_sub(1);
+    }
+
+    /// Stats accounts are created on demand with `init_if_needed`, so the
+    /// first instruction to touch one writes its header.
+    fn fill_header(&mut self, maker: Pubkey, bump: u8) {
+        if self.version == 0 {
+            self.maker = maker;
+            self.bump = bump;
+            self.version = MAKER_STATS_VERSION;
+        }
+    }
+}
@@ -1,2 +1,20 @@
+pub mod config;
+pub use config::*;
+
+pub mod maker_stats;
+pub use maker_stats::*;
+
 pub mod offer;
-pub use offer::*;
\ No newline at end of file
+pub use offer::*;
+
+pub mod quote;
+pub use quote::*;
+
+pub mod requester_stats;
+pub use requester_stats::*;
+
+pub mod rfq;
+pub use rfq::*;
+
+pub mod vesting_escrow;
+pub use vesting_escrow::*;
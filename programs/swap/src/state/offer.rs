@@ -1,9 +1,157 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::ErrorCode;
+
+/// Layout version written by `make_offer` and `migrate_offer`.
+///
+/// Version 2 carved `flags`, `min_fill_amount` and `created_slot` out of the
+/// reserved space. Version 1 offers read those as zero, which means no flags
+/// and no minimum, so they need no migration.
+///
+/// Version 3 carved the vesting schedule and `nonce` out of the reserved
+/// space. Older offers read the schedule as zero, which means token A is
+/// delivered on take, and the nonce as zero, which no newer offer uses.
+///
+/// Version 4 appended `rent_payer`, which didn't fit in the reserved space,
+/// so the account grew by 32 bytes. Every older offer has to go through
+/// `migrate_offer`, which records the maker as the rent payer: before
+/// version 4 all rent went back to the maker on close.
+pub const OFFER_VERSION: u8 = 4;
+
+/// Spare bytes at the end of [`Offer`]. New fields are carved out of this
+/// space so existing accounts keep deserializing after an upgrade.
+pub const OFFER_RESERVED_SPACE: usize = 23;
+
+/// The offer can only be filled in full, by `take_offer` or a `fill_offer`
+/// for everything left in the vault.
+pub const OFFER_FLAG_ALL_OR_NONE: u8 = 1 << 0;
+
+/// The offer can't be filled in the slot it was made, only from the next
+/// slot on.
+///
+/// This is narrower than post-only on an order book. The program keeps no
+/// book, so it can't tell whether an offer would cross another; all it
+/// guarantees is that the offer is visible on chain for at least one slot
+/// before anyone can take it, so the maker can't make and take it in the
+/// same transaction or bundle.
+pub const OFFER_FLAG_POST_ONLY: u8 = 1 << 1;
+
+pub const OFFER_FLAGS_MASK: u8 = OFFER_FLAG_ALL_OR_NONE | OFFER_FLAG_POST_ONLY;
+
 #[account]
 #[derive(InitSpace)]
 
 pub struct Offer {
+    pub id: u64,
+    pub maker: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    /// Token B still wanted for whatever token A is left in the vault.
+    /// Partial fills reduce it in step with the vault balance.
+    pub token_b_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub flags: u8,
+    /// Smallest token A amount a partial fill may take. A fill of everything
+    /// left in the vault is always allowed.
+    pub min_fill_amount: u64,
+    pub created_slot: u64,
+    /// Seconds after the take before any token A can be claimed.
+    pub vesting_cliff_seconds: i64,
+    /// Seconds after the take until all token A can be claimed. Zero means
+    /// the offer does not vest and token A goes straight to the taker.
+    pub vesting_duration_seconds: i64,
+    /// Unique among the maker's offers, even those that reuse an id. Keys
+    /// the vesting escrow, which can outlive the offer.
+    pub nonce: u64,
+    /// Funded the offer and vault accounts, and gets their rent back when
+    /// the offer closes. The deposit still goes back to the maker.
+    pub rent_payer: Pubkey,
+    pub reserved: [u8; OFFER_RESERVED_SPACE],
+}
+
+impl Offer {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE;
+
+    /// Size of version 1 to 3 offers, which end at `reserved` without a
+    /// `rent_payer` before it.
+    pub const V3_SPACE: usize = Offer::SPACE - std::mem::size_of::<Pubkey>();
+
+    pub fn is_all_or_none(&self) -> bool {
+        self.flags & OFFER_FLAG_ALL_OR_NONE != 0
+    }
+
+    /// See [`OFFER_FLAG_POST_ONLY`]: the offer only becomes fillable in the
+    /// slot after `created_slot`.
+    pub fn is_post_only(&self) -> bool {
+        self.flags & OFFER_FLAG_POST_ONLY != 0
+    }
+
+    /// Checks the terms of a new offer. A zero on either side would let one
+    /// party get something for nothing.
+    pub fn check_terms(
+        token_a_offered_amount: u64,
+        token_b_wanted_amount: u64,
+        flags: u8,
+        min_fill_amount: u64,
+    ) -> Result<()> {
+        require!(
+            token_a_offered_amount > 0 && token_b_wanted_amount > 0,
+            ErrorCode::ZeroOfferAmount
+        );
+        require!(flags & !OFFER_FLAGS_MASK == 0, ErrorCode::InvalidOfferFlags);
+        require!(min_fill_amount <= token_a_offered_amount, ErrorCode::MinFillExceedsOffer);
+        Ok(())
+    }
+
+    /// Whether `take_offer` sends token A to a vesting escrow rather than
+    /// to the taker.
+    pub fn vests(&self) -> bool {
+        self.vesting_duration_seconds > 0
+    }
+
+    /// Checks a fill of `token_a_amount` against the offer's flags and
+    /// returns the token B the taker owes for it. `token_a_remaining` is the
+    /// vault balance. The price is rounded up so that a series of partial
+    /// fills never pays the maker less than the offer asked for, and a
+    /// partial fill whose rounded price is everything still wanted is
+    /// rejected, since the rest of the vault would then go for nothing.
+    pub fn check_fill(
+        &self,
+        token_a_amount: u64,
+        token_a_remaining: u64,
+        current_slot: u64,
+    ) -> Result<u64> {
+        require!(token_a_amount > 0, ErrorCode::ZeroFill);
+        require!(token_a_amount <= token_a_remaining, ErrorCode::FillExceedsOffer);
+        require!(
+            !self.is_post_only() || current_slot > self.created_slot,
+            ErrorCode::PostOnlyOfferNotResting
+        );
+
+        if token_a_amount == token_a_remaining {
+            return Ok(self.token_b_wanted_amount);
+        }
+
+        require!(!self.is_all_or_none(), ErrorCode::AllOrNoneOffer);
+        require!(token_a_amount >= self.min_fill_amount, ErrorCode::FillBelowMinimum);
+
+        let numerator = token_a_amount as u128 * self.token_b_wanted_amount as u128;
+        let token_b_amount = numerator.div_ceil(token_a_remaining as u128);
+        require!(
+            token_b_amount < self.token_b_wanted_amount as u128,
+            ErrorCode::FillLeavesNothingWanted
+        );
+        u64::try_from(token_b_amount).map_err(|_| error!(ErrorCode::FillExceedsOffer))
+    }
+}
+
+/// Unversioned layout used by offers created before [`OFFER_VERSION`] 1.
+/// Only read by `migrate_offer`; every new field of [`Offer`] is appended
+/// after these, so the leading bytes of both layouts are identical.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, PartialEq)]
+pub struct OfferV0 {
     pub id: u64,
     pub maker: Pubkey,
     pub token_mint_a: Pubkey,
@@ -12,3 +160,28 @@ pub struct Offer {
     pub bump: u8,
 }
 
+impl OfferV0 {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + OfferV0::INIT_SPACE;
+}
+
+impl From<OfferV0> for Offer {
+    fn from(legacy: OfferV0) -> Self {
+        Offer {
+            id: legacy.id,
+            maker: legacy.maker,
+            token_mint_a: legacy.token_mint_a,
+            token_mint_b: legacy.token_mint_b,
+            token_b_wanted_amount: legacy.token_b_wanted_amount,
+            bump: legacy.bump,
+            version: OFFER_VERSION,
+            flags: 0,
+            min_fill_amount: 0,
+            created_slot: 0,
+            vesting_cliff_seconds: 0,
+            vesting_duration_seconds: 0,
+            nonce: 0,
+            rent_payer: legacy.maker,
+            reserved: [0; OFFER_RESERVED_SPACE],
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+
+/// Layout version written by `submit_quote`.
+pub const QUOTE_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`Quote`] for fields added in later versions.
+pub const QUOTE_RESERVED_SPACE: usize = 56;
+
+/// A maker's answer to an [`Rfq`](super::Rfq). The requested token B is
+/// escrowed in the quote's vault until the quote is accepted or withdrawn.
+///
+/// Stored in the `[b"quote", rfq, rfq.nonce, maker]` PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct Quote {
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    /// The [`Rfq::nonce`](super::Rfq::nonce) of the request this answers.
+    pub rfq_nonce: u64,
+    pub reserved: [u8; QUOTE_RESERVED_SPACE],
+}
+
+impl Quote {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + Quote::INIT_SPACE;
+}
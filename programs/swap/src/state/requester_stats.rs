@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+
+/// Layout version written when a requester's stats are first created.
+pub const REQUESTER_STATS_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`RequesterStats`] for fields added in later
+/// versions.
+pub const REQUESTER_STATS_RESERVED_SPACE: usize = 32;
+
+/// Per-requester bookkeeping, stored in the `[b"requester", requester]` PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct RequesterStats {
+    pub requester: Pubkey,
+    /// RFQs ever requested, which also numbers them: each RFQ's `nonce` is
+    /// the count including itself, so nonces start at one.
+    pub rfqs_requested: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; REQUESTER_STATS_RESERVED_SPACE],
+}
+
+impl RequesterStats {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + RequesterStats::INIT_SPACE;
+
+    /// Counts a new RFQ and returns its nonce. Stats accounts are created on
+    /// demand with `init_if_needed`, so the first RFQ also writes the header.
+    pub fn open_rfq(&mut self, requester: Pubkey, bump: u8) -> u64 {
+        if self.version == 0 {
+            self.requester = requester;
+            self.bump = bump;
+            self.version = REQUESTER_STATS_VERSION;
+        }
+        self.rfqs_requested += 1;
+        self.rfqs_requested
+    }
+}
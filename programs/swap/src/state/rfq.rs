@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+
+/// Layout version written by `request_quote`.
+pub const RFQ_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`Rfq`] for fields added in later versions.
+pub const RFQ_RESERVED_SPACE: usize = 56;
+
+/// A request for quote: the requester wants `token_b_wanted_amount` of
+/// token B and will pay in token A at whatever price a maker quotes.
+///
+/// The requester picks the id, so a closed RFQ's address comes back when the
+/// id is reused. `nonce` tells the two apart: quotes are stored under it, so
+/// quotes left over from the earlier RFQ can't be accepted on the new one.
+#[account]
+#[derive(InitSpace)]
+pub struct Rfq {
+    pub id: u64,
+    pub requester: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_b_wanted_amount: u64,
+    pub bump: u8,
+    pub version: u8,
+    /// Unique among the requester's RFQs, even those that reuse an id.
+    pub nonce: u64,
+    pub reserved: [u8; RFQ_RESERVED_SPACE],
+}
+
+impl Rfq {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + Rfq::INIT_SPACE;
+}
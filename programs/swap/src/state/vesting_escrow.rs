@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ANCHOR_DISCRIMINATOR;
+
+/// Layout version written by `take_offer`.
+pub const VESTING_ESCROW_VERSION: u8 = 1;
+
+/// Spare bytes at the end of [`VestingEscrow`] for fields added in later
+/// versions.
+pub const VESTING_ESCROW_RESERVED_SPACE: usize = 32;
+
+/// Token A bought from a vesting offer, held in the escrow's vault until
+/// the beneficiary claims it with `claim_vested`.
+///
+/// Stored in the `[b"vesting", offer, offer.nonce]` PDA, so an offer id the
+/// maker reuses gets a fresh escrow even while the last one is unclaimed.
+/// The slot the offer was made in wouldn't do: an id can be taken and made
+/// again within one slot.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingEscrow {
+    pub offer: Pubkey,
+    pub offer_nonce: u64,
+    /// The taker of the offer.
+    pub beneficiary: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    /// Unix timestamp of the take, when the schedule starts.
+    pub start_timestamp: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; VESTING_ESCROW_RESERVED_SPACE],
+}
+
+impl VestingEscrow {
+    pub const SPACE: usize = ANCHOR_DISCRIMINATOR + VestingEscrow::INIT_SPACE;
+
+    /// Amount unlocked at `now`: nothing before the cliff, then a linear
+    /// share of the total that reaches all of it after `duration_seconds`.
+    /// Rounds down, so the last claim picks up any remainder.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_timestamp);
+        if elapsed < self.cliff_seconds {
+            return 0;
+        }
+        if elapsed >= self.duration_seconds {
+            return self.total_amount;
+        }
+        let vested =
+            self.total_amount as u128 * elapsed as u128 / self.duration_seconds as u128;
+        vested as u64
+    }
+
+    /// Vested tokens not claimed yet.
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now).saturating_sub(self.claimed_amount)
+    }
+}
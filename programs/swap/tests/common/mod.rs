@@ -0,0 +1,167 @@
+//! A market on an in-process SVM: the swap program with its config, two
+//! mints, and funded maker (Alice) and taker (Bob) wallets.
+
+#![allow(dead_code)]
+
+use local_svm::{FailedTransaction, LocalSvm, TransactionMetadata, UpgradeableProgram};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::TransactionError;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda::{find_associated_token_address, find_offer_address};
+use swap_client::state::{Offer, SwapAccount};
+
+pub const MAX_OPEN_OFFERS_PER_MAKER: u32 = 10;
+pub const OFFER_DEPOSIT_LAMPORTS: u64 = LAMPORTS_PER_SOL / 100;
+pub const STARTING_BALANCE: u64 = 1_000_000_000;
+
+pub struct Market {
+    pub svm: LocalSvm,
+    pub token_program: Pubkey,
+    pub admin: Keypair,
+    pub mint_authority: Keypair,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub alice: Keypair,
+    pub bob: Keypair,
+}
+
+impl Market {
+    /// Alice holds token A and Bob holds token B, as in `tests/swap.ts`.
+    pub fn new(token_program: Pubkey) -> Self {
+        let admin = Keypair::new();
+        let mut svm = LocalSvm::new(&[UpgradeableProgram {
+            name: "swap",
+            program_id: swap::ID,
+            processor: swap::entry,
+            upgrade_authority: Some(admin.pubkey()),
+        }]);
+
+        let payer = svm.payer().insecure_clone();
+        svm.send_transaction(
+            &[instruction::initialize(
+                &payer.pubkey(),
+                &admin.pubkey(),
+                MAX_OPEN_OFFERS_PER_MAKER,
+                OFFER_DEPOSIT_LAMPORTS,
+            )],
+            &[&payer, &admin],
+        )
+        .unwrap();
+
+        let mint_authority = Keypair::new();
+        let token_mint_a = svm.create_mint(&token_program, &mint_authority.pubkey(), 6);
+        let token_mint_b = svm.create_mint(&token_program, &mint_authority.pubkey(), 6);
+
+        let mut market = Self {
+            svm,
+            token_program,
+            admin,
+            mint_authority,
+            token_mint_a,
+            token_mint_b,
+            alice: Keypair::new(),
+            bob: Keypair::new(),
+        };
+        let alice = market.alice.pubkey();
+        let bob = market.bob.pubkey();
+        market.fund(&alice, &market.token_mint_a.clone(), STARTING_BALANCE);
+        market.fund(&bob, &market.token_mint_b.clone(), STARTING_BALANCE);
+        market
+    }
+
+    /// Gives `owner` SOL for fees and deposits and `amount` of `mint`.
+    pub fn fund(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) {
+        self.svm.airdrop(owner, 10 * LAMPORTS_PER_SOL);
+        let account = self
+            .svm
+            .create_associated_token_account(owner, mint, &self.token_program);
+        if amount > 0 {
+            let authority = self.mint_authority.insecure_clone();
+            self.svm
+                .mint_to(&self.token_program, mint, &authority, &account, amount);
+        }
+    }
+
+    pub fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let account = find_associated_token_address(owner, mint, &self.token_program);
+        self.svm.token_balance(&account).unwrap_or(0)
+    }
+
+    pub fn vault_balance(&self, maker: &Pubkey, id: u64) -> Option<u64> {
+        let offer = find_offer_address(maker, id).0;
+        let vault = find_associated_token_address(&offer, &self.token_mint_a, &self.token_program);
+        self.svm.token_balance(&vault)
+    }
+
+    pub fn offer(&self, maker: &Pubkey, id: u64) -> Option<Offer> {
+        let account = self.svm.get_account(&find_offer_address(maker, id).0)?;
+        Some(Offer::decode(&account.data).unwrap())
+    }
+
+    pub fn make_offer_ix(&self, maker: &Keypair, args: &MakeOfferArgs) -> Instruction {
+        instruction::make_offer(
+            &maker.pubkey(),
+            &maker.pubkey(),
+            &self.token_mint_a,
+            &self.token_mint_b,
+            &self.token_program,
+            args,
+        )
+    }
+
+    /// Alice offers `token_a_offered_amount` of A for `token_b_wanted_amount`
+    /// of B, paying for the accounts herself.
+    pub fn make_offer(
+        &mut self,
+        id: u64,
+        token_a_offered_amount: u64,
+        token_b_wanted_amount: u64,
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        let alice = self.alice.insecure_clone();
+        let instruction = self.make_offer_ix(
+            &alice,
+            &MakeOfferArgs {
+                id,
+                token_a_offered_amount,
+                token_b_wanted_amount,
+                ..MakeOfferArgs::default()
+            },
+        );
+        self.svm.send_transaction(&[instruction], &[&alice])
+    }
+
+    pub fn take_offer_ix(&self, taker: &Keypair, offer: &Offer) -> Instruction {
+        instruction::take_offer(&taker.pubkey(), &taker.pubkey(), offer, &self.token_program)
+    }
+
+    /// Bob takes Alice's offer `id`, paying for his own accounts.
+    pub fn take_offer(&mut self, id: u64) -> Result<TransactionMetadata, FailedTransaction> {
+        let offer = self.offer(&self.alice.pubkey(), id).expect("offer exists");
+        let bob = self.bob.insecure_clone();
+        let instruction = self.take_offer_ix(&bob, &offer);
+        self.svm.send_transaction(&[instruction], &[&bob])
+    }
+}
+
+/// The error of the first failing instruction.
+pub fn instruction_error(
+    result: Result<TransactionMetadata, FailedTransaction>,
+) -> InstructionError {
+    match result {
+        Ok(_) => panic!("transaction succeeded"),
+        Err(FailedTransaction {
+            error: TransactionError::InstructionError(_, error),
+            ..
+        }) => error,
+        Err(failure) => panic!("{failure}"),
+    }
+}
+
+/// Anchor and `swap` errors surface as custom program errors.
+pub fn custom(code: impl Into<u32>) -> InstructionError {
+    InstructionError::Custom(code.into())
+}
@@ -0,0 +1,672 @@
+//! Stateful fuzzing of the offer lifecycle. Random sequences of
+//! `make_offer`, `take_offer`, `fill_offer` and `cancel_offer` run against
+//! the program in an in-process SVM, checked after every step against a
+//! model of what each offer should still hold.
+//!
+//! Raise `PROPTEST_CASES` for a longer run; failing sequences are shrunk and
+//! saved to `fuzz_offers.proptest-regressions`.
+
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+
+use common::Market;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda::{
+    find_associated_token_address, find_maker_stats_address, find_offer_address,
+    find_vesting_escrow_address,
+};
+use swap_client::state::{MakerStats, Offer, SwapAccount, VestingEscrow};
+use swap_client::TOKEN_2022_PROGRAM_ID;
+
+const ACTORS: usize = 3;
+const IDS: u64 = 3;
+const STARTING_BALANCE: u64 = 1_000_000;
+const MAX_AMOUNT: u64 = 5_000;
+
+#[derive(Debug, Clone)]
+enum Step {
+    Make {
+        maker: usize,
+        id: u64,
+        token_a_offered_amount: u64,
+        token_b_wanted_amount: u64,
+        flags: u8,
+        min_fill_amount: u64,
+        vesting_cliff_seconds: i64,
+        vesting_duration_seconds: i64,
+    },
+    Take {
+        taker: usize,
+        maker: usize,
+        id: u64,
+    },
+    /// Fills `percent` of what the model says is left; above 100 asks for
+    /// more than the vault holds.
+    Fill {
+        taker: usize,
+        maker: usize,
+        id: u64,
+        percent: u64,
+    },
+    /// `caller` cancels the offer, which only its maker may do.
+    Cancel {
+        caller: usize,
+        maker: usize,
+        id: u64,
+    },
+    /// Lets post-only offers rest.
+    NextSlot,
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let actor = 0..ACTORS;
+    let id = 0..IDS;
+    let amount = 0..=MAX_AMOUNT;
+    prop_oneof![
+        3 => (
+            actor.clone(),
+            id.clone(),
+            amount.clone(),
+            // Sometimes only a few units of B for the lot, where rounding
+            // partial fills up matters most.
+            prop_oneof![3 => amount.clone(), 1 => 0..=3u64],
+            // Mostly plain offers. The undefined bits must be rejected.
+            prop_oneof![3 => Just(0u8), 1 => 0..8u8],
+            // Mostly small minimums, sometimes above the offered amount.
+            prop_oneof![3 => 0..=10u64, 1 => amount.clone()],
+            // Mostly delivered on take, often vesting, sometimes with a
+            // negative cliff or a cliff past the end, which must be rejected.
+            prop_oneof![
+                2 => Just((0i64, 0i64)),
+                2 => (0..=50i64, 1..=50i64).prop_map(|(cliff, rest)| (cliff, cliff + rest)),
+                1 => (-10..=100i64, -10..=100i64),
+            ],
+        )
+            .prop_map(
+                |(
+                    maker,
+                    id,
+                    token_a_offered_amount,
+                    token_b_wanted_amount,
+                    flags,
+                    min_fill_amount,
+                    (vesting_cliff_seconds, vesting_duration_seconds),
+                )| {
+                    Step::Make {
+                        maker,
+                        id,
+                        token_a_offered_amount,
+                        token_b_wanted_amount,
+                        flags,
+                        min_fill_amount,
+                        vesting_cliff_seconds,
+                        vesting_duration_seconds,
+                    }
+                },
+            ),
+        2 => (actor.clone(), actor.clone(), id.clone())
+            .prop_map(|(taker, maker, id)| Step::Take { taker, maker, id }),
+        3 => (actor.clone(), actor.clone(), id.clone(), 0..=110u64)
+            .prop_map(|(taker, maker, id, percent)| Step::Fill {
+                taker,
+                maker,
+                id,
+                percent,
+            }),
+        1 => (
+            // Mostly the maker, sometimes someone else.
+            prop_oneof![3 => Just(None), 1 => actor.clone().prop_map(Some)],
+            actor,
+            id,
+        )
+            .prop_map(|(caller, maker, id)| Step::Cancel {
+                caller: caller.unwrap_or(maker),
+                maker,
+                id,
+            }),
+        1 => Just(Step::NextSlot),
+    ]
+}
+
+/// What an open offer should hold, tracked from the requests that
+/// succeeded rather than from the chain.
+#[derive(Debug, Clone)]
+struct OpenOffer {
+    token_a_remaining: u64,
+    token_b_wanted_amount: u64,
+    /// Token B the maker was promised when the offer was made.
+    token_b_total: u64,
+    token_b_paid: u64,
+    /// Token A goes to a vesting escrow on take, and fills are refused.
+    vests: bool,
+}
+
+struct Harness {
+    market: Market,
+    actors: Vec<Keypair>,
+    open: HashMap<(usize, u64), OpenOffer>,
+    /// Last on-chain state of every offer that was closed, to replay
+    /// against.
+    closed: HashMap<(usize, u64), Offer>,
+    /// Vaults of the vesting escrows opened by takes, which hold token A
+    /// after its offer closed.
+    escrow_vaults: Vec<Pubkey>,
+    supply_a: u64,
+    supply_b: u64,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+        let actors = vec![
+            market.alice.insecure_clone(),
+            market.bob.insecure_clone(),
+            Keypair::new(),
+        ];
+        let (mint_a, mint_b) = (market.token_mint_a, market.token_mint_b);
+        for actor in &actors {
+            for mint in [mint_a, mint_b] {
+                let balance = market.balance(&actor.pubkey(), &mint);
+                market.fund(
+                    &actor.pubkey(),
+                    &mint,
+                    STARTING_BALANCE - balance.min(STARTING_BALANCE),
+                );
+            }
+        }
+        let supply_a = market.svm.mint_supply(&mint_a).unwrap();
+        let supply_b = market.svm.mint_supply(&mint_b).unwrap();
+        Self {
+            market,
+            actors,
+            open: HashMap::new(),
+            closed: HashMap::new(),
+            escrow_vaults: Vec::new(),
+            supply_a,
+            supply_b,
+        }
+    }
+
+    fn offer_address(&self, maker: usize, id: u64) -> Pubkey {
+        find_offer_address(&self.actors[maker].pubkey(), id).0
+    }
+
+    fn vault_address(&self, maker: usize, id: u64) -> Pubkey {
+        find_associated_token_address(
+            &self.offer_address(maker, id),
+            &self.market.token_mint_a,
+            &self.market.token_program,
+        )
+    }
+
+    fn on_chain_offer(&self, maker: usize, id: u64) -> Option<Offer> {
+        let account = self
+            .market
+            .svm
+            .get_account(&self.offer_address(maker, id))?;
+        Some(Offer::decode(&account.data).unwrap())
+    }
+
+    /// Every token account the steps can touch, with its balance.
+    fn ledger(&self) -> BTreeMap<Pubkey, u64> {
+        let mut ledger = BTreeMap::new();
+        for actor in &self.actors {
+            for mint in [self.market.token_mint_a, self.market.token_mint_b] {
+                let address = find_associated_token_address(
+                    &actor.pubkey(),
+                    &mint,
+                    &self.market.token_program,
+                );
+                ledger.insert(
+                    address,
+                    self.market.svm.token_balance(&address).unwrap_or(0),
+                );
+            }
+        }
+        for maker in 0..ACTORS {
+            for id in 0..IDS {
+                let vault = self.vault_address(maker, id);
+                if let Some(balance) = self.market.svm.token_balance(&vault) {
+                    ledger.insert(vault, balance);
+                }
+            }
+        }
+        for vault in &self.escrow_vaults {
+            ledger.insert(*vault, self.market.svm.token_balance(vault).unwrap_or(0));
+        }
+        ledger
+    }
+
+    fn token_balance(&self, owner: usize, mint: &Pubkey) -> u64 {
+        self.market.balance(&self.actors[owner].pubkey(), mint)
+    }
+
+    /// The offer to name in a take or fill: the live one, the last state of
+    /// a closed one, or one that never existed.
+    fn target_offer(&self, maker: usize, id: u64) -> Offer {
+        if let Some(offer) = self.on_chain_offer(maker, id) {
+            return offer;
+        }
+        if let Some(offer) = self.closed.get(&(maker, id)) {
+            return offer.clone();
+        }
+        Offer {
+            id,
+            maker: self.actors[maker].pubkey(),
+            token_mint_a: self.market.token_mint_a,
+            token_mint_b: self.market.token_mint_b,
+            token_b_wanted_amount: 1,
+            bump: 0,
+            version: swap_client::state::OFFER_VERSION,
+            flags: 0,
+            min_fill_amount: 0,
+            created_slot: 0,
+            vesting_cliff_seconds: 0,
+            vesting_duration_seconds: 0,
+            nonce: 0,
+            rent_payer: self.actors[maker].pubkey(),
+        }
+    }
+
+    fn run(&mut self, step: &Step) {
+        let before = self.ledger();
+        let succeeded = match *step {
+            Step::Make {
+                maker,
+                id,
+                token_a_offered_amount,
+                token_b_wanted_amount,
+                flags,
+                min_fill_amount,
+                vesting_cliff_seconds,
+                vesting_duration_seconds,
+            } => self.make(
+                maker,
+                id,
+                token_a_offered_amount,
+                token_b_wanted_amount,
+                flags,
+                min_fill_amount,
+                (vesting_cliff_seconds, vesting_duration_seconds),
+            ),
+            Step::Take { taker, maker, id } => self.take(taker, maker, id),
+            Step::Fill {
+                taker,
+                maker,
+                id,
+                percent,
+            } => {
+                let remaining = self
+                    .open
+                    .get(&(maker, id))
+                    .map_or(1, |open| open.token_a_remaining);
+                self.fill(taker, maker, id, remaining * percent / 100)
+            }
+            Step::Cancel { caller, maker, id } => self.cancel(caller, maker, id),
+            Step::NextSlot => {
+                let slot = self.market.svm.clock().slot;
+                self.market.svm.warp_to_slot(slot + 1);
+                false
+            }
+        };
+        if !succeeded {
+            assert_eq!(self.ledger(), before, "a failed step moved tokens");
+        }
+        self.check_invariants();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn make(
+        &mut self,
+        maker: usize,
+        id: u64,
+        offered: u64,
+        wanted: u64,
+        flags: u8,
+        min_fill: u64,
+        (cliff, duration): (i64, i64),
+    ) -> bool {
+        let maker_keypair = self.actors[maker].insecure_clone();
+        let instruction = self.market.make_offer_ix(
+            &maker_keypair,
+            &MakeOfferArgs {
+                id,
+                token_a_offered_amount: offered,
+                token_b_wanted_amount: wanted,
+                flags,
+                min_fill_amount: min_fill,
+                vesting_cliff_seconds: cliff,
+                vesting_duration_seconds: duration,
+            },
+        );
+        let result = self
+            .market
+            .svm
+            .send_transaction(&[instruction], &[&maker_keypair]);
+        let was_open = self.open.contains_key(&(maker, id));
+
+        if result.is_err() {
+            return false;
+        }
+        assert!(!was_open, "made offer {id} of actor {maker} twice");
+        assert!(offered > 0 && wanted > 0, "accepted a zero amount");
+        assert_eq!(flags & !3, 0, "accepted undefined flags {flags:#x}");
+        assert!(
+            min_fill <= offered,
+            "accepted a minimum fill above the offer"
+        );
+        assert!(
+            0 <= cliff && cliff <= duration,
+            "accepted a vesting cliff of {cliff}s over {duration}s"
+        );
+        self.closed.remove(&(maker, id));
+        self.open.insert(
+            (maker, id),
+            OpenOffer {
+                token_a_remaining: offered,
+                token_b_wanted_amount: wanted,
+                token_b_total: wanted,
+                token_b_paid: 0,
+                vests: duration > 0,
+            },
+        );
+        true
+    }
+
+    fn take(&mut self, taker: usize, maker: usize, id: u64) -> bool {
+        let offer = self.target_offer(maker, id);
+        let taker_keypair = self.actors[taker].insecure_clone();
+        let instruction = instruction::take_offer(
+            &taker_keypair.pubkey(),
+            &taker_keypair.pubkey(),
+            &offer,
+            &self.market.token_program,
+        );
+        let taker_a = self.token_balance(taker, &self.market.token_mint_a);
+        let result = self
+            .market
+            .svm
+            .send_transaction(&[instruction], &[&taker_keypair]);
+
+        let Some(open) = self.open.get(&(maker, id)).cloned() else {
+            assert!(
+                result.is_err(),
+                "took closed or unknown offer {id} of actor {maker}"
+            );
+            return false;
+        };
+        if result.is_err() {
+            return false;
+        }
+        if open.vests {
+            // Token A waits in an escrow for the taker instead.
+            let escrow = find_vesting_escrow_address(&self.offer_address(maker, id), offer.nonce).0;
+            let escrow_vault = find_associated_token_address(
+                &escrow,
+                &self.market.token_mint_a,
+                &self.market.token_program,
+            );
+            assert_eq!(
+                self.token_balance(taker, &self.market.token_mint_a),
+                taker_a
+            );
+            assert_eq!(
+                self.market.svm.token_balance(&escrow_vault),
+                Some(open.token_a_remaining),
+                "escrow of offer {id}"
+            );
+            let escrow = self.market.svm.get_account(&escrow).unwrap();
+            let escrow = VestingEscrow::decode(&escrow.data).unwrap();
+            assert_eq!(escrow.beneficiary, taker_keypair.pubkey());
+            self.escrow_vaults.push(escrow_vault);
+        } else if taker != maker {
+            assert_eq!(
+                self.token_balance(taker, &self.market.token_mint_a),
+                taker_a + open.token_a_remaining
+            );
+        }
+        self.settle(
+            maker,
+            id,
+            offer,
+            open.token_a_remaining,
+            open.token_b_wanted_amount,
+        );
+        true
+    }
+
+    fn fill(&mut self, taker: usize, maker: usize, id: u64, token_a_amount: u64) -> bool {
+        let offer = self.target_offer(maker, id);
+        let taker_keypair = self.actors[taker].insecure_clone();
+        let instruction = instruction::fill_offer(
+            &taker_keypair.pubkey(),
+            &taker_keypair.pubkey(),
+            &offer,
+            &self.market.token_program,
+            token_a_amount,
+        );
+        let maker_b = self.token_balance(maker, &self.market.token_mint_b);
+        let result = self
+            .market
+            .svm
+            .send_transaction(&[instruction], &[&taker_keypair]);
+
+        let Some(open) = self.open.get(&(maker, id)).cloned() else {
+            assert!(
+                result.is_err(),
+                "filled closed or unknown offer {id} of actor {maker}"
+            );
+            return false;
+        };
+        if result.is_err() {
+            return false;
+        }
+        assert!(!open.vests, "filled vesting offer {id} of actor {maker}");
+        assert!(
+            token_a_amount <= open.token_a_remaining,
+            "filled more than the vault holds"
+        );
+        let token_b_paid = if taker == maker {
+            // A self-fill pays the maker from the maker; derive the price
+            // from what is still wanted instead.
+            open.token_b_wanted_amount
+                - self
+                    .on_chain_offer(maker, id)
+                    .map_or(0, |offer| offer.token_b_wanted_amount)
+        } else {
+            self.token_balance(maker, &self.market.token_mint_b) - maker_b
+        };
+        // Never below the pro-rata price of what was taken.
+        assert!(
+            token_b_paid as u128 * open.token_a_remaining as u128
+                >= token_a_amount as u128 * open.token_b_wanted_amount as u128,
+            "fill of {token_a_amount} paid only {token_b_paid}"
+        );
+        self.settle(maker, id, offer, token_a_amount, token_b_paid);
+        true
+    }
+
+    fn cancel(&mut self, caller: usize, maker: usize, id: u64) -> bool {
+        let offer = self.target_offer(maker, id);
+        let caller_keypair = self.actors[caller].insecure_clone();
+        let mut instruction = instruction::cancel_offer(&offer, &self.market.token_program);
+        // Someone else signs in the maker's place, to sweep the vault into
+        // their own account.
+        instruction.accounts[0].pubkey = caller_keypair.pubkey();
+        instruction.accounts[3].pubkey = find_associated_token_address(
+            &caller_keypair.pubkey(),
+            &self.market.token_mint_a,
+            &self.market.token_program,
+        );
+        let offer_address = self.offer_address(maker, id);
+        let escrowed_lamports = self.market.svm.lamports(&offer_address)
+            + self.market.svm.lamports(&self.vault_address(maker, id));
+        let maker_a = self.token_balance(maker, &self.market.token_mint_a);
+        let maker_lamports = self.market.svm.lamports(&self.actors[maker].pubkey());
+        let result = self
+            .market
+            .svm
+            .send_transaction(&[instruction], &[&caller_keypair]);
+
+        let Some(open) = self.open.get(&(maker, id)).cloned() else {
+            assert!(
+                result.is_err(),
+                "cancelled closed or unknown offer {id} of actor {maker}"
+            );
+            return false;
+        };
+        let Ok(meta) = result else {
+            return false;
+        };
+        assert_eq!(
+            caller, maker,
+            "actor {caller} cancelled an offer of actor {maker}"
+        );
+        assert_eq!(
+            self.token_balance(maker, &self.market.token_mint_a),
+            maker_a + open.token_a_remaining,
+            "token A of cancelled offer {id}"
+        );
+        // The maker paid for the accounts, so the rent comes back to them
+        // along with the deposit.
+        assert_eq!(offer.rent_payer, self.actors[maker].pubkey());
+        assert_eq!(
+            self.market.svm.lamports(&self.actors[maker].pubkey()),
+            maker_lamports + escrowed_lamports - meta.fee,
+            "lamports of cancelled offer {id}"
+        );
+        self.open.remove(&(maker, id));
+        self.closed.insert((maker, id), offer);
+        true
+    }
+
+    /// Applies a successful take or fill of `token_a_amount` to the model.
+    fn settle(
+        &mut self,
+        maker: usize,
+        id: u64,
+        offer: Offer,
+        token_a_amount: u64,
+        token_b_paid: u64,
+    ) {
+        let open = self.open.get_mut(&(maker, id)).unwrap();
+        open.token_a_remaining -= token_a_amount;
+        open.token_b_wanted_amount -= token_b_paid;
+        open.token_b_paid += token_b_paid;
+        if open.token_a_remaining == 0 {
+            assert_eq!(
+                open.token_b_paid, open.token_b_total,
+                "maker of offer {id} was paid a different total than asked"
+            );
+            self.open.remove(&(maker, id));
+            self.closed.insert((maker, id), offer);
+        }
+    }
+
+    fn check_invariants(&self) {
+        let ledger = self.ledger();
+        let held = |mint: &Pubkey| -> u64 {
+            self.actors
+                .iter()
+                .map(|actor| {
+                    find_associated_token_address(&actor.pubkey(), mint, &self.market.token_program)
+                })
+                .filter_map(|address| ledger.get(&address))
+                .sum()
+        };
+        let vaulted: u64 = (0..ACTORS)
+            .flat_map(|maker| (0..IDS).map(move |id| (maker, id)))
+            .filter_map(|(maker, id)| ledger.get(&self.vault_address(maker, id)))
+            .sum::<u64>()
+            + self
+                .escrow_vaults
+                .iter()
+                .filter_map(|vault| ledger.get(vault))
+                .sum::<u64>();
+        assert_eq!(
+            held(&self.market.token_mint_a) + vaulted,
+            self.supply_a,
+            "token A created or destroyed"
+        );
+        assert_eq!(
+            held(&self.market.token_mint_b),
+            self.supply_b,
+            "token B created or destroyed"
+        );
+        assert_eq!(
+            self.market.svm.mint_supply(&self.market.token_mint_a),
+            Some(self.supply_a)
+        );
+        assert_eq!(
+            self.market.svm.mint_supply(&self.market.token_mint_b),
+            Some(self.supply_b)
+        );
+
+        for maker in 0..ACTORS {
+            for id in 0..IDS {
+                let offer = self.on_chain_offer(maker, id);
+                let vault = self
+                    .market
+                    .svm
+                    .token_balance(&self.vault_address(maker, id));
+                match self.open.get(&(maker, id)) {
+                    Some(open) => {
+                        assert_eq!(vault, Some(open.token_a_remaining), "vault of offer {id}");
+                        // Token B wanted only runs out with the vault, or the
+                        // rest of it would go for nothing.
+                        assert!(
+                            open.token_b_wanted_amount > 0,
+                            "offer {id} of actor {maker} wants no token B for {} token A",
+                            open.token_a_remaining
+                        );
+                        assert_eq!(
+                            offer.map(|offer| offer.token_b_wanted_amount),
+                            Some(open.token_b_wanted_amount),
+                            "token B still wanted by offer {id}"
+                        );
+                    }
+                    None => {
+                        assert!(
+                            offer.is_none(),
+                            "offer {id} of actor {maker} outlived its tokens"
+                        );
+                        assert!(vault.is_none(), "vault of closed offer {id} left behind");
+                    }
+                }
+            }
+
+            let open_offers = self.open.keys().filter(|(m, _)| *m == maker).count() as u32;
+            let stats = self
+                .market
+                .svm
+                .get_account(&find_maker_stats_address(&self.actors[maker].pubkey()).0)
+                .map(|account| MakerStats::decode(&account.data).unwrap().open_offers);
+            assert_eq!(
+                stats.unwrap_or(0),
+                open_offers,
+                "open offers of actor {maker}"
+            );
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 64,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn offers_keep_tokens_accounted_for(steps in vec(step(), 1..40)) {
+        let mut harness = Harness::new();
+        for step in &steps {
+            harness.run(step);
+        }
+    }
+}
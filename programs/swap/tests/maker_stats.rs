@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use swap::error::ErrorCode;
+use swap::state::maker_stats::{MakerStats, MAKER_STATS_RESERVED_SPACE, MAKER_STATS_VERSION};
+
+/// What `init_if_needed` hands the handler for a brand-new stats account.
+fn new_stats() -> MakerStats {
+    MakerStats {
+        maker: Pubkey::default(),
+        open_offers: 0,
+        bump: 0,
+        version: 0,
+        offers_made: 0,
+        reserved: [0; MAKER_STATS_RESERVED_SPACE],
+    }
+}
+
+#[test]
+fn first_offer_fills_in_the_header() {
+    let maker = Pubkey::new_unique();
+    let mut stats = new_stats();
+
+    stats.open_offer(maker, 253, 5).unwrap();
+
+    assert_eq!(stats.maker, maker);
+    assert_eq!(stats.bump, 253);
+    assert_eq!(stats.version, MAKER_STATS_VERSION);
+    assert_eq!(stats.open_offers, 1);
+}
+
+#[test]
+fn cap_limits_open_offers() {
+    let maker = Pubkey::new_unique();
+    let mut stats = new_stats();
+
+    stats.open_offer(maker, 255, 2).unwrap();
+    stats.open_offer(maker, 255, 2).unwrap();
+    assert_eq!(
+        stats.open_offer(maker, 255, 2).unwrap_err(),
+        ErrorCode::TooManyOpenOffers.into()
+    );
+
+    stats.close_offer(maker, 255);
+    stats.open_offer(maker, 255, 2).unwrap();
+    assert_eq!(stats.open_offers, 2);
+}
+
+#[test]
+fn every_offer_gets_a_new_nonce() {
+    let maker = Pubkey::new_unique();
+    let mut stats = new_stats();
+
+    assert_eq!(stats.open_offer(maker, 255, 0).unwrap(), 1);
+    stats.close_offer(maker, 255);
+    assert_eq!(stats.open_offer(maker, 255, 0).unwrap(), 2);
+    assert_eq!(stats.offers_made, 2);
+}
+
+#[test]
+fn zero_cap_means_unlimited() {
+    let maker = Pubkey::new_unique();
+    let mut stats = new_stats();
+
+    for _ in 0..100 {
+        stats.open_offer(maker, 255, 0).unwrap();
+    }
+    assert_eq!(stats.open_offers, 100);
+}
+
+#[test]
+fn closing_untracked_offers_does_not_underflow() {
+    let maker = Pubkey::new_unique();
+    let mut stats = new_stats();
+
+    stats.close_offer(maker, 254);
+
+    assert_eq!(stats.open_offers, 0);
+    assert_eq!(stats.maker, maker);
+    assert_eq!(stats.version, MAKER_STATS_VERSION);
+}
@@ -0,0 +1,363 @@
+//! `migrate_offer` on raw legacy bytes, and end to end on an in-process SVM.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::{custom, instruction_error, Market, STARTING_BALANCE};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap::error::ErrorCode;
+use swap::instructions::migrate_offer::upgrade_offer_data;
+use swap::state::offer::{Offer, OfferV0, OFFER_RESERVED_SPACE, OFFER_VERSION};
+use swap_client::instruction;
+use swap_client::pda::{find_associated_token_address, find_offer_address};
+use swap_client::state::{Offer as ClientOffer, SwapAccount};
+use swap_client::TOKEN_2022_PROGRAM_ID;
+
+// Raw account data of an offer created before `Offer` was versioned:
+// id 42, maker [1..=32], mint A [0x0a; 32], mint B [0x0b; 32],
+// 1_000_000 token B wanted, bump 254.
+const OFFER_V0_FIXTURE: &[u8] = include_bytes!("fixtures/offer_v0.bin");
+
+fn expected_legacy_offer() -> OfferV0 {
+    let mut maker = [0u8; 32];
+    for (index, byte) in maker.iter_mut().enumerate() {
+        *byte = index as u8 + 1;
+    }
+
+    OfferV0 {
+        id: 42,
+        maker: Pubkey::new_from_array(maker),
+        token_mint_a: Pubkey::new_from_array([0x0a; 32]),
+        token_mint_b: Pubkey::new_from_array([0x0b; 32]),
+        token_b_wanted_amount: 1_000_000,
+        bump: 254,
+    }
+}
+
+/// Mirrors what `migrate_offer` does to the account before rewriting it.
+fn resized_fixture() -> Vec<u8> {
+    let mut data = OFFER_V0_FIXTURE.to_vec();
+    data.resize(Offer::SPACE, 0);
+    data
+}
+
+#[test]
+fn fixture_uses_the_legacy_layout() {
+    assert_eq!(OFFER_V0_FIXTURE.len(), OfferV0::SPACE);
+    assert!(OFFER_V0_FIXTURE.starts_with(Offer::DISCRIMINATOR));
+
+    let legacy = OfferV0::try_from_slice(&OFFER_V0_FIXTURE[8..]).unwrap();
+    assert_eq!(legacy, expected_legacy_offer());
+}
+
+#[test]
+fn versioned_layouts_keep_their_sizes() {
+    // Versions 1 to 3 only carved fields out of the reserved space, so they
+    // share one size. Version 4 appended `rent_payer`.
+    assert_eq!(Offer::V3_SPACE, OfferV0::SPACE + 1 + 64);
+    assert_eq!(Offer::SPACE, Offer::V3_SPACE + 32);
+}
+
+#[test]
+fn legacy_offer_does_not_deserialize_as_current_offer() {
+    assert!(Offer::try_deserialize(&mut &OFFER_V0_FIXTURE[..]).is_err());
+}
+
+#[test]
+fn upgrade_keeps_offer_terms_and_sets_version() {
+    let mut data = resized_fixture();
+    upgrade_offer_data(&mut data).unwrap();
+
+    let offer = Offer::try_deserialize(&mut &data[..]).unwrap();
+    let legacy = expected_legacy_offer();
+    assert_eq!(offer.id, legacy.id);
+    assert_eq!(offer.maker, legacy.maker);
+    assert_eq!(offer.token_mint_a, legacy.token_mint_a);
+    assert_eq!(offer.token_mint_b, legacy.token_mint_b);
+    assert_eq!(offer.token_b_wanted_amount, legacy.token_b_wanted_amount);
+    assert_eq!(offer.bump, legacy.bump);
+    assert_eq!(offer.version, OFFER_VERSION);
+    assert_eq!(offer.flags, 0);
+    assert_eq!(offer.min_fill_amount, 0);
+    assert_eq!(offer.rent_payer, legacy.maker);
+    assert_eq!(offer.reserved, [0; OFFER_RESERVED_SPACE]);
+}
+
+/// `offer` in the version 3 layout, from before `rent_payer`.
+fn v3_layout(offer: &Offer) -> Vec<u8> {
+    let mut data = Vec::new();
+    offer.try_serialize(&mut data).unwrap();
+    let reserved_start = Offer::SPACE - OFFER_RESERVED_SPACE;
+    data.drain(reserved_start - 32..reserved_start);
+    assert_eq!(data.len(), Offer::V3_SPACE);
+    data
+}
+
+/// A version 3 offer resized the way `migrate_offer` does before rewriting
+/// it.
+fn resized_v3_offer() -> (Offer, Vec<u8>) {
+    let offer = Offer {
+        version: 3,
+        flags: 1,
+        min_fill_amount: 10,
+        created_slot: 99,
+        vesting_cliff_seconds: 60,
+        vesting_duration_seconds: 3_600,
+        nonce: 5,
+        ..Offer::from(expected_legacy_offer())
+    };
+    let mut data = v3_layout(&offer);
+    data.resize(Offer::SPACE, 0);
+    (offer, data)
+}
+
+#[test]
+fn upgrade_keeps_v3_terms_and_records_the_maker_as_rent_payer() {
+    let (v3, mut data) = resized_v3_offer();
+    upgrade_offer_data(&mut data).unwrap();
+
+    let expected = Offer {
+        version: OFFER_VERSION,
+        rent_payer: v3.maker,
+        ..v3
+    };
+    let mut expected_data = Vec::new();
+    expected.try_serialize(&mut expected_data).unwrap();
+    assert_eq!(data, expected_data);
+}
+
+#[test]
+fn upgrade_rejects_current_offers() {
+    let (_, mut data) = resized_v3_offer();
+    upgrade_offer_data(&mut data).unwrap();
+
+    assert_eq!(
+        upgrade_offer_data(&mut data).unwrap_err(),
+        ErrorCode::OfferAlreadyMigrated.into()
+    );
+}
+
+#[test]
+fn upgrade_leaves_legacy_bytes_in_place() {
+    let mut data = resized_fixture();
+    upgrade_offer_data(&mut data).unwrap();
+
+    assert_eq!(&data[..OfferV0::SPACE], OFFER_V0_FIXTURE);
+    assert_eq!(data[OfferV0::SPACE], OFFER_VERSION);
+}
+
+#[test]
+fn upgrade_rejects_other_accounts() {
+    let mut data = resized_fixture();
+    data[..8].copy_from_slice(&[0; 8]);
+
+    assert_eq!(
+        upgrade_offer_data(&mut data).unwrap_err(),
+        ErrorCode::NotAnOffer.into()
+    );
+}
+
+#[test]
+fn upgrade_requires_resized_account() {
+    let mut data = OFFER_V0_FIXTURE.to_vec();
+
+    assert_eq!(
+        upgrade_offer_data(&mut data).unwrap_err(),
+        ErrorCode::NotAnOffer.into()
+    );
+}
+
+/// Puts a v0 offer from Alice of `offered` token A for `wanted` token B on
+/// chain, as `make_offer` wrote it before offers were versioned, holding
+/// `lamports`. Returns the offer's address.
+fn place_legacy_offer(market: &mut Market, offered: u64, wanted: u64, lamports: u64) -> Pubkey {
+    let alice = market.alice.pubkey();
+    let (address, bump) = find_offer_address(&alice, 1);
+    let legacy = OfferV0 {
+        id: 1,
+        maker: alice,
+        token_mint_a: market.token_mint_a,
+        token_mint_b: market.token_mint_b,
+        token_b_wanted_amount: wanted,
+        bump,
+    };
+    let mut data = Offer::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), OfferV0::SPACE);
+    place_offer_account(market, address, data, lamports, offered);
+    address
+}
+
+/// Puts a version 3 offer from Alice on chain, as `make_offer` wrote it
+/// before `rent_payer`. Returns the offer's address.
+fn place_v3_offer(market: &mut Market, offered: u64, wanted: u64, lamports: u64) -> Pubkey {
+    let alice = market.alice.pubkey();
+    let (address, bump) = find_offer_address(&alice, 1);
+    let offer = Offer {
+        version: 3,
+        created_slot: 1,
+        nonce: 1,
+        ..Offer::from(OfferV0 {
+            id: 1,
+            maker: alice,
+            token_mint_a: market.token_mint_a,
+            token_mint_b: market.token_mint_b,
+            token_b_wanted_amount: wanted,
+            bump,
+        })
+    };
+    place_offer_account(market, address, v3_layout(&offer), lamports, offered);
+    address
+}
+
+/// Writes `data` to the offer at `address` and funds its vault with
+/// `offered` token A.
+fn place_offer_account(
+    market: &mut Market,
+    address: Pubkey,
+    data: Vec<u8>,
+    lamports: u64,
+    offered: u64,
+) {
+    market.svm.set_account(
+        address,
+        solana_sdk::account::Account {
+            lamports,
+            data,
+            owner: swap::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let token_program = market.token_program;
+    let token_mint_a = market.token_mint_a;
+    let vault = market
+        .svm
+        .create_associated_token_account(&address, &token_mint_a, &token_program);
+    let authority = market.mint_authority.insecure_clone();
+    market
+        .svm
+        .mint_to(&token_program, &token_mint_a, &authority, &vault, offered);
+}
+
+#[test]
+fn migrated_v0_offer_tops_up_rent_and_can_be_taken() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.pubkey();
+    let v0_rent = market.svm.minimum_balance(OfferV0::SPACE);
+    let address = place_legacy_offer(&mut market, 1_000_000, 2_000_000, v0_rent);
+    assert!(market.offer(&alice, 1).unwrap().needs_migration());
+
+    // Anyone may migrate an offer; here a relayer pays the extra rent.
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+    let meta = market
+        .svm
+        .send_transaction(
+            &[instruction::migrate_offer(&relayer.pubkey(), &address)],
+            &[&relayer],
+        )
+        .unwrap();
+
+    let current_rent = market.svm.minimum_balance(Offer::SPACE);
+    let account = market.svm.get_account(&address).unwrap();
+    assert_eq!(account.data.len(), Offer::SPACE);
+    assert_eq!(account.lamports, current_rent);
+    assert_eq!(
+        market.svm.lamports(&relayer.pubkey()),
+        1_000_000_000 - (current_rent - v0_rent) - meta.fee
+    );
+    let offer = ClientOffer::decode(&account.data).unwrap();
+    assert_eq!(offer.version, OFFER_VERSION);
+    assert_eq!(offer.maker, alice);
+    assert_eq!(offer.token_b_wanted_amount, 2_000_000);
+    assert!(!offer.vests());
+
+    assert_eq!(
+        instruction_error(market.svm.send_transaction(
+            &[instruction::migrate_offer(&relayer.pubkey(), &address)],
+            &[&relayer],
+        )),
+        custom(ErrorCode::OfferAlreadyMigrated)
+    );
+
+    market.take_offer(1).unwrap();
+    assert_eq!(market.balance(&bob, &market.token_mint_a), 1_000_000);
+    assert_eq!(market.balance(&alice, &market.token_mint_b), 2_000_000);
+    assert_eq!(
+        market.balance(&bob, &market.token_mint_b),
+        STARTING_BALANCE - 2_000_000
+    );
+    assert!(market.svm.get_account(&address).is_none());
+}
+
+#[test]
+fn migrating_a_well_funded_offer_charges_no_rent() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let current_rent = market.svm.minimum_balance(Offer::SPACE);
+    let address = place_legacy_offer(&mut market, 1_000_000, 2_000_000, current_rent);
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+
+    let meta = market
+        .svm
+        .send_transaction(
+            &[instruction::migrate_offer(&relayer.pubkey(), &address)],
+            &[&relayer],
+        )
+        .unwrap();
+
+    assert_eq!(market.svm.lamports(&address), current_rent);
+    assert_eq!(
+        market.svm.lamports(&relayer.pubkey()),
+        1_000_000_000 - meta.fee
+    );
+}
+
+#[test]
+fn migrated_v3_offer_returns_its_rent_to_the_maker() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let v3_rent = market.svm.minimum_balance(Offer::V3_SPACE);
+    let address = place_v3_offer(&mut market, 1_000_000, 2_000_000, v3_rent);
+    let offer = market.offer(&alice, 1).unwrap();
+    assert!(offer.needs_migration());
+
+    // The program can't read a version 3 offer until it is migrated.
+    let bob = market.bob.insecure_clone();
+    let instruction = market.take_offer_ix(&bob, &offer);
+    assert_eq!(
+        instruction_error(market.svm.send_transaction(&[instruction], &[&bob])),
+        custom(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)
+    );
+
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+    market
+        .svm
+        .send_transaction(
+            &[instruction::migrate_offer(&relayer.pubkey(), &address)],
+            &[&relayer],
+        )
+        .unwrap();
+
+    let offer = market.offer(&alice, 1).unwrap();
+    assert_eq!(offer.version, OFFER_VERSION);
+    assert_eq!(offer.nonce, 1);
+    assert_eq!(offer.rent_payer, alice);
+
+    // Before version 4 all rent went back to the maker, and still does.
+    let offer_lamports = market.svm.lamports(&address);
+    let alice_lamports = market.svm.lamports(&alice);
+    let vault =
+        find_associated_token_address(&address, &market.token_mint_a, &market.token_program);
+    let vault_lamports = market.svm.lamports(&vault);
+    market.take_offer(1).unwrap();
+    assert_eq!(
+        market.svm.lamports(&alice),
+        alice_lamports + offer_lamports + vault_lamports
+    );
+}
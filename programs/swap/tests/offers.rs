@@ -0,0 +1,465 @@
+//! End-to-end `make_offer` / `take_offer` flows against the program running
+//! in an in-process SVM, with the real SPL Token and Token-2022 processors.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use common::{custom, instruction_error, Market, OFFER_DEPOSIT_LAMPORTS, STARTING_BALANCE};
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap_client::instruction::MakeOfferArgs;
+use swap_client::pda::{
+    find_associated_token_address, find_maker_stats_address, find_offer_address,
+};
+use swap_client::state::{MakerStats, SwapAccount};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+const TOKEN_PROGRAMS: [solana_sdk::pubkey::Pubkey; 2] = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
+
+#[test]
+fn make_offer_escrows_token_a() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.pubkey();
+        let lamports_before = market.svm.lamports(&alice);
+
+        market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+
+        let offer = market.offer(&alice, 1).unwrap();
+        assert_eq!(offer.maker, alice);
+        assert_eq!(offer.token_mint_a, market.token_mint_a);
+        assert_eq!(offer.token_mint_b, market.token_mint_b);
+        assert_eq!(offer.token_b_wanted_amount, 2_000_000);
+        assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+        assert_eq!(
+            market.balance(&alice, &market.token_mint_a),
+            STARTING_BALANCE - 1_000_000
+        );
+
+        let offer_address = find_offer_address(&alice, 1).0;
+        let offer_account = market.svm.get_account(&offer_address).unwrap();
+        assert_eq!(
+            offer_account.lamports,
+            market.svm.minimum_balance(offer_account.data.len()) + OFFER_DEPOSIT_LAMPORTS
+        );
+        assert!(market.svm.lamports(&alice) < lamports_before - OFFER_DEPOSIT_LAMPORTS);
+
+        let stats = market
+            .svm
+            .get_account(&find_maker_stats_address(&alice).0)
+            .unwrap();
+        assert_eq!(MakerStats::decode(&stats.data).unwrap().open_offers, 1);
+    }
+}
+
+#[test]
+fn take_offer_swaps_and_refunds_the_maker() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.pubkey();
+        let bob = market.bob.pubkey();
+        market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+        let offer_address = find_offer_address(&alice, 1).0;
+        let vault =
+            find_associated_token_address(&offer_address, &market.token_mint_a, &token_program);
+        let escrowed_lamports = market.svm.lamports(&offer_address) + market.svm.lamports(&vault);
+        let alice_lamports = market.svm.lamports(&alice);
+
+        let meta = market.take_offer(1).unwrap();
+
+        assert_eq!(market.balance(&bob, &market.token_mint_a), 1_000_000);
+        assert_eq!(
+            market.balance(&bob, &market.token_mint_b),
+            STARTING_BALANCE - 2_000_000
+        );
+        assert_eq!(market.balance(&alice, &market.token_mint_b), 2_000_000);
+        assert!(market.offer(&alice, 1).is_none());
+        assert!(market.svm.get_account(&vault).is_none());
+
+        // Offer rent, vault rent and the deposit all go back to Alice; Bob
+        // paid for her new token B account.
+        assert_eq!(
+            market.svm.lamports(&alice),
+            alice_lamports + escrowed_lamports
+        );
+        let stats = market
+            .svm
+            .get_account(&find_maker_stats_address(&alice).0)
+            .unwrap();
+        assert_eq!(MakerStats::decode(&stats.data).unwrap().open_offers, 0);
+        assert_eq!(meta.events.len(), 1, "one OfferFilled event");
+    }
+}
+
+#[test]
+fn take_offer_rejects_a_different_maker() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+    let mallory = Keypair::new();
+    let (mint_b, token_program) = (market.token_mint_b, market.token_program);
+    market.fund(&mallory.pubkey(), &mint_b, 0);
+
+    // Bob's token B would go to Mallory instead of Alice.
+    let offer = market.offer(&alice, 1).unwrap();
+    let bob = market.bob.insecure_clone();
+    let mut instruction = market.take_offer_ix(&bob, &offer);
+    instruction.accounts[2] = AccountMeta::new(mallory.pubkey(), false);
+    instruction.accounts[8] = AccountMeta::new(
+        find_associated_token_address(&mallory.pubkey(), &mint_b, &token_program),
+        false,
+    );
+
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+
+    // The offer PDA is derived from the maker, so the seeds check trips
+    // before `has_one = maker`.
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintSeeds));
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+    assert_eq!(market.balance(&mallory.pubkey(), &mint_b), 0);
+}
+
+#[test]
+fn take_offer_rejects_a_different_mint() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+
+    // Bob tries to pay with a worthless mint of his own.
+    let token_program = market.token_program;
+    let worthless = market
+        .svm
+        .create_mint(&token_program, &market.mint_authority.pubkey(), 6);
+    market.fund(&bob, &worthless, STARTING_BALANCE);
+    let mut offer = market.offer(&alice, 1).unwrap();
+    offer.token_mint_b = worthless;
+    let bob_keypair = market.bob.insecure_clone();
+    let instruction = market.take_offer_ix(&bob_keypair, &offer);
+
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob_keypair]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+    assert_eq!(market.balance(&bob, &worthless), STARTING_BALANCE);
+    assert_eq!(market.balance(&bob, &market.token_mint_a), 0);
+}
+
+#[test]
+fn make_offer_rejects_a_reused_id() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+
+    let error = instruction_error(market.make_offer(1, 5, 5));
+
+    // The system program refuses to create the offer account again.
+    assert_eq!(error, custom(0u32));
+    let offer = market.offer(&alice, 1).unwrap();
+    assert_eq!(offer.token_b_wanted_amount, 2_000_000);
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+}
+
+#[test]
+fn a_taken_offer_id_can_be_reused() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+    market.take_offer(1).unwrap();
+
+    market.make_offer(1, 3, 4).unwrap();
+
+    assert_eq!(market.offer(&alice, 1).unwrap().token_b_wanted_amount, 4);
+    assert_eq!(market.vault_balance(&alice, 1), Some(3));
+}
+
+#[test]
+fn make_offer_rejects_zero_amounts() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+
+    for (offered, wanted) in [(0, 2_000_000), (1_000_000, 0)] {
+        let error = instruction_error(market.make_offer(1, offered, wanted));
+
+        assert_eq!(error, custom(swap::error::ErrorCode::ZeroOfferAmount));
+        assert!(market.offer(&alice, 1).is_none());
+    }
+}
+
+#[test]
+fn make_offer_rejects_more_than_the_maker_holds() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+
+    let error = instruction_error(market.make_offer(1, STARTING_BALANCE + 1, 1));
+
+    // spl-token's `InsufficientFunds`, surfaced through the CPI.
+    assert_eq!(error, custom(1u32));
+    assert!(market.offer(&alice, 1).is_none());
+    assert_eq!(market.vault_balance(&alice, 1), None);
+    assert_eq!(
+        market.balance(&alice, &market.token_mint_a),
+        STARTING_BALANCE
+    );
+}
+
+#[test]
+fn take_offer_rejects_a_taker_who_cannot_pay() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.pubkey();
+    market
+        .make_offer(1, 1_000_000, STARTING_BALANCE + 1)
+        .unwrap();
+
+    let error = instruction_error(market.take_offer(1));
+
+    assert_eq!(error, custom(1u32));
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+    assert_eq!(market.balance(&bob, &market.token_mint_a), 0);
+    assert_eq!(market.balance(&bob, &market.token_mint_b), STARTING_BALANCE);
+}
+
+#[test]
+fn take_offer_requires_the_taker_signature() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+
+    // A relayer pays, but Bob's signature is stripped from the instruction.
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+    let offer = market.offer(&alice, 1).unwrap();
+    let mut instruction = swap_client::instruction::take_offer(
+        &relayer.pubkey(),
+        &market.bob.pubkey(),
+        &offer,
+        &market.token_program,
+    );
+    instruction.accounts[1].is_signer = false;
+
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&relayer]));
+
+    assert_eq!(error, custom(AnchorErrorCode::AccountNotSigner));
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+}
+
+/// Alice offers 1_000_000 token A for 2_000_000 token B as offer `id`, with
+/// `relayer` paying for the accounts.
+fn make_sponsored_offer(market: &mut Market, relayer: &Keypair, id: u64) {
+    let instruction = swap_client::instruction::make_offer(
+        &relayer.pubkey(),
+        &market.alice.pubkey(),
+        &market.token_mint_a,
+        &market.token_mint_b,
+        &market.token_program,
+        &MakeOfferArgs {
+            id,
+            token_a_offered_amount: 1_000_000,
+            token_b_wanted_amount: 2_000_000,
+            ..MakeOfferArgs::default()
+        },
+    );
+    let alice = market.alice.insecure_clone();
+    market
+        .svm
+        .send_transaction(&[instruction], &[relayer, &alice])
+        .unwrap();
+}
+
+#[test]
+fn a_sponsored_offer_takes_the_deposit_from_the_maker() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+    let alice_lamports = market.svm.lamports(&alice);
+
+    make_sponsored_offer(&mut market, &relayer, 1);
+
+    // The relayer covers the fee and the rent; only the deposit, which comes
+    // back to Alice when the offer closes, is hers to put up.
+    assert_eq!(
+        market.svm.lamports(&alice),
+        alice_lamports - OFFER_DEPOSIT_LAMPORTS
+    );
+    let offer_address = find_offer_address(&alice, 1).0;
+    let offer_account = market.svm.get_account(&offer_address).unwrap();
+    assert_eq!(
+        offer_account.lamports,
+        market.svm.minimum_balance(offer_account.data.len()) + OFFER_DEPOSIT_LAMPORTS
+    );
+    assert_eq!(
+        market.offer(&alice, 1).unwrap().rent_payer,
+        relayer.pubkey()
+    );
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Close {
+    Take,
+    Fill,
+    Cancel,
+}
+
+#[test]
+fn closing_a_sponsored_offer_returns_the_rent_to_the_relayer() {
+    for close in [Close::Take, Close::Fill, Close::Cancel] {
+        let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+        let alice = market.alice.insecure_clone();
+        let bob = market.bob.insecure_clone();
+        let relayer = Keypair::new();
+        market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+        make_sponsored_offer(&mut market, &relayer, 1);
+
+        let offer = market.offer(&alice.pubkey(), 1).unwrap();
+        let offer_address = find_offer_address(&alice.pubkey(), 1).0;
+        let vault = find_associated_token_address(
+            &offer_address,
+            &market.token_mint_a,
+            &market.token_program,
+        );
+        let offer_rent = market.svm.minimum_balance(swap::Offer::SPACE);
+        let vault_rent = market.svm.lamports(&vault);
+        let relayer_lamports = market.svm.lamports(&relayer.pubkey());
+        let alice_lamports = market.svm.lamports(&alice.pubkey());
+
+        let alice_fee = match close {
+            Close::Take => {
+                market.take_offer(1).unwrap();
+                0
+            }
+            Close::Fill => {
+                let instruction = swap_client::instruction::fill_offer(
+                    &bob.pubkey(),
+                    &bob.pubkey(),
+                    &offer,
+                    &market.token_program,
+                    1_000_000,
+                );
+                market
+                    .svm
+                    .send_transaction(&[instruction], &[&bob])
+                    .unwrap();
+                0
+            }
+            Close::Cancel => {
+                let instruction =
+                    swap_client::instruction::cancel_offer(&offer, &market.token_program);
+                market
+                    .svm
+                    .send_transaction(&[instruction], &[&alice])
+                    .unwrap()
+                    .fee
+            }
+        };
+
+        assert!(market.offer(&alice.pubkey(), 1).is_none(), "{close:?}");
+        assert!(market.svm.get_account(&vault).is_none(), "{close:?}");
+        assert_eq!(
+            market.svm.lamports(&relayer.pubkey()),
+            relayer_lamports + offer_rent + vault_rent,
+            "{close:?}"
+        );
+        assert_eq!(
+            market.svm.lamports(&alice.pubkey()),
+            alice_lamports + OFFER_DEPOSIT_LAMPORTS - alice_fee,
+            "{close:?}"
+        );
+    }
+}
+
+#[test]
+fn closing_an_offer_checks_its_rent_payer() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.insecure_clone();
+    let bob = market.bob.insecure_clone();
+    let relayer = Keypair::new();
+    market.svm.airdrop(&relayer.pubkey(), 1_000_000_000);
+    make_sponsored_offer(&mut market, &relayer, 1);
+    let offer = market.offer(&alice.pubkey(), 1).unwrap();
+
+    // Bob names himself to collect the rent the relayer put up.
+    let mut instruction = market.take_offer_ix(&bob, &offer);
+    instruction.accounts[3].pubkey = bob.pubkey();
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+
+    // So does Alice when she cancels.
+    let mut instruction = swap_client::instruction::cancel_offer(&offer, &market.token_program);
+    instruction.accounts[1].pubkey = alice.pubkey();
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&alice]));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+
+    assert_eq!(market.vault_balance(&alice.pubkey(), 1), Some(1_000_000));
+}
+
+#[test]
+fn cancel_offer_refunds_the_maker() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.pubkey();
+        market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+        let offer_address = find_offer_address(&alice, 1).0;
+        let vault =
+            find_associated_token_address(&offer_address, &market.token_mint_a, &token_program);
+        let escrowed_lamports = market.svm.lamports(&offer_address) + market.svm.lamports(&vault);
+        let alice_lamports = market.svm.lamports(&alice);
+
+        let offer = market.offer(&alice, 1).unwrap();
+        let alice_keypair = market.alice.insecure_clone();
+        let meta = market
+            .svm
+            .send_transaction(
+                &[swap_client::instruction::cancel_offer(
+                    &offer,
+                    &token_program,
+                )],
+                &[&alice_keypair],
+            )
+            .unwrap();
+
+        assert!(market.offer(&alice, 1).is_none());
+        assert!(market.svm.get_account(&vault).is_none());
+        assert_eq!(
+            market.balance(&alice, &market.token_mint_a),
+            STARTING_BALANCE
+        );
+        // Alice paid for the accounts herself, so the offer rent, the vault
+        // rent and the deposit all go back to her.
+        assert_eq!(
+            market.svm.lamports(&alice),
+            alice_lamports + escrowed_lamports - meta.fee
+        );
+        let stats = market
+            .svm
+            .get_account(&find_maker_stats_address(&alice).0)
+            .unwrap();
+        assert_eq!(MakerStats::decode(&stats.data).unwrap().open_offers, 0);
+        assert_eq!(meta.events.len(), 1, "one OfferCancelled event");
+    }
+}
+
+#[test]
+fn cancel_offer_requires_the_maker() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    market.make_offer(1, 1_000_000, 2_000_000).unwrap();
+
+    // Bob signs in Alice's place to sweep her vault into his own account.
+    // Her offer isn't at his address, so it fails the seeds check.
+    let token_mint_a = market.token_mint_a;
+    market.fund(&market.bob.pubkey(), &token_mint_a, 0);
+    let offer = market.offer(&alice, 1).unwrap();
+    let bob = market.bob.insecure_clone();
+    let mut instruction = swap_client::instruction::cancel_offer(&offer, &market.token_program);
+    instruction.accounts[0].pubkey = bob.pubkey();
+    instruction.accounts[3].pubkey =
+        find_associated_token_address(&bob.pubkey(), &market.token_mint_a, &market.token_program);
+
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintSeeds));
+    assert_eq!(market.vault_balance(&alice, 1), Some(1_000_000));
+}
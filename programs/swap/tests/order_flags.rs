@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use swap::error::ErrorCode;
+use swap::state::offer::{
+    Offer, OFFER_FLAG_ALL_OR_NONE, OFFER_FLAG_POST_ONLY, OFFER_RESERVED_SPACE, OFFER_VERSION,
+};
+
+const CREATED_SLOT: u64 = 100;
+
+fn offer(flags: u8, min_fill_amount: u64, token_b_wanted_amount: u64) -> Offer {
+    Offer {
+        id: 1,
+        maker: Pubkey::new_unique(),
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_b_wanted_amount,
+        bump: 255,
+        version: OFFER_VERSION,
+        flags,
+        min_fill_amount,
+        created_slot: CREATED_SLOT,
+        vesting_cliff_seconds: 0,
+        vesting_duration_seconds: 0,
+        nonce: 1,
+        rent_payer: Pubkey::new_unique(),
+        reserved: [0; OFFER_RESERVED_SPACE],
+    }
+}
+
+#[test]
+fn full_fill_pays_everything_still_wanted() {
+    let offer = offer(0, 0, 3_000);
+
+    assert_eq!(offer.check_fill(1_000, 1_000, CREATED_SLOT).unwrap(), 3_000);
+}
+
+#[test]
+fn partial_fill_rounds_price_up_for_the_maker() {
+    let offer = offer(0, 0, 1_000);
+
+    assert_eq!(offer.check_fill(1, 3, CREATED_SLOT).unwrap(), 334);
+}
+
+#[test]
+fn partial_fill_cannot_pay_off_the_offer_early() {
+    // A unit of A rounds up to the one unit of B wanted, which would leave
+    // the other 99 for nothing.
+    let one_wanted = offer(0, 0, 1);
+    assert_eq!(
+        one_wanted.check_fill(1, 100, CREATED_SLOT).unwrap_err(),
+        ErrorCode::FillLeavesNothingWanted.into()
+    );
+    assert_eq!(one_wanted.check_fill(100, 100, CREATED_SLOT).unwrap(), 1);
+
+    let three_wanted = offer(0, 0, 3);
+    assert_eq!(
+        three_wanted.check_fill(3, 4, CREATED_SLOT).unwrap_err(),
+        ErrorCode::FillLeavesNothingWanted.into()
+    );
+    assert_eq!(three_wanted.check_fill(2, 4, CREATED_SLOT).unwrap(), 2);
+}
+
+#[test]
+fn partial_fill_cannot_exceed_the_vault() {
+    let offer = offer(0, 0, 1_000);
+
+    assert_eq!(
+        offer.check_fill(11, 10, CREATED_SLOT).unwrap_err(),
+        ErrorCode::FillExceedsOffer.into()
+    );
+}
+
+#[test]
+fn fills_must_take_something() {
+    let offer = offer(0, 0, 1_000);
+
+    assert_eq!(
+        offer.check_fill(0, 10, CREATED_SLOT).unwrap_err(),
+        ErrorCode::ZeroFill.into()
+    );
+    // An empty vault is not a full fill.
+    assert_eq!(
+        offer.check_fill(0, 0, CREATED_SLOT).unwrap_err(),
+        ErrorCode::ZeroFill.into()
+    );
+}
+
+#[test]
+fn all_or_none_rejects_partial_fills() {
+    let offer = offer(OFFER_FLAG_ALL_OR_NONE, 0, 1_000);
+
+    assert_eq!(
+        offer.check_fill(5, 10, CREATED_SLOT).unwrap_err(),
+        ErrorCode::AllOrNoneOffer.into()
+    );
+    assert_eq!(offer.check_fill(10, 10, CREATED_SLOT).unwrap(), 1_000);
+}
+
+#[test]
+fn minimum_fill_applies_to_partial_fills_only() {
+    let offer = offer(0, 4, 1_000);
+
+    assert_eq!(
+        offer.check_fill(3, 10, CREATED_SLOT).unwrap_err(),
+        ErrorCode::FillBelowMinimum.into()
+    );
+    assert_eq!(offer.check_fill(4, 10, CREATED_SLOT).unwrap(), 400);
+    // The last few tokens in the vault can always be taken.
+    assert_eq!(offer.check_fill(2, 2, CREATED_SLOT).unwrap(), 1_000);
+}
+
+#[test]
+fn post_only_offer_must_rest_for_a_slot() {
+    let offer = offer(OFFER_FLAG_POST_ONLY, 0, 1_000);
+
+    assert_eq!(
+        offer.check_fill(10, 10, CREATED_SLOT).unwrap_err(),
+        ErrorCode::PostOnlyOfferNotResting.into()
+    );
+    assert_eq!(offer.check_fill(10, 10, CREATED_SLOT + 1).unwrap(), 1_000);
+}
+
+#[test]
+fn series_of_partial_fills_pays_the_full_price() {
+    let mut offer = offer(0, 0, 1_000);
+    let mut token_a_remaining = 7;
+    let mut token_b_paid = 0;
+
+    while token_a_remaining > 0 {
+        let token_b_amount = offer.check_fill(1, token_a_remaining, CREATED_SLOT).unwrap();
+        offer.token_b_wanted_amount -= token_b_amount;
+        token_a_remaining -= 1;
+        token_b_paid += token_b_amount;
+    }
+
+    assert_eq!(token_b_paid, 1_000);
+    assert_eq!(offer.token_b_wanted_amount, 0);
+}
+
+#[test]
+fn offers_must_give_and_want_something() {
+    for (offered, wanted) in [(0, 1_000), (1_000, 0), (0, 0)] {
+        assert_eq!(
+            Offer::check_terms(offered, wanted, 0, 0).unwrap_err(),
+            ErrorCode::ZeroOfferAmount.into()
+        );
+    }
+    Offer::check_terms(1, 1, OFFER_FLAG_ALL_OR_NONE | OFFER_FLAG_POST_ONLY, 1).unwrap();
+}
+
+#[test]
+fn offer_terms_reject_unknown_flags_and_large_minimums() {
+    assert_eq!(
+        Offer::check_terms(1_000, 1_000, 1 << 2, 0).unwrap_err(),
+        ErrorCode::InvalidOfferFlags.into()
+    );
+    assert_eq!(
+        Offer::check_terms(1_000, 1_000, 0, 1_001).unwrap_err(),
+        ErrorCode::MinFillExceedsOffer.into()
+    );
+}
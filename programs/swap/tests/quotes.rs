@@ -0,0 +1,455 @@
+//! The RFQ flow: `request_quote`, `submit_quote`, then `accept_quote`,
+//! `reject_quote`, `withdraw_quote` or `cancel_rfq`, against the program
+//! running in an in-process SVM.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use common::{custom, instruction_error, Market, STARTING_BALANCE};
+use local_svm::{FailedTransaction, TransactionMetadata};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap::error::ErrorCode;
+use swap_client::instruction;
+use swap_client::pda::{find_associated_token_address, find_quote_address, find_rfq_address};
+use swap_client::state::{Quote, Rfq, SwapAccount};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+const TOKEN_PROGRAMS: [Pubkey; 2] = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
+const WANTED: u64 = 1_000_000;
+
+/// Alice asks for [`WANTED`] token B under `id`, paying for the RFQ herself.
+fn request_quote(market: &mut Market, id: u64) -> Result<TransactionMetadata, FailedTransaction> {
+    let alice = market.alice.insecure_clone();
+    let instruction = instruction::request_quote(
+        &alice.pubkey(),
+        &alice.pubkey(),
+        &market.token_mint_a,
+        &market.token_mint_b,
+        &market.token_program,
+        id,
+        WANTED,
+    );
+    market.svm.send_transaction(&[instruction], &[&alice])
+}
+
+fn rfq(market: &Market, id: u64) -> Option<Rfq> {
+    let account = market
+        .svm
+        .get_account(&find_rfq_address(&market.alice.pubkey(), id).0)?;
+    Some(Rfq::decode(&account.data).unwrap())
+}
+
+fn quote_address(rfq: &Rfq, maker: &Pubkey) -> Pubkey {
+    find_quote_address(
+        &find_rfq_address(&rfq.requester, rfq.id).0,
+        rfq.nonce,
+        maker,
+    )
+    .0
+}
+
+fn quote(market: &Market, rfq: &Rfq, maker: &Pubkey) -> Option<Quote> {
+    let account = market.svm.get_account(&quote_address(rfq, maker))?;
+    Some(Quote::decode(&account.data).unwrap())
+}
+
+fn quote_vault_balance(market: &Market, quote: &Pubkey) -> Option<u64> {
+    let vault = find_associated_token_address(quote, &market.token_mint_b, &market.token_program);
+    market.svm.token_balance(&vault)
+}
+
+/// `maker` quotes `price` token A on Alice's RFQ `id`.
+fn submit_quote(
+    market: &mut Market,
+    maker: &Keypair,
+    id: u64,
+    price: u64,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    let rfq = rfq(market, id).expect("rfq exists");
+    let instruction = instruction::submit_quote(
+        &maker.pubkey(),
+        &maker.pubkey(),
+        &rfq,
+        &market.token_program,
+        price,
+    );
+    market.svm.send_transaction(&[instruction], &[maker])
+}
+
+/// Alice accepts `quote` at the price she read from it.
+fn accept_quote(
+    market: &mut Market,
+    rfq: &Rfq,
+    quote: &Quote,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    let alice = market.alice.insecure_clone();
+    let instruction = instruction::accept_quote(
+        &alice.pubkey(),
+        rfq,
+        quote,
+        quote.token_a_wanted_amount,
+        &market.token_program,
+    );
+    market.svm.send_transaction(&[instruction], &[&alice])
+}
+
+fn reject_quote(
+    market: &mut Market,
+    rfq: &Rfq,
+    quote: &Quote,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    let alice = market.alice.insecure_clone();
+    let instruction = instruction::reject_quote(rfq, quote, &market.token_program);
+    market.svm.send_transaction(&[instruction], &[&alice])
+}
+
+fn withdraw_quote(
+    market: &mut Market,
+    maker: &Keypair,
+    quote: &Quote,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    let instruction = instruction::withdraw_quote(quote, &market.token_program);
+    market.svm.send_transaction(&[instruction], &[maker])
+}
+
+/// A second maker holding token B.
+fn carol(market: &mut Market) -> Keypair {
+    let carol = Keypair::new();
+    let token_mint_b = market.token_mint_b;
+    market.fund(&carol.pubkey(), &token_mint_b, STARTING_BALANCE);
+    carol
+}
+
+#[test]
+fn submit_quote_escrows_token_b() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let bob = market.bob.insecure_clone();
+        request_quote(&mut market, 1).unwrap();
+
+        submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+
+        let rfq = rfq(&market, 1).unwrap();
+        assert_eq!(rfq.nonce, 1);
+        let quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+        assert_eq!(quote.maker, bob.pubkey());
+        assert_eq!(quote.token_a_wanted_amount, 400_000);
+        assert_eq!(quote.rfq_nonce, rfq.nonce);
+        assert_eq!(
+            quote_vault_balance(&market, &quote_address(&rfq, &bob.pubkey())),
+            Some(WANTED)
+        );
+        assert_eq!(
+            market.balance(&bob.pubkey(), &market.token_mint_b),
+            STARTING_BALANCE - WANTED
+        );
+    }
+}
+
+#[test]
+fn accept_quote_swaps_and_closes_the_rfq() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.pubkey();
+        let bob = market.bob.insecure_clone();
+        request_quote(&mut market, 1).unwrap();
+        submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+        let rfq = rfq(&market, 1).unwrap();
+        let quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+        let rfq_address = find_rfq_address(&alice, 1).0;
+        let quote_address = quote_address(&rfq, &bob.pubkey());
+        let rfq_rent = market.svm.lamports(&rfq_address);
+        let alice_lamports = market.svm.lamports(&alice);
+        let bob_lamports = market.svm.lamports(&bob.pubkey());
+        let quote_rent = market.svm.lamports(&quote_address)
+            + market.svm.lamports(&find_associated_token_address(
+                &quote_address,
+                &market.token_mint_b,
+                &token_program,
+            ));
+
+        let meta = accept_quote(&mut market, &rfq, &quote).unwrap();
+
+        assert_eq!(market.balance(&alice, &market.token_mint_b), WANTED);
+        assert_eq!(
+            market.balance(&alice, &market.token_mint_a),
+            STARTING_BALANCE - 400_000
+        );
+        assert_eq!(market.balance(&bob.pubkey(), &market.token_mint_a), 400_000);
+        assert!(market.svm.get_account(&rfq_address).is_none());
+        assert!(market.svm.get_account(&quote_address).is_none());
+        assert_eq!(quote_vault_balance(&market, &quote_address), None);
+
+        // Alice gets the RFQ rent back but paid for her token B account and
+        // Bob's token A account. Bob gets the quote and vault rent back.
+        let alice_token_accounts = market.svm.lamports(&find_associated_token_address(
+            &alice,
+            &market.token_mint_b,
+            &token_program,
+        )) + market.svm.lamports(&find_associated_token_address(
+            &bob.pubkey(),
+            &market.token_mint_a,
+            &token_program,
+        ));
+        assert_eq!(
+            market.svm.lamports(&alice),
+            alice_lamports + rfq_rent - alice_token_accounts - meta.fee
+        );
+        assert_eq!(
+            market.svm.lamports(&bob.pubkey()),
+            bob_lamports + quote_rent
+        );
+    }
+}
+
+#[test]
+fn rejected_quotes_are_refunded() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let bob = market.bob.insecure_clone();
+    let carol = carol(&mut market);
+    request_quote(&mut market, 1).unwrap();
+    submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+    submit_quote(&mut market, &carol, 1, 500_000).unwrap();
+    let rfq = rfq(&market, 1).unwrap();
+    let bobs_quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+    let carols_quote = quote(&market, &rfq, &carol.pubkey()).unwrap();
+    accept_quote(&mut market, &rfq, &bobs_quote).unwrap();
+
+    // With the RFQ closed, Carol's quote can't be accepted any more.
+    assert_eq!(
+        instruction_error(accept_quote(&mut market, &rfq, &carols_quote)),
+        custom(AnchorErrorCode::AccountNotInitialized)
+    );
+
+    let carols_address = quote_address(&rfq, &carol.pubkey());
+    let escrowed_lamports = market.svm.lamports(&carols_address)
+        + market.svm.lamports(&find_associated_token_address(
+            &carols_address,
+            &market.token_mint_b,
+            &market.token_program,
+        ));
+    let carol_lamports = market.svm.lamports(&carol.pubkey());
+
+    let meta = withdraw_quote(&mut market, &carol, &carols_quote).unwrap();
+
+    assert_eq!(
+        market.balance(&carol.pubkey(), &market.token_mint_b),
+        STARTING_BALANCE
+    );
+    assert!(market.svm.get_account(&carols_address).is_none());
+    assert_eq!(
+        market.svm.lamports(&carol.pubkey()),
+        carol_lamports + escrowed_lamports - meta.fee
+    );
+}
+
+#[test]
+fn accept_quote_refuses_a_price_above_the_maximum() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.insecure_clone();
+    request_quote(&mut market, 1).unwrap();
+    submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+    let rfq = rfq(&market, 1).unwrap();
+    let seen_quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+
+    // Bob swaps in a dearer quote at the same address before Alice accepts.
+    withdraw_quote(&mut market, &bob, &seen_quote).unwrap();
+    submit_quote(&mut market, &bob, 1, 900_000).unwrap();
+
+    let error = instruction_error(accept_quote(&mut market, &rfq, &seen_quote));
+
+    assert_eq!(error, custom(ErrorCode::QuoteAboveMaximum));
+    assert_eq!(
+        market.balance(&alice, &market.token_mint_a),
+        STARTING_BALANCE
+    );
+    assert_eq!(market.balance(&alice, &market.token_mint_b), 0);
+
+    let dearer_quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+    accept_quote(&mut market, &rfq, &dearer_quote).unwrap();
+    assert_eq!(market.balance(&bob.pubkey(), &market.token_mint_a), 900_000);
+}
+
+#[test]
+fn reject_quote_refunds_the_maker_and_keeps_the_rfq_open() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.pubkey();
+        let bob = market.bob.insecure_clone();
+        let carol = carol(&mut market);
+        request_quote(&mut market, 1).unwrap();
+        submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+        submit_quote(&mut market, &carol, 1, 500_000).unwrap();
+        let rfq = rfq(&market, 1).unwrap();
+        let bobs_quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+        let carols_quote = quote(&market, &rfq, &carol.pubkey()).unwrap();
+        let bobs_address = quote_address(&rfq, &bob.pubkey());
+        let escrowed_lamports = market.svm.lamports(&bobs_address)
+            + market.svm.lamports(&find_associated_token_address(
+                &bobs_address,
+                &market.token_mint_b,
+                &token_program,
+            ));
+        let bob_lamports = market.svm.lamports(&bob.pubkey());
+
+        reject_quote(&mut market, &rfq, &bobs_quote).unwrap();
+
+        assert_eq!(
+            market.balance(&bob.pubkey(), &market.token_mint_b),
+            STARTING_BALANCE
+        );
+        assert!(market.svm.get_account(&bobs_address).is_none());
+        assert_eq!(quote_vault_balance(&market, &bobs_address), None);
+        assert_eq!(
+            market.svm.lamports(&bob.pubkey()),
+            bob_lamports + escrowed_lamports
+        );
+        assert!(market
+            .svm
+            .get_account(&find_rfq_address(&alice, 1).0)
+            .is_some());
+
+        accept_quote(&mut market, &rfq, &carols_quote).unwrap();
+        assert_eq!(market.balance(&alice, &market.token_mint_b), WANTED);
+    }
+}
+
+#[test]
+fn reject_quote_requires_the_requester() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let bob = market.bob.insecure_clone();
+    request_quote(&mut market, 1).unwrap();
+    submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+    let rfq = rfq(&market, 1).unwrap();
+    let quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+
+    let mut instruction = instruction::reject_quote(&rfq, &quote, &market.token_program);
+    instruction.accounts[0].pubkey = bob.pubkey();
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintSeeds));
+    assert_eq!(
+        quote_vault_balance(&market, &quote_address(&rfq, &bob.pubkey())),
+        Some(WANTED)
+    );
+}
+
+#[test]
+fn cancel_rfq_leaves_quotes_to_be_withdrawn() {
+    for token_program in TOKEN_PROGRAMS {
+        let mut market = Market::new(token_program);
+        let alice = market.alice.insecure_clone();
+        let bob = market.bob.insecure_clone();
+        request_quote(&mut market, 1).unwrap();
+        submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+        let rfq = rfq(&market, 1).unwrap();
+        let quote = quote(&market, &rfq, &bob.pubkey()).unwrap();
+        let rfq_address = find_rfq_address(&alice.pubkey(), 1).0;
+        let rfq_rent = market.svm.lamports(&rfq_address);
+        let alice_lamports = market.svm.lamports(&alice.pubkey());
+
+        let meta = market
+            .svm
+            .send_transaction(&[instruction::cancel_rfq(&rfq)], &[&alice])
+            .unwrap();
+
+        assert!(market.svm.get_account(&rfq_address).is_none());
+        assert_eq!(
+            market.svm.lamports(&alice.pubkey()),
+            alice_lamports + rfq_rent - meta.fee
+        );
+        assert_eq!(
+            instruction_error(accept_quote(&mut market, &rfq, &quote)),
+            custom(AnchorErrorCode::AccountNotInitialized)
+        );
+
+        withdraw_quote(&mut market, &bob, &quote).unwrap();
+        assert_eq!(
+            market.balance(&bob.pubkey(), &market.token_mint_b),
+            STARTING_BALANCE
+        );
+        assert!(market
+            .svm
+            .get_account(&quote_address(&rfq, &bob.pubkey()))
+            .is_none());
+    }
+}
+
+#[test]
+fn cancel_rfq_requires_the_requester() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.insecure_clone();
+    request_quote(&mut market, 1).unwrap();
+    let rfq = rfq(&market, 1).unwrap();
+
+    let mut instruction = instruction::cancel_rfq(&rfq);
+    instruction.accounts[0].pubkey = bob.pubkey();
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintSeeds));
+    assert!(market
+        .svm
+        .get_account(&find_rfq_address(&alice, 1).0)
+        .is_some());
+}
+
+#[test]
+fn quotes_on_a_cancelled_rfq_do_not_carry_over_to_a_reused_id() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.insecure_clone();
+    let bob = market.bob.insecure_clone();
+    request_quote(&mut market, 1).unwrap();
+    submit_quote(&mut market, &bob, 1, 400_000).unwrap();
+    let old_rfq = rfq(&market, 1).unwrap();
+    let stale_quote = quote(&market, &old_rfq, &bob.pubkey()).unwrap();
+    market
+        .svm
+        .send_transaction(&[instruction::cancel_rfq(&old_rfq)], &[&alice])
+        .unwrap();
+
+    // Same slot, same id, same address: only the nonce differs.
+    request_quote(&mut market, 1).unwrap();
+    let new_rfq = rfq(&market, 1).unwrap();
+    assert_eq!(new_rfq.nonce, old_rfq.nonce + 1);
+
+    // Naming the stale quote on the new RFQ fails the quote's seeds.
+    let mut instruction = instruction::accept_quote(
+        &alice.pubkey(),
+        &new_rfq,
+        &stale_quote,
+        stale_quote.token_a_wanted_amount,
+        &market.token_program,
+    );
+    let stale_address = quote_address(&old_rfq, &bob.pubkey());
+    let stale_vault =
+        find_associated_token_address(&stale_address, &market.token_mint_b, &market.token_program);
+    instruction.accounts[9].pubkey = stale_address;
+    instruction.accounts[10].pubkey = stale_vault;
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&alice]));
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintSeeds));
+    assert_eq!(
+        market.balance(&alice.pubkey(), &market.token_mint_a),
+        STARTING_BALANCE
+    );
+
+    // Bob can quote the new RFQ while his old quote still waits to be
+    // withdrawn, and each settles on its own.
+    submit_quote(&mut market, &bob, 1, 450_000).unwrap();
+    let fresh_quote = quote(&market, &new_rfq, &bob.pubkey()).unwrap();
+    accept_quote(&mut market, &new_rfq, &fresh_quote).unwrap();
+    withdraw_quote(&mut market, &bob, &stale_quote).unwrap();
+
+    assert_eq!(market.balance(&bob.pubkey(), &market.token_mint_a), 450_000);
+    assert_eq!(
+        market.balance(&bob.pubkey(), &market.token_mint_b),
+        STARTING_BALANCE - WANTED
+    );
+    assert_eq!(
+        market.balance(&alice.pubkey(), &market.token_mint_b),
+        WANTED
+    );
+}
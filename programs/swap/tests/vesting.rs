@@ -0,0 +1,264 @@
+//! Vesting offers: `take_offer` parks token A in a vesting escrow and the
+//! taker releases it over time with `claim_vested`.
+
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use common::{custom, instruction_error, Market, STARTING_BALANCE};
+use local_svm::{FailedTransaction, TransactionMetadata};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use swap::error::ErrorCode;
+use swap_client::instruction::{self, MakeOfferArgs};
+use swap_client::pda::{
+    find_associated_token_address, find_offer_address, find_vesting_escrow_address,
+};
+use swap_client::state::{Offer, SwapAccount, VestingEscrow};
+use swap_client::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+const START: i64 = 1_700_000_000;
+const CLIFF: i64 = 100;
+const DURATION: i64 = 1_000;
+const OFFERED: u64 = 1_000_000;
+const WANTED: u64 = 2_000_000;
+
+fn make_vesting_offer(
+    market: &mut Market,
+    id: u64,
+    vesting_cliff_seconds: i64,
+    vesting_duration_seconds: i64,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    let alice = market.alice.insecure_clone();
+    let instruction = market.make_offer_ix(
+        &alice,
+        &MakeOfferArgs {
+            id,
+            token_a_offered_amount: OFFERED,
+            token_b_wanted_amount: WANTED,
+            vesting_cliff_seconds,
+            vesting_duration_seconds,
+            ..MakeOfferArgs::default()
+        },
+    );
+    market.svm.send_transaction(&[instruction], &[&alice])
+}
+
+/// Makes a vesting offer and has Bob take it at [`START`].
+fn taken_vesting_offer(token_program: Pubkey, cliff: i64, duration: i64) -> (Market, Pubkey) {
+    let mut market = Market::new(token_program);
+    market.svm.set_unix_timestamp(START);
+    make_vesting_offer(&mut market, 1, cliff, duration).unwrap();
+    let alice = market.alice.pubkey();
+    let offer = market.offer(&alice, 1).unwrap();
+    market.take_offer(1).unwrap();
+    let escrow = find_vesting_escrow_address(&find_offer_address(&alice, 1).0, offer.nonce).0;
+    (market, escrow)
+}
+
+fn escrow(market: &Market, address: &Pubkey) -> Option<VestingEscrow> {
+    let account = market.svm.get_account(address)?;
+    Some(VestingEscrow::decode(&account.data).unwrap())
+}
+
+fn escrow_vault_balance(market: &Market, address: &Pubkey) -> Option<u64> {
+    market.svm.token_balance(&find_associated_token_address(
+        address,
+        &market.token_mint_a,
+        &market.token_program,
+    ))
+}
+
+fn claim_at(
+    market: &mut Market,
+    address: &Pubkey,
+    now: i64,
+) -> Result<TransactionMetadata, FailedTransaction> {
+    market.svm.set_unix_timestamp(now);
+    let escrow = escrow(market, address).expect("escrow exists");
+    let bob = market.bob.insecure_clone();
+    let instruction = instruction::claim_vested(&escrow, &market.token_program);
+    market.svm.send_transaction(&[instruction], &[&bob])
+}
+
+#[test]
+fn take_offer_escrows_token_a_and_pays_the_maker() {
+    for token_program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let (market, address) = taken_vesting_offer(token_program, CLIFF, DURATION);
+        let alice = market.alice.pubkey();
+        let bob = market.bob.pubkey();
+
+        assert_eq!(market.balance(&alice, &market.token_mint_b), WANTED);
+        assert_eq!(market.balance(&bob, &market.token_mint_a), 0);
+        assert_eq!(escrow_vault_balance(&market, &address), Some(OFFERED));
+        assert!(market.offer(&alice, 1).is_none());
+        assert_eq!(market.vault_balance(&alice, 1), None);
+
+        let escrow = escrow(&market, &address).unwrap();
+        assert_eq!(escrow.beneficiary, bob);
+        assert_eq!(escrow.token_mint, market.token_mint_a);
+        assert_eq!(escrow.total_amount, OFFERED);
+        assert_eq!(escrow.claimed_amount, 0);
+        assert_eq!(escrow.start_timestamp, START);
+        assert_eq!(escrow.cliff_seconds, CLIFF);
+        assert_eq!(escrow.duration_seconds, DURATION);
+    }
+}
+
+#[test]
+fn claims_unlock_linearly_after_the_cliff() {
+    let (mut market, address) = taken_vesting_offer(TOKEN_2022_PROGRAM_ID, CLIFF, DURATION);
+    let bob = market.bob.pubkey();
+    let mint_a = market.token_mint_a;
+
+    let error = instruction_error(claim_at(&mut market, &address, START + CLIFF - 1));
+    assert_eq!(error, custom(ErrorCode::NothingToClaim));
+
+    claim_at(&mut market, &address, START + CLIFF).unwrap();
+    assert_eq!(market.balance(&bob, &mint_a), OFFERED / 10);
+
+    // Nothing new has vested within the same second.
+    let error = instruction_error(claim_at(&mut market, &address, START + CLIFF));
+    assert_eq!(error, custom(ErrorCode::NothingToClaim));
+
+    claim_at(&mut market, &address, START + 333).unwrap();
+    assert_eq!(market.balance(&bob, &mint_a), 333_000);
+    assert_eq!(escrow(&market, &address).unwrap().claimed_amount, 333_000);
+    assert_eq!(escrow_vault_balance(&market, &address), Some(OFFERED - 333_000));
+}
+
+#[test]
+fn final_claim_closes_the_escrow() {
+    let (mut market, address) = taken_vesting_offer(TOKEN_PROGRAM_ID, CLIFF, DURATION);
+    let bob = market.bob.pubkey();
+    let mint_a = market.token_mint_a;
+    let vault = find_associated_token_address(&address, &mint_a, &market.token_program);
+    claim_at(&mut market, &address, START + 500).unwrap();
+    let escrowed_lamports = market.svm.lamports(&address) + market.svm.lamports(&vault);
+    let bob_lamports = market.svm.lamports(&bob);
+
+    let meta = claim_at(&mut market, &address, START + DURATION + 1).unwrap();
+
+    assert_eq!(market.balance(&bob, &mint_a), OFFERED);
+    assert!(market.svm.get_account(&address).is_none());
+    assert!(market.svm.get_account(&vault).is_none());
+    assert_eq!(
+        market.svm.lamports(&bob),
+        bob_lamports + escrowed_lamports - meta.fee
+    );
+}
+
+#[test]
+fn cliff_equal_to_duration_releases_everything_at_once() {
+    let (mut market, address) = taken_vesting_offer(TOKEN_2022_PROGRAM_ID, DURATION, DURATION);
+    let bob = market.bob.pubkey();
+    let mint_a = market.token_mint_a;
+
+    let error = instruction_error(claim_at(&mut market, &address, START + DURATION - 1));
+    assert_eq!(error, custom(ErrorCode::NothingToClaim));
+
+    claim_at(&mut market, &address, START + DURATION).unwrap();
+    assert_eq!(market.balance(&bob, &mint_a), OFFERED);
+    assert!(market.svm.get_account(&address).is_none());
+}
+
+#[test]
+fn only_the_beneficiary_can_claim() {
+    let (mut market, address) = taken_vesting_offer(TOKEN_2022_PROGRAM_ID, CLIFF, DURATION);
+    market.svm.set_unix_timestamp(START + DURATION);
+    let mallory = Keypair::new();
+    market.svm.airdrop(&mallory.pubkey(), 1_000_000_000);
+    let escrow = VestingEscrow {
+        beneficiary: mallory.pubkey(),
+        ..escrow(&market, &address).unwrap()
+    };
+
+    let instruction = instruction::claim_vested(&escrow, &market.token_program);
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&mallory]));
+
+    assert_eq!(error, custom(AnchorErrorCode::ConstraintHasOne));
+    assert_eq!(escrow_vault_balance(&market, &address), Some(OFFERED));
+}
+
+#[test]
+fn make_offer_rejects_invalid_schedules() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+
+    for (cliff, duration) in [(DURATION + 1, DURATION), (-1, DURATION), (CLIFF, 0)] {
+        let error = instruction_error(make_vesting_offer(&mut market, 1, cliff, duration));
+        assert_eq!(error, custom(ErrorCode::InvalidVestingSchedule));
+    }
+    assert_eq!(
+        market.balance(&market.alice.pubkey(), &market.token_mint_a),
+        STARTING_BALANCE
+    );
+}
+
+#[test]
+fn fill_offer_rejects_vesting_offers() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    make_vesting_offer(&mut market, 1, CLIFF, DURATION).unwrap();
+    let offer = market.offer(&market.alice.pubkey(), 1).unwrap();
+    let bob = market.bob.insecure_clone();
+
+    let instruction = instruction::fill_offer(
+        &bob.pubkey(),
+        &bob.pubkey(),
+        &offer,
+        &market.token_program,
+        OFFERED / 2,
+    );
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+
+    assert_eq!(error, custom(ErrorCode::VestingOfferNotFillable));
+    assert_eq!(market.vault_balance(&offer.maker, 1), Some(OFFERED));
+}
+
+#[test]
+fn take_offer_requires_escrow_accounts_only_for_vesting_offers() {
+    let mut market = Market::new(TOKEN_2022_PROGRAM_ID);
+    let alice = market.alice.pubkey();
+    let bob = market.bob.insecure_clone();
+    make_vesting_offer(&mut market, 1, CLIFF, DURATION).unwrap();
+    market.make_offer(2, OFFERED, WANTED).unwrap();
+
+    // Without the escrow, token A would go straight to Bob.
+    let vesting = market.offer(&alice, 1).unwrap();
+    let as_plain = Offer {
+        vesting_duration_seconds: 0,
+        ..vesting.clone()
+    };
+    let instruction = market.take_offer_ix(&bob, &as_plain);
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+    assert_eq!(error, custom(ErrorCode::MissingVestingEscrow));
+
+    // The client only adds escrow accounts for offers that vest.
+    let plain = market.offer(&alice, 2).unwrap();
+    let as_vesting = Offer {
+        vesting_duration_seconds: DURATION,
+        ..plain.clone()
+    };
+    let instruction = market.take_offer_ix(&bob, &as_vesting);
+    let error = instruction_error(market.svm.send_transaction(&[instruction], &[&bob]));
+    assert_eq!(error, custom(ErrorCode::UnexpectedVestingEscrow));
+
+    assert_eq!(market.vault_balance(&alice, 1), Some(OFFERED));
+    assert_eq!(market.vault_balance(&alice, 2), Some(OFFERED));
+}
+
+#[test]
+fn reused_offer_id_gets_a_fresh_escrow() {
+    // The id is made again in the same slot, so only the nonce tells the
+    // two offers apart.
+    let (mut market, first) = taken_vesting_offer(TOKEN_2022_PROGRAM_ID, CLIFF, DURATION);
+    let alice = market.alice.pubkey();
+    make_vesting_offer(&mut market, 1, CLIFF, DURATION).unwrap();
+    let offer = market.offer(&alice, 1).unwrap();
+
+    market.take_offer(1).unwrap();
+
+    let second = find_vesting_escrow_address(&find_offer_address(&alice, 1).0, offer.nonce).0;
+    assert_ne!(first, second);
+    assert_eq!(escrow_vault_balance(&market, &first), Some(OFFERED));
+    assert_eq!(escrow_vault_balance(&market, &second), Some(OFFERED));
+}
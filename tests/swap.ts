@@ -20,6 +20,13 @@ const TOKEN_PROGRAM = TOKEN_2022_PROGRAM_ID;
 
 const SECONDS = 1000;
 
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+const MAX_OPEN_OFFERS_PER_MAKER = 10;
+const OFFER_DEPOSIT_LAMPORTS = new BN(0.01 * LAMPORTS_PER_SOL);
+
 // Tests must complete within half this time otherwise
 // they are marked as slow. Since Anchor involves a little
 // network IO, these tests usually take about 15 seconds.
@@ -42,8 +49,13 @@ describe("swap", () => {
 
   const program = anchor.workspace.Swap as Program<Swap>;
 
-  // We're going to reuse these accounts across multiple tests
+  // We're going to reuse these accounts across multiple tests.
+  // The provider wallet sponsors rent and fees, so Alice and Bob only sign
+  // for their own token movements. It is recorded as each offer's rent
+  // payer and gets the rent back when the offer closes.
   const accounts: Record<string, PublicKey> = {
+    payer: payer.publicKey,
+    rentPayer: payer.publicKey,
     tokenProgram: TOKEN_PROGRAM,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
@@ -97,7 +109,31 @@ describe("swap", () => {
       const bobTokenAccountA = tokenAccounts[1][0];
       const bobTokenAccountB = tokenAccounts[1][1];
 
+      // Offers can't be made until the program config exists. The provider
+      // wallet deployed the program, so it is the upgrade authority.
+      const config = PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+      )[0];
+      const programData = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+      )[0];
+
+      await program.methods
+        .initialize(MAX_OPEN_OFFERS_PER_MAKER, OFFER_DEPOSIT_LAMPORTS)
+        .accounts({
+          payer: payer.publicKey,
+          admin: payer.publicKey,
+          config,
+          program: program.programId,
+          programData,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
       // Save the accounts for later use
+      accounts.config = config;
       accounts.maker = alice.publicKey;
       accounts.taker = bob.publicKey;
       accounts.tokenMintA = tokenMintA.publicKey;
@@ -132,7 +168,7 @@ describe("swap", () => {
     accounts.vault = vault;
 
     const txSig = await program.methods
-      .makeOffer(offerId, tokenAOfferedAmount, tokenBWantedAmount)
+      .makeOffer(offerId, tokenAOfferedAmount, tokenBWantedAmount, 0, new BN(0), new BN(0), new BN(0))
       .accounts({ ...accounts })
       .signers([alice])
       .rpc();